@@ -0,0 +1,129 @@
+//! Parsing benchmarks
+//!
+//! Run with `cargo bench`, or `cargo bench -- warm` to run only the cases
+//! whose name contains `warm`. Each case reports the median time per
+//! iteration over several samples. `list/memory` compares the bytes
+//! allocated for the list with `List::memory_usage`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    env,
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use dns_name::List;
+
+const LIST: &str = include_str!("../suffix-list.txt");
+const HOSTNAMES: &str = include_str!("fixtures/hostnames.txt");
+
+/// The system allocator, counting the bytes currently allocated
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Times `f`, reporting the median of `SAMPLES` samples of about
+/// `SAMPLE_TIME` each
+fn bench<T>(filter: Option<&str>, name: &str, mut f: impl FnMut() -> T) {
+    const SAMPLES: usize = 11;
+    const SAMPLE_TIME: Duration = Duration::from_millis(100);
+
+    if filter.is_some_and(|filter| !name.contains(filter)) {
+        return;
+    }
+
+    // find how many iterations fill a sample
+    let mut iters = 1u32;
+    loop {
+        let start = Instant::now();
+        (0..iters).for_each(|_| drop(black_box(f())));
+        if start.elapsed() >= SAMPLE_TIME / 10 || iters >= 1 << 30 {
+            break;
+        }
+        iters *= 2;
+    }
+    let iters = iters * 10;
+
+    let mut samples = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            (0..iters).for_each(|_| drop(black_box(f())));
+            start.elapsed() / iters
+        })
+        .collect::<Vec<_>>();
+    samples.sort();
+    println!("{name:<32} {:>12?}/iter", samples[SAMPLES / 2]);
+}
+
+fn main() {
+    // cargo passes `--bench`; anything else is a filter
+    let filter = env::args().skip(1).find(|arg| !arg.starts_with("--"));
+    let filter = filter.as_deref();
+
+    bench(filter, "list/load", || LIST.parse::<List>().unwrap());
+
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let list = LIST.parse::<List>().unwrap();
+    if filter.is_none_or(|filter| "list/memory".contains(filter)) {
+        let allocated = ALLOCATED.load(Ordering::Relaxed) - before;
+        let estimate = list.memory_usage();
+        println!(
+            "{:<32} {allocated:>12} bytes, estimated {estimate}",
+            "list/memory"
+        );
+    }
+    let hostnames = HOSTNAMES.lines().collect::<Vec<_>>();
+
+    bench(filter, "lookup/cold", || {
+        // a fresh list each time, so nothing is warm in cache
+        let list = LIST.parse::<List>().unwrap();
+        list.parse_dns_name("www.example.co.uk").unwrap()
+    });
+    bench(filter, "lookup/warm", || {
+        list.parse_dns_name("www.example.co.uk").unwrap()
+    });
+    bench(filter, "lookup/uppercase", || {
+        list.parse_dns_name("WWW.EXAMPLE.CO.UK").unwrap()
+    });
+    bench(filter, "lookup/wildcard", || {
+        list.parse_dns_name("www.example.x.kobe.jp").unwrap()
+    });
+    bench(filter, "lookup/exception", || {
+        list.parse_dns_name("www.city.kobe.jp").unwrap()
+    });
+    bench(filter, "lookup/unlisted", || {
+        list.parse_dns_name("www.example.nope").unwrap()
+    });
+    bench(filter, "lookup/suffix-match", || {
+        list.longest_suffix_match("www.example.co.uk").unwrap()
+    });
+
+    bench(filter, "batch/loop", || {
+        hostnames
+            .iter()
+            .map(|name| list.parse_dns_name(name))
+            .collect::<Vec<_>>()
+    });
+    bench(filter, "batch/parse_many", || {
+        list.parse_many(hostnames.iter().copied())
+    });
+    bench(filter, "batch/par_parse_many", || {
+        list.par_parse_many(&hostnames)
+    });
+}
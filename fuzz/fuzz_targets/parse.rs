@@ -0,0 +1,40 @@
+//! Run with `cargo +nightly fuzz run parse` from the crate root
+
+#![no_main]
+
+use std::sync::OnceLock;
+
+use dns_name::{DnsName, List, ParseOptions, ValidationProfile};
+use libfuzzer_sys::fuzz_target;
+
+fn list() -> &'static List {
+    static LIST: OnceLock<List> = OnceLock::new();
+    LIST.get_or_init(|| List::from_reader(&include_bytes!("../../suffix-list.txt")[..]).unwrap())
+}
+
+fuzz_target!(|data: &[u8]| {
+    let list = list();
+    let _ = DnsName::from_wire_message(data, 0, list);
+    let Ok(name) = list.parse_dns_name_bytes(data) else {
+        return;
+    };
+
+    let strict = ParseOptions::new()
+        .strict_lengths(true)
+        .profile(ValidationProfile::Hostname);
+    let _ = list.parse_dns_name_with(name.name(), &strict);
+
+    let full = name.name();
+    for part in [name.suffix(), name.root()].into_iter().flatten() {
+        assert!(full.ends_with(part));
+    }
+    let _ = (
+        name.registrable(),
+        name.subdomain(),
+        name.organizational_domain(),
+    );
+    let _ = (name.to_escaped(), name.parent(), name.ancestors().count());
+    if let Ok(wire) = name.to_wire() {
+        let _ = DnsName::from_wire(&wire, list);
+    }
+});
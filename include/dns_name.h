@@ -0,0 +1,41 @@
+/*
+ * C interface to the dns_name crate, enabled by the `ffi` feature.
+ *
+ * Lists and names are opaque and owned by the caller. Functions returning
+ * pointers return NULL on failure. Accessors copy into `buf` snprintf style:
+ * at most `len - 1` bytes plus a terminating NUL are written, and the full
+ * length of the part is returned, or -1 if the name has no such part.
+ *
+ * Written by hand; keep in sync with src/ffi.rs. Lengths are `size_t` and
+ * results `ptrdiff_t`, the C types of Rust's `usize` and `isize`.
+ */
+
+#ifndef DNS_NAME_H
+#define DNS_NAME_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct List List;
+typedef struct DnsName DnsName;
+
+List *dns_name_list_load(const char *path);
+List *dns_name_list_from_rules(const char *rules);
+void dns_name_list_free(List *list);
+
+DnsName *dns_name_parse(const List *list, const char *name);
+void dns_name_free(DnsName *name);
+
+ptrdiff_t dns_name_name(const DnsName *name, char *buf, size_t len);
+ptrdiff_t dns_name_root(const DnsName *name, char *buf, size_t len);
+ptrdiff_t dns_name_suffix(const DnsName *name, char *buf, size_t len);
+ptrdiff_t dns_name_registrable(const DnsName *name, char *buf, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* DNS_NAME_H */
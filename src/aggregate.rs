@@ -0,0 +1,96 @@
+//! Collapsing hostnames into the organizations that registered them
+//!
+//! Names are keyed by `DnsName::org_key`: the root domain (eTLD+1) without
+//! the trailing dot. Names without a root domain, such as public suffixes,
+//! are their own key. Names that fail to parse are skipped; use
+//! `DnsNameIterExt::parse_dns_names` to see the errors.
+//!
+//! ```rust
+//! # use dns_name::{aggregate, List};
+//! let list = List::from_path("suffix-list.txt").unwrap();
+//! let log = ["www.example.com", "mail.Example.com.", "example.co.uk"];
+//!
+//! let groups = aggregate::by_registrable(log, &list);
+//! assert_eq!(groups["example.com"].len(), 2);
+//!
+//! let counts = aggregate::count_by_registrable(log, &list);
+//! assert_eq!(counts["example.co.uk"], 1);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{DnsName, DnsNameIterExt, List};
+
+/// Buckets names by root domain
+pub fn by_registrable<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+    list: &List,
+) -> HashMap<String, Vec<DnsName>> {
+    group(names.into_iter().parse_dns_names(list).flatten())
+}
+
+/// Buckets parsed names by root domain
+pub(crate) fn group(names: impl Iterator<Item = DnsName>) -> HashMap<String, Vec<DnsName>> {
+    let mut groups = HashMap::<String, Vec<DnsName>>::new();
+    for name in names {
+        match groups.get_mut(&*name.org_key()) {
+            Some(group) => group.push(name),
+            None => {
+                groups.insert(name.org_key().into_owned(), vec![name]);
+            }
+        }
+    }
+    groups
+}
+
+/// Counts names by root domain, without keeping the names
+pub fn count_by_registrable<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+    list: &List,
+) -> HashMap<String, usize> {
+    let mut counts = HashMap::<String, usize>::new();
+    for name in names.into_iter().parse_dns_names(list).flatten() {
+        match counts.get_mut(&*name.org_key()) {
+            Some(count) => *count += 1,
+            None => {
+                counts.insert(name.org_key().into_owned(), 1);
+            }
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let names = [
+            "www.example.com",
+            "WWW.example.com.",
+            "example.com",
+            "a.b.example.co.uk",
+            "co.uk",
+            "co.uk.",
+            ".",
+            "a..b",
+        ];
+
+        let groups = by_registrable(names, &list);
+        let mut keys = groups.keys().map(String::as_str).collect::<Vec<_>>();
+        keys.sort_unstable();
+        assert_eq!(keys, [".", "co.uk", "example.co.uk", "example.com"]);
+        assert_eq!(groups["example.com"][1].name(), "www.example.com.");
+
+        let counts = count_by_registrable(names, &list);
+        assert_eq!(counts.len(), 4);
+        assert_eq!(counts["example.com"], 3);
+        assert_eq!(counts["co.uk"], 2);
+        assert_eq!(counts["."], 1);
+        for (key, group) in &groups {
+            assert_eq!(counts[key], group.len());
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Parsing many names into caller-owned memory
+
+use std::{cell::RefCell, fmt, io, ops::Range};
+
+use std::hash::{Hash, Hasher};
+
+use crate::{
+    escape, host, DnsName, List, ParseOptions, Scratch, SuffixOrigin, NO_OFFSET, PERCENT_DECODED,
+};
+
+/// Bytes set aside at a time; longer names get a block of their own
+const BLOCK_BYTES: usize = 16 * 1024;
+
+/// Memory that names parsed with `List::parse_into` are stored in, freed
+/// all at once
+///
+/// Names are copied into large blocks rather than allocated one by one, and
+/// the buffers used while parsing are kept between parses, so parsing into
+/// an arena rarely touches the global allocator. `reset` frees every name
+/// at once and keeps a block for reuse.
+///
+/// ```rust
+/// # use dns_name::{List, NameArena};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let mut arena = NameArena::new();
+/// for batch in [["www.example.com", "a.b.co.uk"], ["mail.example.net", "co.uk"]] {
+///     let names = batch
+///         .iter()
+///         .map(|name| list.parse_into(name, &arena).unwrap())
+///         .collect::<Vec<_>>();
+///     assert!(names[0].root().is_some());
+///     drop(names);
+///     arena.reset();
+/// }
+/// ```
+#[derive(Default)]
+pub struct NameArena {
+    /// blocks of names; a block never grows past its capacity, so the names
+    /// in it never move
+    blocks: RefCell<Vec<String>>,
+    scratch: RefCell<Scratch>,
+}
+
+impl NameArena {
+    /// Creates an empty arena; nothing is allocated until a name is parsed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copies `parts` into the arena, one after the other
+    fn alloc(&self, parts: &[&str]) -> &str {
+        let len = parts.iter().map(|part| part.len()).sum();
+        let mut blocks = self.blocks.borrow_mut();
+        let fits = blocks
+            .last()
+            .is_some_and(|block| block.capacity() - block.len() >= len);
+        if !fits {
+            blocks.push(String::with_capacity(BLOCK_BYTES.max(len)));
+        }
+        let Some(block) = blocks.last_mut() else {
+            unreachable!("a block was just added");
+        };
+        let start = block.len();
+        for part in parts {
+            block.push_str(part);
+        }
+        let stored: *const str = &block[start..];
+        // SAFETY: the block had room for `parts`, so pushing did not move its
+        // buffer, and nothing else will: blocks are only appended to while
+        // there is room, and only cleared or dropped through `&mut self`,
+        // which cannot happen while the returned borrow of `self` lives
+        unsafe { &*stored }
+    }
+
+    /// Bytes of names currently stored
+    pub fn allocated_bytes(&self) -> usize {
+        self.blocks.borrow().iter().map(String::len).sum()
+    }
+
+    /// Frees every name, keeping one block to reuse
+    pub fn reset(&mut self) {
+        let blocks = self.blocks.get_mut();
+        let keep = blocks
+            .iter()
+            .position(|block| block.capacity() == BLOCK_BYTES);
+        let kept = keep.map(|i| blocks.swap_remove(i));
+        blocks.clear();
+        if let Some(mut block) = kept {
+            block.clear();
+            blocks.push(block);
+        }
+    }
+}
+
+impl fmt::Debug for NameArena {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NameArena")
+            .field("blocks", &self.blocks.borrow().len())
+            .field("allocated_bytes", &self.allocated_bytes())
+            .finish()
+    }
+}
+
+/// A name parsed into a `NameArena`, with the parts of a `DnsName`
+///
+/// Like `DnsName`, it compares and hashes by name alone.
+#[derive(Debug, Clone, Copy)]
+pub struct DnsNameRef<'a> {
+    /// the name, followed by the original input if it differs
+    buf: &'a str,
+    /// start of the suffix, or `NO_OFFSET`
+    suffix: u16,
+    /// start of the root, or `NO_OFFSET`
+    root: u16,
+    /// start of the original input in `buf`, or `NO_OFFSET` if it is the
+    /// name
+    original: u16,
+    origin: Option<SuffixOrigin>,
+    /// `DnsName::flags` bits
+    flags: u8,
+}
+
+impl<'a> DnsNameRef<'a> {
+    /// Range of a part of the name starting at `offset`, without the
+    /// trailing dot
+    fn range(&self, offset: u16) -> Option<Range<usize>> {
+        (offset != NO_OFFSET).then(|| usize::from(offset)..escape::trim_dot(self.name()).len())
+    }
+
+    /// Get the DNS name
+    pub fn name(&self) -> &'a str {
+        match self.original {
+            NO_OFFSET => self.buf,
+            start => &self.buf[..usize::from(start)],
+        }
+    }
+
+    /// Gets the input the Name was parsed from, as `DnsName::original`
+    pub fn original(&self) -> &'a str {
+        match self.original {
+            NO_OFFSET => self.buf,
+            start => &self.buf[usize::from(start)..],
+        }
+    }
+
+    /// Gets the root domain portion of the Name, as `DnsName::root`
+    pub fn root(&self) -> Option<&'a str> {
+        self.range(self.root).map(|root| &self.name()[root.start..])
+    }
+
+    /// Gets the suffix portion of the Name, as `DnsName::suffix`
+    pub fn suffix(&self) -> Option<&'a str> {
+        self.range(self.suffix)
+            .map(|suffix| &self.name()[suffix.start..])
+    }
+
+    /// Gets the registrable label of the Name, as `DnsName::registrable`
+    pub fn registrable(&self) -> Option<&'a str> {
+        let root = self.range(self.root)?;
+        escape::split_first_label(&self.name()[root]).map(|(label, _)| label)
+    }
+
+    /// Gets the port that followed the Name in the input, as
+    /// `DnsName::port`
+    pub fn port(&self) -> Option<u16> {
+        host::port_of(self.original(), self.flags)
+    }
+
+    /// Returns true if `%XX` escapes were decoded from the input, as
+    /// `DnsName::was_percent_decoded`
+    pub fn was_percent_decoded(&self) -> bool {
+        self.flags & PERCENT_DECODED != 0
+    }
+
+    /// Gets the kind of rule the suffix came from, as
+    /// `DnsName::suffix_origin`
+    pub fn suffix_origin(&self) -> Option<SuffixOrigin> {
+        self.range(self.suffix).and(self.origin)
+    }
+
+    /// Copies the name out of the arena
+    pub fn to_dns_name(&self) -> DnsName {
+        let name = DnsName::new(self.name(), self.range(self.suffix), self.range(self.root));
+        let mut name = name.with_original(self.original());
+        name.origin = self.origin;
+        name.flags = self.flags;
+        name
+    }
+}
+
+impl PartialEq for DnsNameRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for DnsNameRef<'_> {}
+
+impl Hash for DnsNameRef<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name().hash(state);
+    }
+}
+
+impl fmt::Display for DnsNameRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl List {
+    /// Parses a DNS name into `arena`
+    ///
+    /// This gives the same parts as `parse_dns_name`, for bulk parsing
+    /// where the results are short-lived. Parses made this way are not
+    /// reported to a `ParseObserver`.
+    pub fn parse_into<'a>(&self, domain: &str, arena: &'a NameArena) -> io::Result<DnsNameRef<'a>> {
+        self.parse_into_with(domain, arena, &ParseOptions::default())
+    }
+
+    /// Parses a DNS name into `arena`, with extra checks enabled by
+    /// `options`
+    pub fn parse_into_with<'a>(
+        &self,
+        domain: &str,
+        arena: &'a NameArena,
+        options: &ParseOptions,
+    ) -> io::Result<DnsNameRef<'a>> {
+        let mut scratch = arena.scratch.borrow_mut();
+        let split = DnsName::split(domain, self, None, options, &mut scratch)?;
+        let flags = split.flags();
+        let (suffix, root) = DnsName::offsets(split.suffix, split.root);
+        let (buf, original) = if domain == split.name {
+            (arena.alloc(&[domain]), NO_OFFSET)
+        } else {
+            // names are never longer than `MAX_NAME_BYTES`
+            let original = split.name.len() as u16;
+            (arena.alloc(&[split.name, domain]), original)
+        };
+        Ok(DnsNameRef {
+            buf,
+            suffix,
+            root,
+            original,
+            origin: split.origin,
+            flags,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agrees_with_parse() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let arena = NameArena::new();
+        for input in [
+            "www.Example.co.uk.",
+            "a.b.c.kobe.jp",
+            "www.city.kobe.jp",
+            "co.uk",
+            "example.nope",
+            ".",
+            "ex\\.ample.com",
+        ] {
+            let owned = list.parse_dns_name(input).unwrap();
+            let parsed = list.parse_into(input, &arena).unwrap();
+            assert_eq!(parsed.name(), owned.name(), "{input}");
+            assert_eq!(parsed.suffix(), owned.suffix(), "{input}");
+            assert_eq!(parsed.root(), owned.root(), "{input}");
+            assert_eq!(parsed.registrable(), owned.registrable(), "{input}");
+            let copied = parsed.to_dns_name();
+            assert_eq!(copied, owned);
+            assert_eq!(copied.root(), owned.root());
+        }
+        assert!(list.parse_into("a..b", &arena).is_err());
+    }
+
+    #[test]
+    fn keeps_input() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let arena = NameArena::new();
+        let options = ParseOptions::new().port_suffix(true).percent_decoding(true);
+        for input in [
+            "example.com:8080",
+            "ex%41mple.com",
+            "Example.com",
+            "example.com",
+        ] {
+            let owned = list.parse_dns_name_with(input, &options).unwrap();
+            let parsed = list.parse_into_with(input, &arena, &options).unwrap();
+            assert_eq!(parsed.name(), owned.name(), "{input}");
+            assert_eq!(parsed.original(), owned.original(), "{input}");
+            assert_eq!(parsed.port(), owned.port(), "{input}");
+            assert_eq!(
+                parsed.was_percent_decoded(),
+                owned.was_percent_decoded(),
+                "{input}"
+            );
+            let copied = parsed.to_dns_name();
+            assert_eq!(copied.original(), owned.original());
+            assert_eq!(copied.port(), owned.port());
+            assert_eq!(copied.was_percent_decoded(), owned.was_percent_decoded());
+        }
+        let port = list.parse_into_with("example.com:8080", &arena, &options);
+        assert_eq!(port.unwrap().port(), Some(8080));
+        let decoded = list.parse_into_with("ex%41mple.com", &arena, &options);
+        assert!(decoded.unwrap().was_percent_decoded());
+    }
+
+    #[test]
+    fn blocks() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let mut arena = NameArena::new();
+
+        let first = list.parse_into("www.example.com", &arena).unwrap();
+        let many = (0..2000)
+            .map(|i| {
+                list.parse_into(&format!("host{i}.example.com"), &arena)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        // earlier names stay put as blocks are added
+        assert_eq!(first.name(), "www.example.com");
+        assert_eq!(many[1999].name(), "host1999.example.com");
+        assert!(arena.blocks.borrow().len() > 1);
+
+        let long = ["a"; 60].join(".").repeat(100);
+        let options = ParseOptions::new().max_labels(usize::MAX);
+        let long = list.parse_into_with(&long, &arena, &options).unwrap();
+        assert!(long.name().len() > BLOCK_BYTES / 4);
+
+        drop(many);
+        arena.reset();
+        assert_eq!(arena.allocated_bytes(), 0);
+        assert_eq!(arena.blocks.borrow().len(), 1);
+        list.parse_into("www.example.com", &arena).unwrap();
+        assert_eq!(arena.allocated_bytes(), "www.example.com".len());
+    }
+}
@@ -0,0 +1,149 @@
+//! Parsing many names at once
+
+use std::{io, num::NonZeroUsize, thread};
+
+use crate::{DnsName, List, ParseOptions, Scratch};
+
+impl List {
+    /// Parses many DNS names using the list
+    ///
+    /// Results are in input order. This gives the same results as calling
+    /// `parse_dns_name` in a loop, but reuses the buffers used for
+    /// lowercasing and splitting labels, so only long names that parse
+    /// allocate.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let names = list.parse_many(["www.example.com", ".bad", "example.co.uk"]);
+    /// assert_eq!(names.len(), 3);
+    /// assert_eq!(names[0].as_ref().unwrap().root(), Some("example.com"));
+    /// assert!(names[1].is_err());
+    /// ```
+    pub fn parse_many<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<io::Result<DnsName>> {
+        self.parse_many_with(names, &ParseOptions::default())
+    }
+
+    /// Parses many DNS names using the list, with extra checks enabled by
+    /// `options`
+    pub fn parse_many_with<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a str>,
+        options: &ParseOptions,
+    ) -> Vec<io::Result<DnsName>> {
+        let mut scratch = Scratch::default();
+        names
+            .into_iter()
+            .map(|name| DnsName::find_match_in(name, self, options, &mut scratch))
+            .collect()
+    }
+
+    /// Parses a slice of DNS names across threads
+    ///
+    /// The slice is split into one contiguous shard per available core, each
+    /// parsed with `parse_many`, and results are returned in input order.
+    /// Small batches are parsed on the calling thread.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let names = vec!["www.example.com"; 10_000];
+    /// let parsed = list.par_parse_many(&names);
+    /// assert_eq!(parsed.len(), 10_000);
+    /// assert!(parsed.iter().all(|name| name.is_ok()));
+    /// ```
+    pub fn par_parse_many<S: AsRef<str> + Sync>(&self, names: &[S]) -> Vec<io::Result<DnsName>> {
+        self.par_parse_many_with(names, &ParseOptions::default())
+    }
+
+    /// Parses a slice of DNS names across threads, with extra checks enabled
+    /// by `options`
+    pub fn par_parse_many_with<S: AsRef<str> + Sync>(
+        &self,
+        names: &[S],
+        options: &ParseOptions,
+    ) -> Vec<io::Result<DnsName>> {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let shard_len = names.len().div_ceil(threads).max(MIN_SHARD_LEN);
+        if names.len() <= shard_len {
+            return self.parse_many_with(names.iter().map(AsRef::as_ref), options);
+        }
+
+        thread::scope(|scope| {
+            let shards = names
+                .chunks(shard_len)
+                .map(|shard| {
+                    scope.spawn(move || {
+                        self.parse_many_with(shard.iter().map(AsRef::as_ref), options)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let mut parsed = Vec::with_capacity(names.len());
+            for shard in shards {
+                match shard.join() {
+                    Ok(shard) => parsed.extend(shard),
+                    Err(panic) => std::panic::resume_unwind(panic),
+                }
+            }
+            parsed
+        })
+    }
+}
+
+/// Fewest names worth starting a thread for
+const MIN_SHARD_LEN: usize = 1024;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_many() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let names = [
+            "WWW.Example.COM.",
+            "a\\.b.example.co.uk",
+            "",
+            "x.kobe.jp",
+            "a..b",
+            ".",
+            "www.city.kobe.jp",
+        ];
+
+        let batch = list.parse_many(names);
+        assert_eq!(batch.len(), names.len());
+        for (name, parsed) in names.iter().zip(batch) {
+            match list.parse_dns_name(name) {
+                Ok(expected) => assert_eq!(parsed.unwrap(), expected),
+                Err(_) => assert!(parsed.is_err()),
+            }
+        }
+    }
+
+    #[test]
+    fn par_parse_many() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let names = (0..5000)
+            .map(|i| match i % 3 {
+                0 => format!("host{i}.example.co.uk"),
+                1 => format!("{i}..bad"),
+                _ => format!("x{i}.kobe.jp."),
+            })
+            .collect::<Vec<_>>();
+
+        let parallel = list.par_parse_many(&names);
+        let serial = list.parse_many(names.iter().map(String::as_str));
+        assert_eq!(parallel.len(), serial.len());
+        for (parallel, serial) in parallel.into_iter().zip(serial) {
+            match serial {
+                Ok(serial) => assert_eq!(parallel.unwrap(), serial),
+                Err(_) => assert!(parallel.is_err()),
+            }
+        }
+        assert!(list.par_parse_many::<&str>(&[]).is_empty());
+    }
+}
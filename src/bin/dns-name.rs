@@ -0,0 +1,186 @@
+//! Ad hoc lookups against the public suffix list
+//!
+//! Requires the `cli` feature: `cargo install dns_name --features cli`.
+
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    process::ExitCode,
+};
+
+use dns_name::{DnsName, List};
+
+const USAGE: &str = "\
+usage: dns-name [--list FILE] [--json] parse [NAME...]
+       dns-name check-list FILE
+
+parse        print the suffix, root and registrable label of each NAME, or of
+             each line of standard input if no NAME is given
+check-list   report malformed, unmatched exception and duplicate rules
+
+--list FILE  parse with the list in FILE instead of the bundled one
+--json       print one JSON object per name instead of columns";
+
+/// The list shipped with the crate
+const BUNDLED_LIST: &str = include_str!("../../suffix-list.txt");
+
+fn main() -> ExitCode {
+    match run(env::args().skip(1).collect()) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("dns-name: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn usage(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, format!("{msg}\n{USAGE}"))
+}
+
+fn run(args: Vec<String>) -> io::Result<ExitCode> {
+    let mut list_path = None;
+    let mut json = false;
+    let mut args = args.into_iter();
+    let command = loop {
+        match args.next().as_deref() {
+            Some("--list") => {
+                list_path = Some(args.next().ok_or_else(|| usage("--list needs a file"))?);
+            }
+            Some("--json") => json = true,
+            Some("-h" | "--help") => {
+                println!("{USAGE}");
+                return Ok(ExitCode::SUCCESS);
+            }
+            Some(command) => break command.to_owned(),
+            None => return Err(usage("missing command")),
+        }
+    };
+    let rest = args.collect::<Vec<_>>();
+
+    match command.as_str() {
+        "parse" => {
+            let list = match list_path {
+                Some(path) => load(&path)?,
+                None => BUNDLED_LIST.parse()?,
+            };
+            parse(&list, &rest, json)
+        }
+        "check-list" => match rest.as_slice() {
+            [path] => check_list(path),
+            _ => Err(usage("check-list needs exactly one file")),
+        },
+        command => Err(usage(&format!("unknown command {command:?}"))),
+    }
+}
+
+/// Loads a list in either the comma separated or publicsuffix.org format
+fn load(path: &str) -> io::Result<List> {
+    List::from_buf_reader(BufReader::new(File::open(path)?))
+}
+
+fn parse(list: &List, names: &[String], json: bool) -> io::Result<ExitCode> {
+    let mut out = BufWriter::new(io::stdout().lock());
+    let mut failed = false;
+    let mut print = |name: &str| -> io::Result<()> {
+        let parsed = list.parse_dns_name(name);
+        failed |= parsed.is_err();
+        if json {
+            write_json(&mut out, name, &parsed)
+        } else {
+            write_columns(&mut out, name, &parsed)
+        }
+    };
+
+    if names.is_empty() {
+        for line in io::stdin().lock().lines() {
+            let line = line?;
+            let name = line.trim();
+            if !name.is_empty() {
+                print(name)?;
+            }
+        }
+    } else {
+        names.iter().try_for_each(|name| print(name))?;
+    }
+
+    out.flush()?;
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Writes `name suffix root registrable`, tab separated, with `-` for
+/// missing parts
+fn write_columns(
+    out: &mut impl Write,
+    input: &str,
+    parsed: &io::Result<DnsName>,
+) -> io::Result<()> {
+    match parsed {
+        Ok(name) => writeln!(
+            out,
+            "{}\t{}\t{}\t{}",
+            name.name(),
+            name.suffix().unwrap_or("-"),
+            name.root().unwrap_or("-"),
+            name.registrable().unwrap_or("-"),
+        ),
+        Err(err) => writeln!(out, "{input}\terror: {err}"),
+    }
+}
+
+fn write_json(out: &mut impl Write, input: &str, parsed: &io::Result<DnsName>) -> io::Result<()> {
+    match parsed {
+        Ok(name) => writeln!(
+            out,
+            r#"{{"name":{},"suffix":{},"root":{},"registrable":{}}}"#,
+            json_string(Some(name.name())),
+            json_string(name.suffix()),
+            json_string(name.root()),
+            json_string(name.registrable()),
+        ),
+        Err(err) => writeln!(
+            out,
+            r#"{{"input":{},"error":{}}}"#,
+            json_string(Some(input)),
+            json_string(Some(&err.to_string())),
+        ),
+    }
+}
+
+/// Quotes a JSON string, or `null`
+fn json_string(s: Option<&str>) -> String {
+    let Some(s) = s else {
+        return "null".to_owned();
+    };
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn check_list(path: &str) -> io::Result<ExitCode> {
+    let issues = load(path)?.lint();
+    let mut out = BufWriter::new(io::stdout().lock());
+    for issue in &issues {
+        writeln!(out, "{issue}")?;
+    }
+    out.flush()?;
+    Ok(if issues.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
@@ -0,0 +1,213 @@
+//! Building names from labels
+
+use std::{io, ops::Range};
+
+use crate::{escape, DnsName, List, MAX_LABEL_LEN};
+
+/// Longest name permitted by RFC 1035, in presentation format without the
+/// trailing dot
+const MAX_NAME_LEN: usize = 253;
+
+/// Checks that a label is 1 to 63 letters, digits, hyphens or underscores and
+/// does not start or end with a hyphen
+fn validate_label(label: &str) -> io::Result<()> {
+    let invalid = |msg| Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+    if label.is_empty() {
+        return invalid("empty label");
+    }
+    if label.len() > MAX_LABEL_LEN {
+        return invalid("label too long");
+    }
+    if !label
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    {
+        return invalid("invalid character in label");
+    }
+    if label.starts_with('-') || label.ends_with('-') {
+        return invalid("label starts or ends with a hyphen");
+    }
+    Ok(())
+}
+
+/// Checks every label of a dot separated prefix
+fn validate_labels(prefix: &str) -> io::Result<()> {
+    prefix.split('.').try_for_each(validate_label)
+}
+
+impl DnsName {
+    /// Adds one or more labels to the left of the Name
+    ///
+    /// Labels are validated (1 to 63 letters, digits, hyphens or underscores)
+    /// and lowercased. The suffix and root are carried over without consulting
+    /// the list again; prepending to a public suffix makes the new leftmost
+    /// label part of the root. A name parsed without any matching rule stays
+    /// without a suffix.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.example.co.uk").unwrap();
+    /// let root = list.parse_dns_name(name.root().unwrap()).unwrap();
+    /// let api = root.prepend("api").unwrap();
+    /// assert_eq!(api.name(), "api.example.co.uk");
+    /// assert_eq!(api.root(), Some("example.co.uk"));
+    /// assert!(root.prepend("bad label").is_err());
+    /// ```
+    pub fn prepend(&self, prefix: &str) -> io::Result<DnsName> {
+        validate_labels(prefix)?;
+        self.prepend_unchecked(&prefix.to_ascii_lowercase())
+    }
+
+    /// Adds labels to the left of the Name without validating them
+    pub(crate) fn prepend_unchecked(&self, prefix: &str) -> io::Result<DnsName> {
+        let (name, added) = if self.name() == "." {
+            (format!("{prefix}."), prefix.len())
+        } else {
+            (format!("{prefix}.{}", self.name()), prefix.len() + 1)
+        };
+        if escape::trim_dot(&name).len() > MAX_NAME_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "name too long"));
+        }
+
+        let shift = |range: &Range<usize>| Range {
+            start: range.start + added,
+            end: range.end + added,
+        };
+        let suffix = self.suffix_range().as_ref().map(shift);
+        let root = match (self.root_range(), &suffix) {
+            (Some(root), _) => Some(shift(&root)),
+            (None, Some(suffix)) => {
+                // the name was a public suffix, the last new label joins it
+                let start = prefix.rfind('.').map_or(0, |i| i + 1);
+                Some(Range {
+                    start,
+                    end: suffix.end,
+                })
+            }
+            (None, None) => None,
+        };
+
+        let mut name = DnsName::new(name, suffix, root);
+        name.origin = self.origin;
+        Ok(name)
+    }
+}
+
+/// Builds a `DnsName` from validated labels
+///
+/// Labels are added from left to right and validated when the name is built,
+/// so the first problem is reported by `build`.
+///
+/// ```rust
+/// # use dns_name::{DnsNameBuilder, List};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let name = DnsNameBuilder::new()
+///     .label("api")
+///     .labels("example.co.uk")
+///     .fqdn(true)
+///     .build(&list)
+///     .unwrap();
+/// assert_eq!(name.name(), "api.example.co.uk.");
+/// assert_eq!(name.root(), Some("example.co.uk."));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DnsNameBuilder {
+    labels: Vec<String>,
+    fqdn: bool,
+}
+
+impl DnsNameBuilder {
+    /// Creates a builder without any labels
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a single label
+    pub fn label(mut self, label: &str) -> Self {
+        self.labels.push(label.to_owned());
+        self
+    }
+
+    /// Adds every label of a dot separated name, ignoring a trailing dot
+    pub fn labels(mut self, name: &str) -> Self {
+        let name = name.strip_suffix('.').unwrap_or(name);
+        self.labels.extend(name.split('.').map(str::to_owned));
+        self
+    }
+
+    /// Sets whether the built name has a trailing dot
+    pub fn fqdn(mut self, fqdn: bool) -> Self {
+        self.fqdn = fqdn;
+        self
+    }
+
+    /// Validates the labels and parses the resulting name using the list
+    pub fn build(&self, list: &List) -> io::Result<DnsName> {
+        if self.labels.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no labels"));
+        }
+        self.labels
+            .iter()
+            .try_for_each(|label| validate_label(label))?;
+
+        let mut name = self.labels.join(".");
+        if name.len() > MAX_NAME_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "name too long"));
+        }
+        if self.fqdn {
+            name.push('.');
+        }
+        list.parse_dns_name(&name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prepend() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let parse = |name: &str| list.parse_dns_name(name).unwrap();
+
+        // agrees with parsing the whole name
+        for (base, prefix) in [
+            ("example.com.", "www"),
+            ("co.uk", "a.B.example"),
+            ("b.c.kobe.jp", "x.y"),
+        ] {
+            let name = parse(base).prepend(prefix).unwrap();
+            assert_eq!(name, parse(&format!("{prefix}.{base}")));
+        }
+
+        let name = parse(".").prepend("com").unwrap();
+        assert_eq!(name.name(), "com.");
+
+        let com = parse("com");
+        assert!(com.prepend("").is_err());
+        assert!(com.prepend("a..b").is_err());
+        assert!(com.prepend("-a").is_err());
+        assert!(com.prepend("a/b").is_err());
+        assert!(com.prepend(&"a".repeat(64)).is_err());
+        assert!(com.prepend(&"a".repeat(63)).is_ok());
+        assert!(com.prepend(&["a"; 127].join(".")).is_err());
+    }
+
+    #[test]
+    fn builder() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+
+        let name = DnsNameBuilder::new()
+            .labels("_dmarc")
+            .labels("mail.example.com.")
+            .build(&list)
+            .unwrap();
+        assert_eq!(name.name(), "_dmarc.mail.example.com");
+        assert_eq!(name.root(), Some("example.com"));
+
+        assert!(DnsNameBuilder::new().build(&list).is_err());
+        assert!(DnsNameBuilder::new().label("a.b").build(&list).is_err());
+        assert!(DnsNameBuilder::new().labels(".").build(&list).is_err());
+    }
+}
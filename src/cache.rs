@@ -0,0 +1,279 @@
+//! A bounded LRU cache in front of `List::parse_dns_name`
+
+use std::{
+    collections::HashMap,
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{DnsName, List};
+
+/// Marks the end of the recency list
+const NIL: usize = usize::MAX;
+
+/// Hit and miss counts of a `CachedList`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// A `List` that remembers the most recently parsed names
+///
+/// Names are keyed on their lowercased input, so `WWW.Example.com` and
+/// `www.example.com` share an entry. Only successful parses are cached.
+/// The cache is behind a mutex, so a `CachedList` can be shared between
+/// threads.
+///
+/// ```rust
+/// # use dns_name::{CachedList, List};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let cache = CachedList::new(list, 1000);
+///
+/// let a = cache.parse_dns_name("www.example.com").unwrap();
+/// let b = cache.parse_dns_name("WWW.EXAMPLE.COM").unwrap();
+/// assert_eq!(a, b);
+/// assert_eq!(cache.stats().hits, 1);
+/// assert_eq!(cache.stats().misses, 1);
+/// ```
+#[derive(Debug)]
+pub struct CachedList {
+    list: List,
+    lru: Mutex<Lru>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CachedList {
+    /// Wraps a list with a cache holding up to `capacity` names
+    pub fn new(list: List, capacity: usize) -> Self {
+        Self {
+            list,
+            lru: Mutex::new(Lru::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The wrapped list
+    pub fn list(&self) -> &List {
+        &self.list
+    }
+
+    /// Parses a DNS name using the list, or returns the cached result
+    ///
+    /// Names differing only in ASCII case share an entry; `original` is the
+    /// input of each call.
+    pub fn parse_dns_name(&self, domain: &str) -> io::Result<DnsName> {
+        let key = domain.to_ascii_lowercase();
+        if let Some(name) = self.lock().get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(name.with_original(domain));
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let name = self.list.parse_dns_name(domain)?;
+        self.lock().insert(key, name.clone());
+        Ok(name)
+    }
+
+    /// Hit and miss counts since creation or the last `reset_stats`
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Zeroes the hit and miss counts
+    pub fn reset_stats(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    /// Number of cached names
+    pub fn len(&self) -> usize {
+        self.lock().map.len()
+    }
+
+    /// Returns true if no names are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every cached name
+    pub fn clear(&self) {
+        let mut lru = self.lock();
+        *lru = Lru::new(lru.capacity);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru> {
+        // the cache is always consistent between calls, so a panic while
+        // holding the lock cannot leave it broken
+        self.lru
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A cached name, linked into the recency list
+#[derive(Debug)]
+struct Entry {
+    key: String,
+    name: DnsName,
+    prev: usize,
+    next: usize,
+}
+
+/// Least recently used map from lowercased input to parsed name
+///
+/// Entries live in a `Vec` and are linked by index from most (`head`) to
+/// least (`tail`) recently used.
+#[derive(Debug)]
+struct Lru {
+    capacity: usize,
+    map: HashMap<String, usize>,
+    entries: Vec<Entry>,
+    head: usize,
+    tail: usize,
+}
+
+impl Lru {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            map: HashMap::new(),
+            entries: Vec::new(),
+            head: NIL,
+            tail: NIL,
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<DnsName> {
+        let index = *self.map.get(key)?;
+        self.unlink(index);
+        self.push_front(index);
+        Some(self.entries[index].name.clone())
+    }
+
+    fn insert(&mut self, key: String, name: DnsName) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Some(&index) = self.map.get(&key) {
+            self.entries[index].name = name;
+            self.unlink(index);
+            self.push_front(index);
+            return;
+        }
+
+        let index = if self.entries.len() < self.capacity {
+            self.entries.push(Entry {
+                key: key.clone(),
+                name,
+                prev: NIL,
+                next: NIL,
+            });
+            self.entries.len() - 1
+        } else {
+            // reuse the least recently used slot
+            let index = self.tail;
+            self.unlink(index);
+            let entry = &mut self.entries[index];
+            self.map.remove(&entry.key);
+            entry.key.clone_from(&key);
+            entry.name = name;
+            index
+        };
+        self.map.insert(key, index);
+        self.push_front(index);
+    }
+
+    fn unlink(&mut self, index: usize) {
+        let Entry { prev, next, .. } = self.entries[index];
+        match prev {
+            NIL => self.head = next,
+            prev => self.entries[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.entries[next].prev = prev,
+        }
+    }
+
+    fn push_front(&mut self, index: usize) {
+        self.entries[index].prev = NIL;
+        self.entries[index].next = self.head;
+        match self.head {
+            NIL => self.tail = index,
+            head => self.entries[head].prev = index,
+        }
+        self.head = index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lru() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let cache = CachedList::new(list, 2);
+
+        cache.parse_dns_name("a.example.com").unwrap();
+        cache.parse_dns_name("b.example.com").unwrap();
+        // a is now more recently used than b
+        cache.parse_dns_name("A.example.com").unwrap();
+        cache.parse_dns_name("c.example.com").unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 3 });
+
+        // b was evicted, a and c remain
+        cache.parse_dns_name("a.example.com").unwrap();
+        cache.parse_dns_name("c.example.com").unwrap();
+        cache.parse_dns_name("b.example.com").unwrap();
+        assert_eq!(cache.stats(), CacheStats { hits: 3, misses: 4 });
+
+        assert!(cache.parse_dns_name("a..b").is_err());
+        assert!(cache.parse_dns_name("a..b").is_err());
+        assert_eq!(cache.stats().misses, 6);
+
+        cache.reset_stats();
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats(), CacheStats::default());
+
+        let cache = CachedList::new(List::empty(), 0);
+        cache.parse_dns_name("example.com").unwrap();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn cached_name_matches() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let expected = list.parse_dns_name("www.Example.co.uk.").unwrap();
+        let cache = CachedList::new(list, 10);
+        for _ in 0..3 {
+            assert_eq!(
+                cache.parse_dns_name("www.Example.co.uk.").unwrap(),
+                expected
+            );
+        }
+
+        // a hit keeps the spelling of its own input
+        for input in [
+            "WWW.EXAMPLE.CO.UK.",
+            "www.example.co.uk.",
+            "www.Example.co.uk.",
+        ] {
+            let name = cache.parse_dns_name(input).unwrap();
+            assert_eq!(name.original(), input);
+            assert_eq!(name.name(), "www.example.co.uk.");
+        }
+        assert_eq!(cache.stats().misses, 1);
+    }
+}
@@ -0,0 +1,234 @@
+//! Comparing names regardless of case and trailing dot
+
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+use crate::{escape, DnsName};
+
+/// A `DnsName` that compares and hashes canonically
+///
+/// `DnsName` compares the parsed strings as they are, so `example.com` and
+/// `example.com.` are different. Wrapped in `CanonicalDnsName` they are
+/// equal and hash identically, as is any difference in ASCII case.
+///
+/// ```rust
+/// # use dns_name::{CanonicalDnsName, List};
+/// # use std::collections::HashSet;
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let names = ["Example.COM.", "example.com", "www.example.com"]
+///     .iter()
+///     .map(|name| CanonicalDnsName::from(list.parse_dns_name(name).unwrap()))
+///     .collect::<HashSet<_>>();
+/// assert_eq!(names.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CanonicalDnsName(DnsName);
+
+impl CanonicalDnsName {
+    /// Unwraps the `DnsName`
+    pub fn into_inner(self) -> DnsName {
+        self.0
+    }
+}
+
+impl From<DnsName> for CanonicalDnsName {
+    fn from(name: DnsName) -> Self {
+        Self(name)
+    }
+}
+
+impl Deref for CanonicalDnsName {
+    type Target = DnsName;
+
+    fn deref(&self) -> &DnsName {
+        &self.0
+    }
+}
+
+impl PartialEq for CanonicalDnsName {
+    fn eq(&self, other: &Self) -> bool {
+        self.0
+            .without_trailing_dot()
+            .eq_ignore_ascii_case(other.0.without_trailing_dot())
+    }
+}
+
+impl Eq for CanonicalDnsName {}
+
+impl Hash for CanonicalDnsName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for b in self.0.without_trailing_dot().bytes() {
+            state.write_u8(b.to_ascii_lowercase());
+        }
+        state.write_u8(0xff);
+    }
+}
+
+impl PartialOrd for CanonicalDnsName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders names like `DnsName` does, except that names differing only in
+/// ASCII case or a trailing dot are equal
+///
+/// Names whose labels hold the same octets but that are not equal are
+/// ordered by their lowercased string without the trailing dot, to stay
+/// consistent with `Eq`.
+impl Ord for CanonicalDnsName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lowercase = |name: &DnsName| {
+            let name = name.without_trailing_dot().bytes();
+            name.map(|b| b.to_ascii_lowercase()).collect::<Vec<_>>()
+        };
+        canonical_cmp(self.0.name(), other.0.name())
+            .then_with(|| lowercase(&self.0).cmp(&lowercase(&other.0)))
+    }
+}
+
+impl PartialOrd for DnsName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders names in DNS canonical order ([RFC 4034 section 6.1])
+///
+/// Names are compared label by label starting from the rightmost label,
+/// ignoring ASCII case, so names in the same zone sort together:
+/// `example.com` < `a.example.com` < `z.example.com` < `b.zzz.com`. Names
+/// that are canonically equal (`example.com` and `example.com.`) are ordered
+/// by their parsed string to stay consistent with `Eq`.
+///
+/// [RFC 4034 section 6.1]: https://www.rfc-editor.org/rfc/rfc4034#section-6.1
+impl Ord for DnsName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        canonical_cmp(self.name(), other.name()).then_with(|| self.name().cmp(other.name()))
+    }
+}
+
+/// Compares two names label by label from the right, ignoring ASCII case
+///
+/// Labels are compared as octets, with escapes decoded.
+fn canonical_cmp(a: &str, b: &str) -> Ordering {
+    fn labels(name: &str) -> impl Iterator<Item = &str> {
+        let name = escape::trim_dot(name);
+        let ranges = if name.is_empty() {
+            Vec::new()
+        } else {
+            escape::label_ranges(name)
+        };
+        ranges.into_iter().rev().map(move |range| &name[range])
+    }
+    fn octets(label: &str) -> Cow<'_, [u8]> {
+        // names are validated when parsed, so escapes are well formed
+        escape::unescape(label).unwrap_or_default()
+    }
+
+    let mut a = labels(a);
+    let mut b = labels(b);
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => {
+                let (a, b) = (octets(a), octets(b));
+                let lowercase = |b: &u8| b.to_ascii_lowercase();
+                match a.iter().map(lowercase).cmp(b.iter().map(lowercase)) {
+                    Ordering::Equal => continue,
+                    ordering => return ordering,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::List;
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash(name: &CanonicalDnsName) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn canonical_eq() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let parse = |name: &str| CanonicalDnsName::from(list.parse_dns_name(name).unwrap());
+
+        let a = parse("WWW.example.com.");
+        let b = parse("www.EXAMPLE.com");
+        assert_ne!(*a, *b);
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+
+        assert_ne!(parse("example.com"), parse("www.example.com"));
+        assert_eq!(parse("."), parse("."));
+        assert_ne!(parse("."), parse("com"));
+        assert_eq!(a.into_inner().name(), "www.example.com.");
+    }
+
+    #[test]
+    fn canonical_order() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+
+        // the example from RFC 4034 section 6.1
+        let sorted = [
+            ".",
+            "example",
+            "a.example",
+            "yljkjljk.a.example",
+            "z.a.example",
+            "zabc.a.example",
+            "z.example",
+            "*.z.example",
+            "\\200.z.example",
+        ];
+        let mut names = sorted
+            .iter()
+            .rev()
+            .map(|name| list.parse_dns_name(name).unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        let names = names.iter().map(|name| name.name()).collect::<Vec<_>>();
+        assert_eq!(names, sorted);
+
+        let a = list.parse_dns_name("example.com").unwrap();
+        let b = list.parse_dns_name("example.com.").unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Less);
+        assert_eq!(
+            CanonicalDnsName::from(a).cmp(&CanonicalDnsName::from(b)),
+            Ordering::Equal
+        );
+
+        // escaped and unescaped forms are equal exactly when they compare so
+        let canonical = |name: &str| CanonicalDnsName::from(list.parse_dns_name(name).unwrap());
+        let names = [
+            "\\040.example.",
+            "(.EXAMPLE",
+            "a\\ b.example",
+            "A\\032B.example.",
+            "\\200.example",
+            "a\\.b.example",
+            "a\\046b.example",
+        ]
+        .map(canonical);
+        for a in &names {
+            for b in &names {
+                assert_eq!(a == b, a.cmp(b) == Ordering::Equal, "{a:?} {b:?}");
+            }
+        }
+        assert_eq!(names[0].cmp(&names[1]), Ordering::Equal);
+        assert_eq!(names[2].cmp(&names[3]), Ordering::Equal);
+    }
+}
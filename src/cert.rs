@@ -0,0 +1,78 @@
+//! TLS certificate hostname matching (RFC 6125)
+
+use crate::{escape, DnsName};
+
+/// Checks whether a host matches a certificate `subjectAltName` DNS pattern
+///
+/// Implements the rules of [RFC 6125 section 6.4.3]:
+/// - names are compared case-insensitively, ignoring a trailing dot
+/// - a `*` is only allowed as the complete leftmost label, and matches
+///   exactly one label
+/// - a wildcard never matches directly beneath a public suffix, so
+///   `*.co.uk` matches nothing
+///
+/// ```rust
+/// # use dns_name::{cert, List};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let host = list.parse_dns_name("www.example.co.uk").unwrap();
+/// assert!(cert::matches_san(&host, "*.example.co.uk"));
+/// assert!(!cert::matches_san(&host, "*.co.uk"));
+/// assert!(!cert::matches_san(&host, "w*.example.co.uk"));
+/// ```
+///
+/// [RFC 6125 section 6.4.3]: https://www.rfc-editor.org/rfc/rfc6125#section-6.4.3
+pub fn matches_san(host: &DnsName, pattern: &str) -> bool {
+    let name = escape::trim_dot(host.name());
+    let pattern = escape::trim_dot(pattern);
+    if name.is_empty() || pattern.is_empty() {
+        return false;
+    }
+
+    let Some(base) = pattern.strip_prefix("*.") else {
+        return !pattern.contains('*') && name.eq_ignore_ascii_case(pattern);
+    };
+    if base.contains('*') {
+        return false;
+    }
+
+    let Some((_, parent)) = escape::split_first_label(name) else {
+        return false;
+    };
+    if !parent.eq_ignore_ascii_case(base) {
+        return false;
+    }
+
+    // the wildcard label must lie outside the root domain
+    match host.root() {
+        Some(root) => parent.len() >= escape::trim_dot(root).len(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::List;
+
+    #[test]
+    fn matches() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let matches =
+            |host: &str, pattern: &str| matches_san(&list.parse_dns_name(host).unwrap(), pattern);
+
+        assert!(matches("example.com", "EXAMPLE.com."));
+        assert!(matches("www.example.com.", "*.example.com"));
+        assert!(matches("a.b.example.com", "*.b.example.com"));
+        assert!(!matches("a.b.example.com", "*.example.com"));
+        assert!(!matches("example.com", "*.example.com"));
+        assert!(!matches("example.com", "*.com"));
+        assert!(!matches("www.example.com", "*"));
+        assert!(!matches("www.example.com", "www.*.com"));
+        assert!(!matches("www.example.com", "*.*.com"));
+        assert!(!matches("www.example.com", "*www.example.com"));
+        // wildcard public suffix rules
+        assert!(!matches("b.c.kobe.jp", "*.c.kobe.jp"));
+        assert!(matches("a.b.c.kobe.jp", "*.b.c.kobe.jp"));
+        assert!(!matches(".", "*"));
+    }
+}
@@ -0,0 +1,168 @@
+//! The publicsuffix.org test suite, for checking a list snapshot
+//!
+//! Each test is a line `input expected-root`, with `null` for an empty input
+//! or no root domain. Lines starting with `//` and blank lines are skipped.
+//!
+//! ```rust
+//! # use std::{fs::File, io::BufReader};
+//! # use dns_name::{conformance, List};
+//! let list = List::from_path("suffix-list.txt").unwrap();
+//! let tests = BufReader::new(File::open("tests.txt").unwrap());
+//! let report = conformance::run(&list, tests).unwrap();
+//! assert!(report.is_ok(), "{report}");
+//! ```
+
+use std::{fmt, io, io::BufRead};
+
+use crate::{List, ParseOptions};
+
+/// The outcome of running the test suite
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    /// Number of tests that passed
+    pub passed: usize,
+    /// Every test that failed, in file order
+    pub failures: Vec<Failure>,
+}
+
+/// A test whose result did not match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    /// 1-based line number in the test file
+    pub line: usize,
+    pub input: String,
+    pub expected_root: Option<String>,
+    pub expected_suffix: Option<String>,
+    pub found_root: Option<String>,
+    pub found_suffix: Option<String>,
+}
+
+impl Report {
+    /// Returns true if every test passed
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} passed, {} failed", self.passed, self.failures.len())?;
+        self.failures
+            .iter()
+            .try_for_each(|failure| writeln!(f, "{failure}"))
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: given `{}`, expected root {:?} and suffix {:?}, found root {:?} and \
+             suffix {:?}",
+            self.line,
+            self.input,
+            self.expected_root,
+            self.expected_suffix,
+            self.found_root,
+            self.found_suffix
+        )
+    }
+}
+
+/// Runs every test in `tests` against `list`
+///
+/// Fails if `tests` cannot be read or a line is not a valid test.
+pub fn run<R: BufRead>(list: &List, tests: R) -> io::Result<Report> {
+    run_with(list, tests, &ParseOptions::default())
+}
+
+/// Runs every test in `tests` against `list`, parsing with `options`
+///
+/// Use `Algorithm::PslSpec` to check agreement with the publicsuffix.org
+/// algorithm.
+pub fn run_with<R: BufRead>(list: &List, tests: R, options: &ParseOptions) -> io::Result<Report> {
+    let mut report = Report::default();
+
+    for (i, line) in tests.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+
+        let mut test = line.split_whitespace();
+        let (Some(input), Some(root)) = (test.next(), test.next()) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {} is not a valid test", i + 1),
+            ));
+        };
+        let input = match input {
+            "null" => "",
+            input => input,
+        };
+        let (expected_root, expected_suffix) = match root {
+            "null" => (None, None),
+            root => {
+                // the suffix is the root without its first label
+                let suffix = root.split_once('.').map_or("", |(_, suffix)| suffix);
+                (Some(root.to_owned()), Some(suffix.to_owned()))
+            }
+        };
+
+        let (found_root, found_suffix) = match list.parse_dns_name_with(input, options) {
+            Ok(domain) => (
+                domain.root().map(str::to_owned),
+                domain.suffix().map(str::to_owned),
+            ),
+            Err(_) => (None, None),
+        };
+
+        if expected_root != found_root
+            || (expected_root.is_some() && expected_suffix != found_suffix)
+        {
+            report.failures.push(Failure {
+                line: i + 1,
+                input: input.to_owned(),
+                expected_root,
+                expected_suffix,
+                found_root,
+                found_suffix,
+            });
+        } else {
+            report.passed += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let tests = "// comment\n\nnull null\nexample.com example.com\nwww.co.uk co.uk\n";
+
+        let report = run(&list, tests.as_bytes()).unwrap();
+        assert_eq!(report.passed, 2);
+        assert_eq!(
+            report.failures,
+            [Failure {
+                line: 5,
+                input: "www.co.uk".to_owned(),
+                expected_root: Some("co.uk".to_owned()),
+                expected_suffix: Some("uk".to_owned()),
+                found_root: Some("www.co.uk".to_owned()),
+                found_suffix: Some("co.uk".to_owned()),
+            }]
+        );
+        assert!(report
+            .to_string()
+            .starts_with("2 passed, 1 failed\nline 5:"));
+
+        assert!(run(&list, "example.com\n".as_bytes()).is_err());
+    }
+}
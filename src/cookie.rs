@@ -0,0 +1,125 @@
+//! Cookie domain matching (RFC 6265)
+
+use crate::{escape, host, DnsName, List};
+
+/// Canonicalizes a cookie `Domain` attribute: no leading or trailing dot,
+/// lowercase
+fn canonical_cookie_domain(cookie_domain: &str) -> String {
+    let domain = cookie_domain.strip_prefix('.').unwrap_or(cookie_domain);
+    escape::trim_dot(domain).to_ascii_lowercase()
+}
+
+impl DnsName {
+    /// Checks whether the Name domain-matches a cookie domain
+    ///
+    /// Implements [RFC 6265 section 5.1.3]: the names are identical, or the
+    /// cookie domain is a suffix of the Name on a label boundary. A leading dot
+    /// on the cookie domain is ignored, as is case and a trailing dot.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.example.com").unwrap();
+    /// assert!(name.domain_matches("example.com"));
+    /// assert!(name.domain_matches(".Example.com"));
+    /// assert!(!name.domain_matches("ample.com"));
+    /// ```
+    ///
+    /// [RFC 6265 section 5.1.3]: https://www.rfc-editor.org/rfc/rfc6265#section-5.1.3
+    pub fn domain_matches(&self, cookie_domain: &str) -> bool {
+        let cookie_domain = canonical_cookie_domain(cookie_domain);
+        let name = escape::trim_dot(self.name());
+        if cookie_domain.is_empty() {
+            return false;
+        }
+
+        match name.strip_suffix(cookie_domain.as_str()) {
+            Some("") => true,
+            Some(rest) => rest.ends_with('.') && !escape::is_escaped(rest, rest.len() - 1),
+            None => false,
+        }
+    }
+}
+
+impl List {
+    /// Checks whether a response from `request_host` may set a cookie for
+    /// `cookie_domain`
+    ///
+    /// Implements the `Domain` attribute checks of [RFC 6265 section 5.3]:
+    /// cookies may not be set for a public suffix (`co.uk`) unless it is the
+    /// request host itself, and the request host must domain-match the
+    /// cookie domain. IP address hosts only match themselves.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// assert!(list.is_cookie_domain_acceptable("www.example.co.uk", "example.co.uk"));
+    /// assert!(!list.is_cookie_domain_acceptable("www.example.co.uk", "co.uk"));
+    /// assert!(!list.is_cookie_domain_acceptable("www.example.co.uk", "other.co.uk"));
+    /// ```
+    ///
+    /// [RFC 6265 section 5.3]: https://www.rfc-editor.org/rfc/rfc6265#section-5.3
+    pub fn is_cookie_domain_acceptable(&self, request_host: &str, cookie_domain: &str) -> bool {
+        let canonical = canonical_cookie_domain(cookie_domain);
+        if canonical.is_empty() {
+            return false;
+        }
+
+        if host::parse_ip(request_host).is_some() {
+            return request_host.eq_ignore_ascii_case(&canonical);
+        }
+
+        let request_host = match self.parse_dns_name(request_host) {
+            Ok(name) => name,
+            Err(_) => return false,
+        };
+
+        let is_public_suffix = match self.parse_dns_name(&canonical) {
+            Ok(domain) => domain.root().is_none() && domain.suffix().is_some(),
+            Err(_) => return false,
+        };
+        if is_public_suffix {
+            // only acceptable as a host-only cookie on the suffix itself
+            return escape::trim_dot(request_host.name()) == canonical;
+        }
+
+        request_host.domain_matches(&canonical)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn domain_matches() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let name = list.parse_dns_name("WWW.Example.COM.").unwrap();
+
+        assert!(name.domain_matches("www.example.com"));
+        assert!(name.domain_matches("example.com."));
+        assert!(name.domain_matches("com"));
+        assert!(!name.domain_matches("notexample.com"));
+        assert!(!name.domain_matches("a.www.example.com"));
+        assert!(!name.domain_matches(""));
+        assert!(!name.domain_matches("."));
+    }
+
+    #[test]
+    fn cookie_domain_acceptable() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+
+        assert!(list.is_cookie_domain_acceptable("example.com", ".example.com"));
+        assert!(list.is_cookie_domain_acceptable("a.b.example.com", "b.example.com"));
+        assert!(!list.is_cookie_domain_acceptable("example.com", "com"));
+        assert!(!list.is_cookie_domain_acceptable("a.b.c.kobe.jp", "c.kobe.jp"));
+        assert!(list.is_cookie_domain_acceptable("a.b.c.kobe.jp", "b.c.kobe.jp"));
+        // a public suffix may set host-only cookies for itself
+        assert!(list.is_cookie_domain_acceptable("github.io", "github.io"));
+        assert!(list.is_cookie_domain_acceptable("localhost", "localhost"));
+
+        assert!(list.is_cookie_domain_acceptable("127.0.0.1", "127.0.0.1"));
+        assert!(!list.is_cookie_domain_acceptable("127.0.0.1", "0.0.1"));
+        assert!(!list.is_cookie_domain_acceptable("exa..mple.com", "mple.com"));
+    }
+}
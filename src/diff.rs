@@ -0,0 +1,206 @@
+//! Updating a list in place from the changes between two snapshots
+
+use std::{collections::HashSet, io};
+
+use crate::{query, List, ListLeaf, ListNode, PREVAILING_STAR_RULE};
+
+/// Rules added and removed between two versions of a list
+///
+/// Changing a rule to or from an exception is a removal and an addition.
+///
+/// ```rust
+/// # use dns_name::{List, ListDiff};
+/// let mut list = "com,uk,co.uk".parse::<List>().unwrap();
+/// let newer = "com,uk,co.uk,ac.uk".parse::<List>().unwrap();
+///
+/// let diff = list.diff(&newer);
+/// assert_eq!(diff.added(), ["ac.uk"]);
+/// list.apply_diff(&diff).unwrap();
+/// assert_eq!(list.parse_dns_name("www.ox.ac.uk").unwrap().root(), Some("ox.ac.uk"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListDiff {
+    added: Vec<String>,
+    removed: Vec<String>,
+}
+
+impl ListDiff {
+    /// Creates an empty diff
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule, in the same syntax as a list
+    pub fn add_rule(mut self, rule: &str) -> Self {
+        self.added.push(rule.to_owned());
+        self
+    }
+
+    /// Removes a rule, in the same syntax as a list
+    pub fn remove_rule(mut self, rule: &str) -> Self {
+        self.removed.push(rule.to_owned());
+        self
+    }
+
+    /// Rules to add
+    pub fn added(&self) -> &[String] {
+        &self.added
+    }
+
+    /// Rules to remove
+    pub fn removed(&self) -> &[String] {
+        &self.removed
+    }
+
+    /// Returns true if the diff changes nothing
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl List {
+    /// Finds the rules to add and remove to turn the List into `newer`
+    ///
+    /// Both sets of rules are sorted.
+    pub fn diff(&self, newer: &List) -> ListDiff {
+        let old = self.rules();
+        let new = newer.rules();
+        let mut added = new.difference(&old).cloned().collect::<Vec<_>>();
+        let mut removed = old.difference(&new).cloned().collect::<Vec<_>>();
+        added.sort();
+        removed.sort();
+        ListDiff { added, removed }
+    }
+
+    /// Applies a diff in place, without rebuilding the rest of the List
+    ///
+    /// Removals are applied before additions. Removing a rule that is not in
+    /// the List does nothing. Every added rule is checked with
+    /// `List::validate_rule` first, so on error the List is unchanged. The
+    /// default `*` rule is kept even if a diff removes it.
+    pub fn apply_diff(&mut self, diff: &ListDiff) -> io::Result<()> {
+        for rule in &diff.added {
+            List::validate_rule(rule).map_err(|err| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("{rule}: {err}"))
+            })?;
+        }
+
+        for rule in &diff.removed {
+            let (is_exception_rule, labels) = match rule.strip_prefix('!') {
+                Some(rule) => (true, rule),
+                None => (false, rule.as_str()),
+            };
+            if remove_from(&mut self.root, labels.rsplit('.'), is_exception_rule) {
+                self.duplicates.retain(|duplicate| duplicate != rule);
+            }
+        }
+        for rule in &diff.added {
+            self.append(rule)?;
+        }
+        if !self.has_prevailing_rule() {
+            self.append(PREVAILING_STAR_RULE)?;
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            added = diff.added.len(),
+            removed = diff.removed.len(),
+            "applied list diff"
+        );
+        Ok(())
+    }
+
+    /// Every rule in the List, exceptions with their `!`
+    fn rules(&self) -> HashSet<String> {
+        let mut rules = Vec::new();
+        query::collect_rules(&self.root, &mut Vec::new(), &mut rules);
+        rules.into_iter().collect()
+    }
+}
+
+/// Removes the rule whose labels, right to left, are `labels`, pruning nodes
+/// left empty; returns true if it was found
+fn remove_from<'a>(
+    node: &mut ListNode,
+    mut labels: impl Iterator<Item = &'a str>,
+    is_exception_rule: bool,
+) -> bool {
+    let Some(label) = labels.next() else {
+        let found = matches!(
+            node.leaf,
+            Some(ListLeaf { is_exception_rule: e, .. }) if e == is_exception_rule
+        );
+        if found {
+            node.leaf = None;
+        }
+        return found;
+    };
+
+    let Some(child) = node.children.get_mut(label) else {
+        return false;
+    };
+    let found = remove_from(child, labels, is_exception_rule);
+    if found && child.leaf.is_none() && child.children.is_empty() {
+        node.children.remove(label);
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_and_apply() {
+        let mut list = "com,uk,co.uk,*.sch.uk,!www.sch.uk,a.b.c"
+            .parse::<List>()
+            .unwrap();
+        let newer = "com,uk,co.uk,*.sch.uk,www.sch.uk,ac.uk"
+            .parse::<List>()
+            .unwrap();
+
+        let diff = list.diff(&newer);
+        assert_eq!(diff.added(), ["ac.uk", "www.sch.uk"]);
+        assert_eq!(diff.removed(), ["!www.sch.uk", "a.b.c"]);
+
+        list.apply_diff(&diff).unwrap();
+        assert!(list.diff(&newer).is_empty());
+        // the empty nodes for `a.b.c` are pruned
+        assert!(!list.root.children.contains_key("c"));
+        for name in ["www.sch.uk", "x.www.sch.uk", "a.ac.uk", "x.a.b.c"] {
+            let applied = list.parse_dns_name(name).unwrap();
+            let expected = newer.parse_dns_name(name).unwrap();
+            assert_eq!(applied.root(), expected.root());
+            assert_eq!(applied.suffix(), expected.suffix());
+        }
+    }
+
+    #[test]
+    fn apply() {
+        let mut list = "com,net".parse::<List>().unwrap();
+
+        let diff = ListDiff::new().add_rule("org").add_rule("a..b");
+        assert!(list.apply_diff(&diff).is_err());
+        assert_eq!(
+            list.parse_dns_name("a.b.org").unwrap().suffix(),
+            Some("org")
+        );
+        assert!(list.tlds().all(|tld| tld != "org"));
+
+        let diff = ListDiff::new()
+            .remove_rule("*")
+            .remove_rule("!com")
+            .remove_rule("gone");
+        list.apply_diff(&diff).unwrap();
+        assert!(list.has_prevailing_rule());
+        assert_eq!(list.parse_dns_name("a.com").unwrap().root(), Some("a.com"));
+
+        let duplicated = "com,com,net".parse::<List>().unwrap();
+        assert_eq!(duplicated.lint().len(), 1);
+        let mut duplicated = duplicated;
+        duplicated
+            .apply_diff(&ListDiff::new().remove_rule("com"))
+            .unwrap();
+        assert!(duplicated.lint().is_empty());
+    }
+}
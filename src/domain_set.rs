@@ -0,0 +1,167 @@
+//! Blocklist and allowlist matching
+
+use std::{collections::HashSet, io, io::BufRead};
+
+use crate::{escape, DnsName, List};
+
+/// How a name matched a `DomainSet`, from strongest to weakest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchKind {
+    /// the name is an entry
+    Exact,
+    /// the name is beneath an entry, or beneath the domain of a `*.` entry
+    Subdomain,
+    /// the name has the same root domain (eTLD+1) as an entry
+    Registrable,
+}
+
+/// Domains to match names against, such as a blocklist
+///
+/// An entry `example.com` matches itself and every name beneath it; an entry
+/// `*.example.com` matches only the names beneath `example.com`. Every entry
+/// also matches, more weakly, the names sharing its root domain, so
+/// `ads.example.com` reaches `cdn.example.com` at `MatchKind::Registrable`.
+/// Callers pick which kinds count.
+///
+/// ```rust
+/// # use dns_name::{DomainSet, List, MatchKind};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let blocklist = "# ads\nads.example.com\n*.tracker.co.uk\n";
+/// let set = DomainSet::from_buf_reader(blocklist.as_bytes(), &list).unwrap();
+///
+/// let kind = |name: &str| set.matches(&list.parse_dns_name(name).unwrap());
+/// assert_eq!(kind("ads.example.com"), Some(MatchKind::Exact));
+/// assert_eq!(kind("x.ads.example.com."), Some(MatchKind::Subdomain));
+/// assert_eq!(kind("cdn.example.com"), Some(MatchKind::Registrable));
+/// assert_eq!(kind("tracker.co.uk"), Some(MatchKind::Registrable));
+/// assert_eq!(kind("example.org"), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DomainSet {
+    /// entries without a trailing dot
+    exact: HashSet<String>,
+    /// domains of `*.` entries, without a trailing dot
+    wildcard: HashSet<String>,
+    /// root domains of the entries, or the entry itself if it has none
+    roots: HashSet<String>,
+}
+
+impl DomainSet {
+    /// Creates an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads entries one per line, using `list` to find their root domains
+    ///
+    /// Blank lines and lines starting with `#` or `//` are skipped, as is
+    /// anything after an entry's first whitespace. Fails on the first entry
+    /// that does not parse.
+    pub fn from_buf_reader<R: BufRead>(mut reader: R, list: &List) -> io::Result<DomainSet> {
+        let mut set = DomainSet::new();
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 {
+            let trimmed = line.trim();
+            if !trimmed.starts_with('#') && !trimmed.starts_with("//") {
+                if let Some(entry) = trimmed.split_whitespace().next() {
+                    set.insert(entry, list)?;
+                }
+            }
+            line.clear();
+        }
+        Ok(set)
+    }
+
+    /// Adds an entry, `example.com` or `*.example.com`
+    pub fn insert(&mut self, entry: &str, list: &List) -> io::Result<()> {
+        let (domain, wildcard) = match entry.strip_prefix("*.") {
+            Some(domain) => (domain, true),
+            None => (entry, false),
+        };
+        let name = list.parse_dns_name(domain)?;
+        let key = escape::trim_dot(name.name()).to_owned();
+        self.roots
+            .insert(name.root_relative().unwrap_or(&key).to_owned());
+        if wildcard {
+            self.wildcard.insert(key);
+        } else {
+            self.exact.insert(key);
+        }
+        Ok(())
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.exact.len() + self.wildcard.len()
+    }
+
+    /// Returns true if there are no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Finds the strongest way the name matches an entry
+    pub fn matches(&self, name: &DnsName) -> Option<MatchKind> {
+        let domain = escape::trim_dot(name.name());
+        if self.exact.contains(domain) {
+            return Some(MatchKind::Exact);
+        }
+        let below = escape::label_ranges(domain)
+            .into_iter()
+            .skip(1)
+            .map(|label| &domain[label.start..])
+            .any(|parent| self.exact.contains(parent) || self.wildcard.contains(parent));
+        if below {
+            return Some(MatchKind::Subdomain);
+        }
+        let root = name.root_relative().unwrap_or(domain);
+        self.roots.contains(root).then_some(MatchKind::Registrable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_kinds() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let mut set = DomainSet::new();
+        for entry in ["Example.com.", "*.b.c.kobe.jp", "co.uk", "ex\\.ample.org"] {
+            set.insert(entry, &list).unwrap();
+        }
+        assert_eq!(set.len(), 4);
+        assert!(set.insert("a..b", &list).is_err());
+
+        let kind = |name: &str| set.matches(&list.parse_dns_name(name).unwrap());
+        assert_eq!(kind("example.com"), Some(MatchKind::Exact));
+        assert_eq!(kind("EXAMPLE.com."), Some(MatchKind::Exact));
+        assert_eq!(kind("a.b.example.com"), Some(MatchKind::Subdomain));
+        assert_eq!(kind("notexample.com"), None);
+        assert_eq!(kind("com"), None);
+
+        assert_eq!(kind("x.b.c.kobe.jp"), Some(MatchKind::Subdomain));
+        assert_eq!(kind("b.c.kobe.jp"), Some(MatchKind::Registrable));
+        assert_eq!(kind("other.c.kobe.jp"), None);
+
+        // a public suffix entry has no root domain, it only covers itself and
+        // what is beneath it
+        assert_eq!(kind("example.co.uk"), Some(MatchKind::Subdomain));
+        assert_eq!(kind("uk"), None);
+
+        assert_eq!(kind("ex\\.ample.org"), Some(MatchKind::Exact));
+        assert_eq!(kind("ample.org"), None);
+    }
+
+    #[test]
+    fn from_buf_reader() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let data = "# comment\n// comment\n\nexample.com trailing words\n  *.example.net\n";
+        let set = DomainSet::from_buf_reader(data.as_bytes(), &list).unwrap();
+        assert_eq!(set.len(), 2);
+        assert!(DomainSet::from_buf_reader("a b\nx..y\n".as_bytes(), &list).is_err());
+        assert!(DomainSet::from_buf_reader("".as_bytes(), &list)
+            .unwrap()
+            .is_empty());
+    }
+}
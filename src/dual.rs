@@ -0,0 +1,244 @@
+//! Parsing a name under the ICANN section and the whole list at once
+
+use std::io;
+
+use crate::{
+    escape, spec::Rule, Algorithm, DnsName, List, ListNode, ParseOptions, Prepared, Scratch, Split,
+    PREVAILING_STAR_RULE,
+};
+
+/// A name parsed under both the ICANN section of a list and the whole list,
+/// private domains included
+///
+/// This is created by `List::parse_dual`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DualDnsName {
+    /// The name split by the ICANN rules only, as for grouping names by
+    /// organization
+    pub icann: DnsName,
+    /// The name split by every rule, as for cookie scoping
+    pub all: DnsName,
+}
+
+impl DualDnsName {
+    /// Returns true if a private domain rule gave the name a different
+    /// suffix
+    pub fn differs(&self) -> bool {
+        self.icann.suffix() != self.all.suffix()
+    }
+}
+
+impl List {
+    /// Parses a DNS name under the ICANN rules and under every rule, in one
+    /// walk of the list
+    ///
+    /// Rules count as private between the `===BEGIN PRIVATE DOMAINS===` and
+    /// `===END PRIVATE DOMAINS===` comments of a publicsuffix.org list; every
+    /// other rule, including those added with `ListBuilder::add_rules`, counts
+    /// as ICANN. Parses made this way are not reported to a `ParseObserver`.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let data = "com\n// ===BEGIN PRIVATE DOMAINS===\nblogspot.com\n";
+    /// let list = List::from_buf_reader(data.as_bytes()).unwrap();
+    /// let dual = list.parse_dual("www.example.blogspot.com").unwrap();
+    /// assert_eq!(dual.icann.root(), Some("blogspot.com"));
+    /// assert_eq!(dual.all.root(), Some("example.blogspot.com"));
+    /// assert!(dual.differs());
+    /// ```
+    pub fn parse_dual(&self, domain: &str) -> io::Result<DualDnsName> {
+        self.parse_dual_with(domain, &ParseOptions::default())
+    }
+
+    /// Parses a DNS name under the ICANN rules and under every rule, with
+    /// extra checks enabled by `options`
+    pub fn parse_dual_with(&self, domain: &str, options: &ParseOptions) -> io::Result<DualDnsName> {
+        let (input, port) = crate::host::strip_decoration(domain, options)?;
+        let mut scratch = Scratch::default();
+        let (mut icann, mut all) = match DnsName::prepare(input, options, &mut scratch)? {
+            Prepared::Done(split) => (split.clone(), split),
+            Prepared::Labels {
+                input,
+                all_labels,
+                labels,
+                decoded,
+            } => {
+                let name = escape::trim_dot(input);
+                let label = |i: usize| &name[labels[labels.len() - 1 - i].clone()];
+                let (all, icann) = match options.algorithm {
+                    Algorithm::Legacy => self.longest_rules(labels.len(), label),
+                    Algorithm::PslSpec => self.prevailing_rules(labels.len(), label),
+                };
+                let split = |rule| {
+                    let split = DnsName::split_at(input, all_labels, labels, rule, options)?;
+                    Ok::<_, io::Error>(Split {
+                        percent_decoded: decoded,
+                        ..split
+                    })
+                };
+                (split(icann)?, split(all)?)
+            }
+        };
+        icann.port = port;
+        all.port = port;
+        Ok(DualDnsName {
+            icann: icann.into_name().with_original(domain),
+            all: all.into_name().with_original(domain),
+        })
+    }
+
+    /// Finds the longest rule as `longest_rule` does, both among all rules
+    /// and as a list without the private domains section would
+    fn longest_rules<'a, 'b>(
+        &'a self,
+        len: usize,
+        label: impl Fn(usize) -> &'b str,
+    ) -> (Option<Rule<'a>>, Option<Rule<'a>>) {
+        let mut all = Walk::new(&self.root, false);
+        let mut icann = Walk::new(&self.root, true);
+        for depth in 0..len {
+            let label = label(depth);
+            all.step(label);
+            icann.step(label);
+        }
+        (all.longest, icann.longest)
+    }
+}
+
+/// A walk down the list for `List::longest_rules`
+struct Walk<'a> {
+    node: Option<&'a ListNode>,
+    depth: usize,
+    wildcard: bool,
+    longest: Option<Rule<'a>>,
+    /// whether to walk as if the private domains section were left out
+    icann: bool,
+}
+
+impl<'a> Walk<'a> {
+    fn new(root: &'a ListNode, icann: bool) -> Self {
+        Walk {
+            node: Some(root),
+            depth: 0,
+            wildcard: false,
+            longest: None,
+            icann,
+        }
+    }
+
+    /// Takes the exact label if there is one, else the wildcard, and
+    /// records the rule reached
+    fn step(&mut self, label: &str) {
+        let Some(node) = self.node else {
+            return;
+        };
+        let exact = node.children.get(label);
+        let star = node.children.get(PREVAILING_STAR_RULE);
+        self.node = match (exact, star) {
+            // without the private section the label would not be listed
+            (Some(exact), Some(star)) if self.icann && !has_icann(exact) => {
+                self.wildcard = true;
+                Some(star)
+            }
+            (Some(exact), _) => Some(exact),
+            (None, Some(star)) => {
+                self.wildcard = true;
+                Some(star)
+            }
+            (None, None) => None,
+        };
+        self.depth += 1;
+
+        if let Some(leaf) = self.node.and_then(|node| node.leaf.as_ref()) {
+            if !(self.icann && leaf.private) {
+                self.longest = Some((leaf, self.depth, self.wildcard));
+            }
+        }
+    }
+}
+
+/// Returns true if any rule at or below `node` is outside the private
+/// domains section
+fn has_icann(node: &ListNode) -> bool {
+    node.leaf.as_ref().is_some_and(|leaf| !leaf.private) || node.children.values().any(has_icann)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ListBuilder;
+
+    const PSL: &str = "\
+// ===BEGIN ICANN DOMAINS===
+com
+uk
+co.uk
+*.ck
+!www.ck
+// ===END ICANN DOMAINS===
+
+// ===BEGIN PRIVATE DOMAINS===
+blogspot.com
+*.compute.amazonaws.com
+foo.ck
+*.bar.ck
+!www.ck
+// ===END PRIVATE DOMAINS===
+";
+
+    #[test]
+    fn same_as_two_lists() {
+        let full = ListBuilder::new()
+            .from_bytes(PSL.as_bytes())
+            .build()
+            .unwrap();
+        let icann = ListBuilder::new()
+            .from_bytes(PSL.as_bytes())
+            .include_private(false)
+            .build()
+            .unwrap();
+
+        for algorithm in [Algorithm::Legacy, Algorithm::PslSpec] {
+            let options = ParseOptions::new().algorithm(algorithm);
+            for name in [
+                ".",
+                "com",
+                "www.example.com.",
+                "a.b.blogspot.com",
+                "x.y.compute.amazonaws.com",
+                "www.example.co.uk",
+                "a.b.foo.ck",
+                "a.b.bar.ck",
+                "a.www.ck",
+                "www.example.nope",
+            ] {
+                let dual = full.parse_dual_with(name, &options).unwrap();
+                let parse = |list: &List| list.parse_dns_name_with(name, &options).unwrap();
+                let (expected_icann, expected_all) = (parse(&icann), parse(&full));
+                assert_eq!(
+                    (dual.icann.suffix(), dual.icann.root()),
+                    (expected_icann.suffix(), expected_icann.root()),
+                    "{name} {algorithm:?}"
+                );
+                assert_eq!(
+                    (dual.all.suffix(), dual.all.root()),
+                    (expected_all.suffix(), expected_all.root()),
+                    "{name} {algorithm:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn decorated() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let options = ParseOptions::new().port_suffix(true);
+        let dual = list
+            .parse_dual_with("WWW.example.com:443", &options)
+            .unwrap();
+        assert!(!dual.differs());
+        assert_eq!(dual.icann.port(), Some(443));
+        assert_eq!(dual.all.original(), "WWW.example.com:443");
+        assert!(list.parse_dual("a..com").is_err());
+    }
+}
@@ -0,0 +1,141 @@
+//! Detailed parse errors
+
+use std::{error::Error, fmt, io};
+
+/// Why a name was rejected
+///
+/// Parsing returns `io::Error` for compatibility; errors raised by the checks
+/// enabled through `ParseOptions` carry one of these, which can be recovered
+/// with `ParseError::from_io`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// A label is longer than 63 octets
+    LabelTooLong { index: usize, len: usize },
+    /// The name is longer than 255 octets in wire format (253 characters in
+    /// presentation format without escapes)
+    NameTooLong { len: usize },
+    /// A label is empty or not allowed by the validation profile
+    ///
+    /// `index` counts labels from the left and `offset` is the byte where
+    /// the problem is. A label with escapes is blamed at its start.
+    ///
+    /// Both are positions in the name as `DnsName::name` would give it, not
+    /// in the input: a leading dot allowed by `ParseOptions::leading_dot` is
+    /// dropped, escapes are canonicalized and `%XX` escapes are replaced by
+    /// what `ParseOptions::percent_decoding` decodes them to. For other input
+    /// the two are the same.
+    InvalidLabel {
+        index: usize,
+        offset: usize,
+        reason: LabelError,
+    },
+    /// The name has a single label (`localhost`, `com`) and dotless names
+    /// were rejected
+    DotlessDomain,
+    /// The last label is all digits, which no top-level domain can be, and
+    /// numeric TLDs were rejected
+    NumericTld,
+    /// The input is empty and was not taken as the root name
+    EmptyName,
+    /// The name has more labels than `ParseOptions::max_labels` allows
+    TooManyLabels { count: usize, max: usize },
+    /// The name has more labels left of its root domain than
+    /// `ParseOptions::max_depth` allows
+    TooDeep { depth: usize, max: usize },
+    /// The input is an IP address literal and `IpPolicy::Reject` was set
+    IpLiteral,
+}
+
+/// What is wrong with a label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LabelError {
+    /// The label contains a character the profile does not allow
+    InvalidCharacter(char),
+    /// The label starts with a hyphen
+    LeadingHyphen,
+    /// The label ends with a hyphen
+    TrailingHyphen,
+    /// Hyphens in the third and fourth positions are reserved for `xn--`
+    ReservedHyphens,
+    /// An `xn--` label is not a valid IDNA A-label
+    InvalidPunycode,
+    /// Underscore-prefixed labels were rejected
+    UnderscoreLabel,
+    /// The label is empty, as between the dots of `a..b`
+    Empty,
+}
+
+impl fmt::Display for LabelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LabelError::InvalidCharacter(c) => write!(f, "invalid character {c:?}"),
+            LabelError::LeadingHyphen => f.write_str("starts with a hyphen"),
+            LabelError::TrailingHyphen => f.write_str("ends with a hyphen"),
+            LabelError::ReservedHyphens => f.write_str("hyphens in the third and fourth positions"),
+            LabelError::InvalidPunycode => f.write_str("invalid punycode"),
+            LabelError::UnderscoreLabel => f.write_str("starts with an underscore"),
+            LabelError::Empty => f.write_str("empty"),
+        }
+    }
+}
+
+impl ParseError {
+    /// Gets the `ParseError` carried by an `io::Error`, if any
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseError, ParseOptions};
+    /// let list = List::empty();
+    /// let options = ParseOptions::new().strict_lengths(true);
+    /// let err = list.parse_dns_name_with(&"a".repeat(64), &options).unwrap_err();
+    /// assert_eq!(
+    ///     ParseError::from_io(&err),
+    ///     Some(&ParseError::LabelTooLong { index: 0, len: 64 })
+    /// );
+    /// ```
+    pub fn from_io(err: &io::Error) -> Option<&ParseError> {
+        err.get_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::LabelTooLong { index, len } => {
+                write!(f, "label {index} is {len} octets, more than 63")
+            }
+            ParseError::NameTooLong { len } => {
+                write!(f, "name is {len} octets, more than 255")
+            }
+            ParseError::InvalidLabel {
+                index,
+                offset,
+                reason,
+            } => {
+                write!(f, "label {index} is invalid at byte {offset}: {reason}")
+            }
+            ParseError::DotlessDomain => f.write_str("name has a single label"),
+            ParseError::NumericTld => f.write_str("top-level label is numeric"),
+            ParseError::EmptyName => f.write_str("name is empty"),
+            ParseError::TooManyLabels { count, max } => {
+                write!(f, "name has {count} labels, more than {max}")
+            }
+            ParseError::IpLiteral => f.write_str("name is an IP address literal"),
+            ParseError::TooDeep { depth, max } => {
+                write!(
+                    f,
+                    "name is {depth} labels below its root domain, more than {max}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<ParseError> for io::Error {
+    fn from(err: ParseError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, err)
+    }
+}
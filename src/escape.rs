@@ -0,0 +1,336 @@
+//! Presentation format escapes (RFC 1035 section 5.1)
+//!
+//! A label may contain any octet: `\.` is a literal dot, `\\` a literal
+//! backslash, `\DDD` the octet with decimal value `DDD`, and `\X` any other
+//! character `X`. Names are split into labels on unescaped dots only.
+
+use std::{borrow::Cow, io, ops::Range};
+
+use crate::DnsName;
+
+fn invalid(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+/// Finds the byte index of the first unescaped dot at or after `from`
+fn find_dot(name: &str, from: usize) -> Option<usize> {
+    let bytes = name.as_bytes();
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'.' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Returns true if the byte at `index` is escaped by a preceding backslash
+pub(crate) fn is_escaped(name: &str, index: usize) -> bool {
+    let backslashes = name.as_bytes()[..index]
+        .iter()
+        .rev()
+        .take_while(|&&b| b == b'\\')
+        .count();
+    backslashes % 2 == 1
+}
+
+/// Strips every unescaped trailing dot
+pub(crate) fn trim_dot(name: &str) -> &str {
+    let mut name = name;
+    while name.ends_with('.') && !is_escaped(name, name.len() - 1) {
+        name = &name[..name.len() - 1];
+    }
+    name
+}
+
+/// Splits off the first label, returning it and the rest of the name
+pub(crate) fn split_first_label(name: &str) -> Option<(&str, &str)> {
+    find_dot(name, 0).map(|i| (&name[..i], &name[i + 1..]))
+}
+
+/// Byte ranges of each label of a name without its trailing dot
+pub(crate) fn label_ranges(domain: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    label_ranges_into(domain, &mut ranges);
+    ranges
+}
+
+/// Like `label_ranges`, replacing the contents of `ranges`
+pub(crate) fn label_ranges_into(domain: &str, ranges: &mut Vec<Range<usize>>) {
+    ranges.clear();
+    let mut start = 0;
+    while let Some(dot) = find_dot(domain, start) {
+        ranges.push(start..dot);
+        start = dot + 1;
+    }
+    ranges.push(start..domain.len());
+}
+
+/// Decodes the escapes of a single label into its octets
+pub(crate) fn unescape(label: &str) -> io::Result<Cow<'_, [u8]>> {
+    if !label.contains('\\') {
+        return Ok(Cow::Borrowed(label.as_bytes()));
+    }
+
+    let bytes = label.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+
+        match bytes.get(i + 1) {
+            None => return Err(invalid("name ends with an escape")),
+            Some(b'0'..=b'9') => {
+                let digits = bytes
+                    .get(i + 1..i + 4)
+                    .filter(|digits| digits.iter().all(u8::is_ascii_digit))
+                    .ok_or_else(|| invalid("escape needs three digits"))?;
+                let value = digits
+                    .iter()
+                    .fold(0u16, |acc, d| acc * 10 + u16::from(d - b'0'));
+                let value = u8::try_from(value).map_err(|_| invalid("escape out of range"))?;
+                out.push(value);
+                i += 4;
+            }
+            Some(_) => {
+                // `\X` is X, which may be the start of a multi-byte character
+                let c = label[i + 1..].chars().next().unwrap_or_default();
+                let mut buf = [0; 4];
+                out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                i += 1 + c.len_utf8();
+            }
+        }
+    }
+    Ok(Cow::Owned(out))
+}
+
+/// Appends a label in presentation format
+///
+/// Dots and backslashes are escaped, as are control characters, space and
+/// DEL. With `ascii_only`, or if the label is not valid UTF-8, every octet
+/// above 127 is written as `\DDD` too.
+pub(crate) fn escape(label: &[u8], ascii_only: bool, out: &mut String) {
+    let push_octet = |out: &mut String, b: u8| match b {
+        b'.' | b'\\' => {
+            out.push('\\');
+            out.push(char::from(b));
+        }
+        0x21..=0x7e => out.push(char::from(b)),
+        _ => out.push_str(&format!("\\{b:03}")),
+    };
+
+    match std::str::from_utf8(label) {
+        Ok(text) if !ascii_only => {
+            for c in text.chars() {
+                if c.is_ascii() {
+                    push_octet(out, c as u8);
+                } else {
+                    out.push(c);
+                }
+            }
+        }
+        _ => label.iter().for_each(|&b| push_octet(out, b)),
+    }
+}
+
+/// Converts a name to a string, writing each byte that is not part of valid
+/// UTF-8 as a `\DDD` escape
+pub(crate) fn escape_invalid_utf8(name: &[u8]) -> String {
+    let mut out = String::with_capacity(name.len());
+    for chunk in name.utf8_chunks() {
+        out.push_str(chunk.valid());
+        for b in chunk.invalid() {
+            out.push_str(&format!("\\{b:03}"));
+        }
+    }
+    out
+}
+
+/// Decodes the `%XX` escapes of a name into `out`, returning true if there
+/// were any
+///
+/// Decoded octets are written in presentation format so they cannot end a
+/// label or start an escape: dots and backslashes are escaped, and octets
+/// that are not UTF-8 become `\DDD`.
+pub(crate) fn percent_decode_into(name: &str, out: &mut String) -> bool {
+    let hex = |b: Option<&u8>| char::from(*b?).to_digit(16);
+    let bytes = name.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut any = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let octet = (bytes[i] == b'%')
+            .then(|| Some(hex(bytes.get(i + 1))? << 4 | hex(bytes.get(i + 2))?))
+            .flatten();
+        match octet.map(|octet| octet as u8) {
+            Some(octet @ (b'.' | b'\\')) => {
+                decoded.extend_from_slice(&[b'\\', octet]);
+                i += 3;
+            }
+            Some(octet) => {
+                decoded.push(octet);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+        any |= octet.is_some();
+    }
+    out.push_str(&escape_invalid_utf8(&decoded));
+    any
+}
+
+/// Rewrites a name with escapes into its canonical presentation format
+///
+/// Escaped ASCII letters are lowercased, printable characters are unescaped,
+/// and everything else uses the escapes produced by `escape`.
+pub(crate) fn canonicalize(name: &str) -> io::Result<String> {
+    let domain = trim_dot(name);
+    let mut out = String::with_capacity(name.len());
+    for (i, range) in label_ranges(domain).into_iter().enumerate() {
+        if i > 0 {
+            out.push('.');
+        }
+        let mut label = unescape(&domain[range])?.into_owned();
+        label.make_ascii_lowercase();
+        escape(&label, false, &mut out);
+    }
+    out.push_str(&name[domain.len()..]);
+    Ok(out)
+}
+
+impl DnsName {
+    /// Get the DNS name in strict ASCII presentation format
+    ///
+    /// `name()` keeps UTF-8 text as it is, while this escapes every octet
+    /// outside printable ASCII as `\DDD`, as zone files expect.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::empty();
+    /// let name = list.parse_dns_name("b\\.c\\001.bücher.de").unwrap();
+    /// assert_eq!(name.name(), "b\\.c\\001.bücher.de");
+    /// assert_eq!(name.to_escaped(), "b\\.c\\001.b\\195\\188cher.de");
+    /// ```
+    pub fn to_escaped(&self) -> String {
+        let domain = trim_dot(self.name());
+        if domain.is_empty() {
+            return self.name().to_string();
+        }
+
+        let mut out = String::with_capacity(self.name().len());
+        for (i, range) in label_ranges(domain).into_iter().enumerate() {
+            if i > 0 {
+                out.push('.');
+            }
+            // names are validated when parsed, so escapes are well formed
+            let label = unescape(&domain[range]).unwrap_or_default();
+            escape(&label, true, &mut out);
+        }
+        out.push_str(&self.name()[domain.len()..]);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels() {
+        let ranges = |name: &str| {
+            label_ranges(name)
+                .into_iter()
+                .map(|r| name[r].to_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ranges("a.b.c"), ["a", "b", "c"]);
+        assert_eq!(ranges("a\\.b.c"), ["a\\.b", "c"]);
+        assert_eq!(ranges("a\\\\.b"), ["a\\\\", "b"]);
+        assert_eq!(trim_dot("a.b."), "a.b");
+        assert_eq!(trim_dot("a.b\\."), "a.b\\.");
+        assert_eq!(trim_dot("a.b\\\\."), "a.b\\\\");
+        assert_eq!(split_first_label("a\\.b.c.d"), Some(("a\\.b", "c.d")));
+    }
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(&*unescape("a\\.b").unwrap(), b"a.b");
+        assert_eq!(&*unescape("\\050\\A\\\\").unwrap(), b"2A\\");
+        assert_eq!(&*unescape("\\é").unwrap(), "é".as_bytes());
+        assert!(unescape("a\\").is_err());
+        assert!(unescape("\\05").is_err());
+        assert!(unescape("\\05x").is_err());
+        assert!(unescape("\\256").is_err());
+
+        let mut out = String::new();
+        escape(b"a.b\\c d\x00\xff", false, &mut out);
+        assert_eq!(out, "a\\.b\\\\c\\032d\\000\\255");
+        out.clear();
+        escape("bücher".as_bytes(), true, &mut out);
+        assert_eq!(out, "b\\195\\188cher");
+
+        assert_eq!(
+            canonicalize("\\070oo\\.bar\\046.ex\\ample.").unwrap(),
+            "foo\\.bar\\..example."
+        );
+    }
+
+    #[test]
+    fn percent() {
+        let decode = |name| {
+            let mut out = String::new();
+            let any = percent_decode_into(name, &mut out);
+            (out, any)
+        };
+        assert_eq!(decode("ex%61mple.com"), ("example.com".into(), true));
+        assert_eq!(decode("a%2eb%5Cc"), ("a\\.b\\\\c".into(), true));
+        assert_eq!(decode("b%C3%BCcher%ff"), ("bücher\\255".into(), true));
+        assert_eq!(decode("100%.com%4"), ("100%.com%4".into(), false));
+
+        let list = crate::List::from_path("suffix-list.txt").unwrap();
+        let options = crate::ParseOptions::new().percent_decoding(true);
+        let name = list.parse_dns_name_with("A%5C.b%2Ecom", &options).unwrap();
+        assert_eq!(name.name(), "a\\\\.b\\.com");
+        assert_eq!(name.suffix(), Some("b\\.com"));
+        assert_eq!(name.original(), "A%5C.b%2Ecom");
+        let name = list.parse_dns_name_with("example.com", &options).unwrap();
+        assert!(!name.was_percent_decoded());
+        // without the option `%` is just another character
+        let name = list.parse_dns_name("ex%61mple.com").unwrap();
+        assert_eq!(name.name(), "ex%61mple.com");
+        assert!(!name.was_percent_decoded());
+    }
+
+    #[test]
+    fn parse_escaped() {
+        let list = crate::List::from_path("suffix-list.txt").unwrap();
+
+        let name = list.parse_dns_name("Foo\\.bar.example.co.uk").unwrap();
+        assert_eq!(name.name(), "foo\\.bar.example.co.uk");
+        assert_eq!(name.root(), Some("example.co.uk"));
+        assert_eq!(name.subdomain(), Some("foo\\.bar"));
+
+        // an escaped dot does not end a label
+        let name = list.parse_dns_name("example.co\\.uk\\.").unwrap();
+        assert_eq!(name.suffix(), Some("co\\.uk\\."));
+        assert!(!name.is_fqdn());
+
+        let name = list.parse_dns_name("\\050.\\069xample.com.").unwrap();
+        assert_eq!(name.name(), "2.example.com.");
+        assert_eq!(name.root(), Some("example.com."));
+
+        let name = list.parse_dns_name("a\\032b.example.com").unwrap();
+        assert_eq!(name.to_escaped(), "a\\032b.example.com");
+        assert!(list.parse_dns_name("example.com\\").is_err());
+        assert!(list.parse_dns_name("\\300.com").is_err());
+    }
+}
@@ -0,0 +1,237 @@
+//! Step by step account of how a name was matched against a list
+
+use std::{fmt, io, ops::Range};
+
+use crate::{escape, DnsName, List, ListNode};
+
+/// How a label was looked up in the list's trie
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lookup {
+    /// A rule has this exact label
+    Label,
+    /// No rule has this label, but a wildcard `*` covers it
+    Wildcard,
+    /// Neither matched, so the walk stopped here
+    NotFound,
+}
+
+/// One label examined while matching, from `Explanation::steps`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Step {
+    label: String,
+    lookup: Lookup,
+    rule: Option<String>,
+}
+
+impl Step {
+    /// The label of the name
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// How the label was found
+    pub fn lookup(&self) -> Lookup {
+        self.lookup
+    }
+
+    /// The rule ending at this label, if the list has one: a candidate for
+    /// the longest match
+    pub fn rule(&self) -> Option<&str> {
+        self.rule.as_deref()
+    }
+}
+
+/// Why a name was given its suffix, from `List::explain`
+///
+/// Its `Display` prints the trace for a person to read.
+///
+/// ```rust
+/// # use dns_name::{List, Lookup};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let explanation = list.explain("www.city.kobe.jp").unwrap();
+/// assert_eq!(
+///     explanation.candidates().collect::<Vec<_>>(),
+///     ["jp", "!city.kobe.jp"]
+/// );
+/// assert_eq!(explanation.rule(), Some("!city.kobe.jp"));
+/// assert_eq!(explanation.steps()[2].lookup(), Lookup::Label);
+/// assert_eq!(explanation.name().suffix(), Some("kobe.jp"));
+/// println!("{explanation}");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Explanation {
+    name: DnsName,
+    steps: Vec<Step>,
+}
+
+impl Explanation {
+    /// The parsed name
+    pub fn name(&self) -> &DnsName {
+        &self.name
+    }
+
+    /// The labels examined, from right to left
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Every rule the walk passed, shortest first
+    pub fn candidates(&self) -> impl Iterator<Item = &str> {
+        self.steps.iter().filter_map(Step::rule)
+    }
+
+    /// The rule that decided the suffix: the longest candidate
+    ///
+    /// `None` when no rule matched, as with an empty list or the root name.
+    pub fn rule(&self) -> Option<&str> {
+        self.candidates().last()
+    }
+
+    /// Byte range of the suffix in the name, without the trailing dot
+    pub fn suffix_range(&self) -> Option<Range<usize>> {
+        self.name.suffix_range()
+    }
+
+    /// Byte range of the root domain in the name, without the trailing dot
+    pub fn root_range(&self) -> Option<Range<usize>> {
+        self.name.root_range()
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "name: {}", self.name.name())?;
+        for step in &self.steps {
+            let label = &step.label;
+            match (step.lookup, &step.rule) {
+                (Lookup::NotFound, _) => writeln!(f, "  {label}: no rule, stop")?,
+                (Lookup::Label, None) => writeln!(f, "  {label}: no rule ends here")?,
+                (Lookup::Wildcard, None) => writeln!(f, "  {label}: via *, no rule ends here")?,
+                (Lookup::Label, Some(rule)) => writeln!(f, "  {label}: rule {rule}")?,
+                (Lookup::Wildcard, Some(rule)) => writeln!(f, "  {label}: via *, rule {rule}")?,
+            }
+        }
+        match self.rule() {
+            Some(rule) if rule.starts_with('!') => {
+                writeln!(f, "rule: {rule} (exception, drops its leftmost label)")?
+            }
+            Some(rule) => writeln!(f, "rule: {rule}")?,
+            None => writeln!(f, "rule: none")?,
+        }
+        writeln!(f, "suffix: {}", self.name.suffix().unwrap_or("none"))?;
+        write!(f, "root: {}", self.name.root().unwrap_or("none"))
+    }
+}
+
+impl List {
+    /// Parses a name and records each decision made along the way
+    ///
+    /// This follows the same walk as `List::parse_dns_name`: labels are
+    /// looked up right to left, falling back to a wildcard, until one is
+    /// not found. The longest rule passed wins, and an exception rule
+    /// removes its leftmost label from the suffix.
+    pub fn explain(&self, domain: &str) -> io::Result<Explanation> {
+        let name = self.parse_dns_name(domain)?;
+        let domain = escape::trim_dot(name.name());
+
+        let mut steps = Vec::new();
+        let mut keys = Vec::new();
+        let mut node = &self.root;
+        // the root name has no labels to look up
+        let labels = match domain {
+            "" => Vec::new(),
+            domain => escape::label_ranges(domain),
+        };
+        for label in labels.into_iter().rev() {
+            let label = &domain[label];
+            let (key, lookup) = if node.children.contains_key(label) {
+                (label, Lookup::Label)
+            } else if node.children.contains_key("*") {
+                ("*", Lookup::Wildcard)
+            } else {
+                steps.push(Step {
+                    label: label.to_owned(),
+                    lookup: Lookup::NotFound,
+                    rule: None,
+                });
+                break;
+            };
+
+            node = &node.children[key];
+            keys.push(key);
+            steps.push(Step {
+                label: label.to_owned(),
+                lookup,
+                rule: rule_at(node, &keys),
+            });
+        }
+
+        Ok(Explanation { name, steps })
+    }
+}
+
+/// The rule ending at `node`, whose trie keys are `keys` in reverse order
+fn rule_at(node: &ListNode, keys: &[&str]) -> Option<String> {
+    let leaf = node.leaf.as_ref()?;
+    let rule = keys.iter().rev().copied().collect::<Vec<_>>().join(".");
+    Some(if leaf.is_exception_rule {
+        format!("!{rule}")
+    } else {
+        rule
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explain() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+
+        let explanation = list.explain("WWW.Example.co.uk.").unwrap();
+        let steps = explanation
+            .steps()
+            .iter()
+            .map(|step| (step.label(), step.lookup(), step.rule()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            steps,
+            [
+                ("uk", Lookup::Label, Some("uk")),
+                ("co", Lookup::Label, Some("co.uk")),
+                ("example", Lookup::NotFound, None),
+            ]
+        );
+        assert_eq!(explanation.rule(), Some("co.uk"));
+        assert_eq!(explanation.suffix_range(), Some(12..17));
+        assert_eq!(explanation.root_range(), Some(4..17));
+        assert_eq!(
+            explanation.to_string(),
+            "name: www.example.co.uk.\n\
+             \x20 uk: rule uk\n\
+             \x20 co: rule co.uk\n\
+             \x20 example: no rule, stop\n\
+             rule: co.uk\n\
+             suffix: co.uk.\n\
+             root: example.co.uk."
+        );
+
+        // only the default rule
+        let explanation = list.explain("a.b.invalid").unwrap();
+        assert_eq!(explanation.rule(), Some("*"));
+        assert_eq!(explanation.steps()[0].lookup(), Lookup::Wildcard);
+        assert_eq!(explanation.steps().len(), 2);
+
+        // the rule agrees with `longest_suffix_match`
+        for name in ["a.b.c.kobe.jp", "www.city.kobe.jp", "x.y.example.com"] {
+            let found = list.longest_suffix_match(name).unwrap();
+            assert_eq!(list.explain(name).unwrap().rule(), Some(&*found.rule()));
+        }
+
+        let explanation = List::empty().explain("example.com").unwrap();
+        assert_eq!(explanation.rule(), None);
+        assert_eq!(list.explain(".").unwrap().steps(), []);
+        assert!(list.explain("a..b").is_err());
+    }
+}
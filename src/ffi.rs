@@ -0,0 +1,255 @@
+//! C interface
+//!
+//! Lists and names are opaque pointers owned by the caller, created by
+//! `dns_name_list_load` and `dns_name_parse` and released with
+//! `dns_name_list_free` and `dns_name_free`. Failures return `NULL`.
+//!
+//! Accessors copy into a caller supplied buffer, `snprintf` style: they
+//! write at most `len - 1` bytes and a terminating NUL, and return the full
+//! length of the part, or -1 if the name has no such part. Names never
+//! contain NUL bytes, as those are escaped as `\000`.
+//!
+//! The C declarations are in `include/dns_name.h`. Build a static or shared
+//! library with `cargo rustc --release --features ffi --crate-type staticlib`
+//! (or `cdylib`).
+
+use std::{
+    ffi::{c_char, CStr},
+    ptr,
+};
+
+use crate::{DnsName, List};
+
+/// Converts a C string, returning `None` for NULL or invalid UTF-8
+///
+/// # Safety
+///
+/// `s` must be NULL or a valid NUL terminated string.
+unsafe fn to_str<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        return None;
+    }
+    CStr::from_ptr(s).to_str().ok()
+}
+
+/// Copies `part` into `buf`, see the module documentation
+///
+/// # Safety
+///
+/// `buf` must be NULL or valid for `len` bytes.
+unsafe fn copy_out(part: Option<&str>, buf: *mut c_char, len: usize) -> isize {
+    let Some(part) = part else {
+        return -1;
+    };
+    if !buf.is_null() && len > 0 {
+        let n = part.len().min(len - 1);
+        ptr::copy_nonoverlapping(part.as_ptr().cast(), buf, n);
+        *buf.add(n) = 0;
+    }
+    isize::try_from(part.len()).unwrap_or(isize::MAX)
+}
+
+/// Loads a list from a file
+///
+/// Returns NULL if the path is NULL or invalid, or the file cannot be read
+/// as a list.
+///
+/// # Safety
+///
+/// `path` must be NULL or a valid NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn dns_name_list_load(path: *const c_char) -> *mut List {
+    match to_str(path).map(List::from_path) {
+        Some(Ok(list)) => Box::into_raw(Box::new(list)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Builds a list from comma separated rules
+///
+/// # Safety
+///
+/// `rules` must be NULL or a valid NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn dns_name_list_from_rules(rules: *const c_char) -> *mut List {
+    match to_str(rules).map(str::parse::<List>) {
+        Some(Ok(list)) => Box::into_raw(Box::new(list)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Frees a list; NULL is ignored
+///
+/// # Safety
+///
+/// `list` must be NULL or a list returned by this library that has not
+/// been freed. Names parsed from it stay valid.
+#[no_mangle]
+pub unsafe extern "C" fn dns_name_list_free(list: *mut List) {
+    if !list.is_null() {
+        drop(Box::from_raw(list));
+    }
+}
+
+/// Parses a name using a list
+///
+/// Returns NULL if either argument is NULL or the name is invalid. A list
+/// may be used from several threads at once.
+///
+/// # Safety
+///
+/// `list` must be NULL or a live list, and `name` NULL or a valid NUL
+/// terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn dns_name_parse(list: *const List, name: *const c_char) -> *mut DnsName {
+    let (Some(list), Some(name)) = (list.as_ref(), to_str(name)) else {
+        return ptr::null_mut();
+    };
+    match list.parse_dns_name(name) {
+        Ok(name) => Box::into_raw(Box::new(name)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Frees a name; NULL is ignored
+///
+/// # Safety
+///
+/// `name` must be NULL or a name returned by `dns_name_parse` that has not
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn dns_name_free(name: *mut DnsName) {
+    if !name.is_null() {
+        drop(Box::from_raw(name));
+    }
+}
+
+/// Copies the full name
+///
+/// # Safety
+///
+/// `name` must be NULL or a live name, and `buf` NULL or valid for `len`
+/// bytes.
+#[no_mangle]
+pub unsafe extern "C" fn dns_name_name(
+    name: *const DnsName,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    copy_out(name.as_ref().map(DnsName::name), buf, len)
+}
+
+/// Copies the root domain (eTLD+1)
+///
+/// # Safety
+///
+/// As `dns_name_name`.
+#[no_mangle]
+pub unsafe extern "C" fn dns_name_root(
+    name: *const DnsName,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    copy_out(name.as_ref().and_then(DnsName::root), buf, len)
+}
+
+/// Copies the public suffix
+///
+/// # Safety
+///
+/// As `dns_name_name`.
+#[no_mangle]
+pub unsafe extern "C" fn dns_name_suffix(
+    name: *const DnsName,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    copy_out(name.as_ref().and_then(DnsName::suffix), buf, len)
+}
+
+/// Copies the registrable label
+///
+/// # Safety
+///
+/// As `dns_name_name`.
+#[no_mangle]
+pub unsafe extern "C" fn dns_name_registrable(
+    name: *const DnsName,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    copy_out(name.as_ref().and_then(DnsName::registrable), buf, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    fn part(
+        f: unsafe extern "C" fn(*const DnsName, *mut c_char, usize) -> isize,
+        name: *const DnsName,
+    ) -> Option<String> {
+        let mut buf = [0 as c_char; 64];
+        let len = unsafe { f(name, buf.as_mut_ptr(), buf.len()) };
+        let len = usize::try_from(len).ok()?;
+        let bytes = buf[..len].iter().map(|&c| c as u8).collect();
+        Some(String::from_utf8(bytes).unwrap())
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = CString::new("suffix-list.txt").unwrap();
+        let list = unsafe { dns_name_list_load(path.as_ptr()) };
+        assert!(!list.is_null());
+
+        let input = CString::new("WWW.Example.co.uk.").unwrap();
+        let name = unsafe { dns_name_parse(list, input.as_ptr()) };
+        assert!(!name.is_null());
+        assert_eq!(
+            part(dns_name_name, name).as_deref(),
+            Some("www.example.co.uk.")
+        );
+        assert_eq!(part(dns_name_root, name).as_deref(), Some("example.co.uk."));
+        assert_eq!(part(dns_name_suffix, name).as_deref(), Some("co.uk."));
+        assert_eq!(part(dns_name_registrable, name).as_deref(), Some("example"));
+
+        // truncated copies are NUL terminated and report the full length
+        let mut buf = [1 as c_char; 4];
+        assert_eq!(unsafe { dns_name_root(name, buf.as_mut_ptr(), 4) }, 14);
+        assert_eq!(buf.map(|c| c as u8), *b"exa\0");
+        assert_eq!(unsafe { dns_name_root(name, ptr::null_mut(), 0) }, 14);
+
+        let invalid = CString::new("a..b").unwrap();
+        assert!(unsafe { dns_name_parse(list, invalid.as_ptr()) }.is_null());
+        assert!(unsafe { dns_name_parse(list, ptr::null()) }.is_null());
+
+        unsafe {
+            dns_name_list_free(list);
+            // names outlive the list they were parsed with
+            assert_eq!(
+                part(dns_name_name, name).as_deref(),
+                Some("www.example.co.uk.")
+            );
+            dns_name_free(name);
+            dns_name_free(ptr::null_mut());
+            dns_name_list_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn from_rules() {
+        let rules = CString::new("com,*.uk").unwrap();
+        let list = unsafe { dns_name_list_from_rules(rules.as_ptr()) };
+        assert!(!list.is_null());
+        let input = CString::new("a.b.uk").unwrap();
+        let name = unsafe { dns_name_parse(list, input.as_ptr()) };
+        assert_eq!(part(dns_name_suffix, name).as_deref(), Some("b.uk"));
+        assert_eq!(part(dns_name_registrable, ptr::null()), None);
+        unsafe {
+            dns_name_free(name);
+            dns_name_list_free(list);
+        }
+        assert!(unsafe { dns_name_list_load(ptr::null()) }.is_null());
+    }
+}
@@ -0,0 +1,77 @@
+//! Identifying a list by its rules
+
+use crate::{query, sha256, List};
+
+impl List {
+    /// Gets a fingerprint of the rules of the List, as 64 hex digits
+    ///
+    /// Lists with the same rules have the same fingerprint, however they
+    /// were built: rule order, duplicates, comments and where the list was
+    /// loaded from make no difference. It is the SHA-256 digest of the
+    /// sorted rules, exceptions with their `!`, each followed by a newline,
+    /// the default `*` rule included, so it is stable across processes,
+    /// platforms and versions of this crate.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let a = "com,co.uk,uk".parse::<List>().unwrap();
+    /// let b = List::from_buf_reader("// comment\nuk\nco.uk\ncom\ncom\n".as_bytes()).unwrap();
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// assert_ne!(a.fingerprint(), "com".parse::<List>().unwrap().fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> String {
+        let mut rules = Vec::new();
+        query::collect_rules(&self.root, &mut Vec::new(), &mut rules);
+        rules.sort();
+
+        let mut data = String::new();
+        for rule in &rules {
+            data.push_str(rule);
+            data.push('\n');
+        }
+        sha256::digest(data.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ListDiff;
+
+    #[test]
+    fn fingerprint() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let fingerprint = list.fingerprint();
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(
+            List::from_path("suffix-list.txt").unwrap().fingerprint(),
+            fingerprint
+        );
+
+        // sha256sum of "*\ncom\n"
+        assert_eq!(
+            "com".parse::<List>().unwrap().fingerprint(),
+            "5067f5eb505e9ecfc71cd60073035b3085b62fabd3612a2d61f09dcb5e0f54c7"
+        );
+        assert_eq!(
+            List::empty().fingerprint(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        let mut changed = "com,!www.ck,*.ck".parse::<List>().unwrap();
+        let before = changed.fingerprint();
+        let diff = ListDiff::new().add_rule("net");
+        changed.apply_diff(&diff).unwrap();
+        assert_ne!(changed.fingerprint(), before);
+        assert_eq!(
+            changed.fingerprint(),
+            "*.ck,!www.ck,net,com"
+                .parse::<List>()
+                .unwrap()
+                .fingerprint()
+        );
+    }
+}
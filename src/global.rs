@@ -0,0 +1,76 @@
+//! A process-wide default list
+
+use std::{io, str::FromStr, sync::OnceLock};
+
+use crate::{DnsName, List};
+
+static DEFAULT_LIST: OnceLock<List> = OnceLock::new();
+
+/// Installs the list used by `DnsName::parse` and `FromStr`
+///
+/// The list can only be set once; if one is already installed, `list` is
+/// handed back.
+///
+/// ```rust
+/// # use dns_name::{DnsName, List};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// dns_name::set_default_list(list).unwrap();
+///
+/// let name: DnsName = "www.example.co.uk".parse().unwrap();
+/// assert_eq!(name.root(), Some("example.co.uk"));
+/// assert!(dns_name::set_default_list(List::empty()).is_err());
+/// ```
+pub fn set_default_list(list: List) -> Result<(), List> {
+    DEFAULT_LIST.set(list)
+}
+
+/// The list installed by `set_default_list`, if any
+pub fn default_list() -> Option<&'static List> {
+    DEFAULT_LIST.get()
+}
+
+impl DnsName {
+    /// Parses a DNS name using the default list
+    ///
+    /// Fails with `io::ErrorKind::NotFound` if `set_default_list` has not
+    /// been called.
+    pub fn parse(domain: &str) -> io::Result<DnsName> {
+        match default_list() {
+            Some(list) => list.parse_dns_name(domain),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no default list has been set",
+            )),
+        }
+    }
+}
+
+impl FromStr for DnsName {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_list() {
+        // the list is process-wide, so this is the only test that sets it
+        let err = DnsName::parse("example.com").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        set_default_list(List::from_path("suffix-list.txt").unwrap()).unwrap();
+        let name = "WWW.Example.co.uk.".parse::<DnsName>().unwrap();
+        assert_eq!(name.name(), "www.example.co.uk.");
+        assert_eq!(
+            DnsName::parse("a.example.com").unwrap().suffix(),
+            Some("com")
+        );
+        assert!(DnsName::parse("a..b").is_err());
+        assert!(set_default_list(List::empty()).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+//! Lexical features for spotting algorithmically generated domains (DGA)
+//!
+//! Malware that generates its rendezvous domains tends to produce registrable
+//! labels that are long, random looking and low on vowels. The features here
+//! are computed on the registrable label alone, so `a1b2.example.co.uk` and
+//! `example.com` score the same: subdomains are free to pick, and the public
+//! suffix says nothing about the owner.
+//!
+//! These are inputs for a classifier or a threshold, not a verdict.
+
+use crate::DnsName;
+
+/// Lexical features of a registrable label
+///
+/// ```rust
+/// # use dns_name::List;
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let name = list.parse_dns_name("www.xkcd4tq9zv.co.uk").unwrap();
+/// let features = name.features().unwrap();
+/// assert_eq!(features.length, 10);
+/// assert_eq!(features.digit_ratio, 0.2);
+/// assert_eq!(features.consonant_run, 4);
+/// assert!(features.entropy > 3.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Features {
+    /// Number of characters
+    pub length: usize,
+    /// Shannon entropy of the characters, in bits per character
+    pub entropy: f64,
+    /// Fraction of the characters that are ASCII digits
+    pub digit_ratio: f64,
+    /// Length of the longest run of consonants (ASCII letters other than
+    /// `a`, `e`, `i`, `o` and `u`)
+    pub consonant_run: usize,
+}
+
+impl Features {
+    /// Computes the features of a single label
+    pub fn of(label: &str) -> Self {
+        let chars = label.chars().collect::<Vec<_>>();
+        let length = chars.len();
+        if length == 0 {
+            return Features {
+                length,
+                entropy: 0.0,
+                digit_ratio: 0.0,
+                consonant_run: 0,
+            };
+        }
+
+        let mut sorted = chars.clone();
+        sorted.sort_unstable();
+        let entropy = sorted
+            .chunk_by(|a, b| a == b)
+            .map(|run| {
+                let p = run.len() as f64 / length as f64;
+                -p * p.log2()
+            })
+            .sum();
+
+        let digits = chars.iter().filter(|c| c.is_ascii_digit()).count();
+
+        let mut consonant_run = 0;
+        let mut run = 0;
+        for c in &chars {
+            if c.is_ascii_alphabetic() && !matches!(c, 'a' | 'e' | 'i' | 'o' | 'u') {
+                run += 1;
+                consonant_run = consonant_run.max(run);
+            } else {
+                run = 0;
+            }
+        }
+
+        Features {
+            length,
+            entropy,
+            digit_ratio: digits as f64 / length as f64,
+            consonant_run,
+        }
+    }
+}
+
+impl DnsName {
+    /// Computes the lexical features of the registrable label
+    ///
+    /// Returns `None` if the Name has no registrable label, like a bare
+    /// public suffix or a name the list does not cover.
+    pub fn features(&self) -> Option<Features> {
+        self.registrable().map(Features::of)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::List;
+
+    #[test]
+    fn label_features() {
+        let features = Features::of("aaaa");
+        assert_eq!(features.entropy, 0.0);
+        assert_eq!(features.consonant_run, 0);
+
+        let features = Features::of("abcd");
+        assert_eq!(features.entropy, 2.0);
+        assert_eq!(features.consonant_run, 3);
+
+        let features = Features::of("a1-2");
+        assert_eq!(features.digit_ratio, 0.5);
+        assert_eq!(features.consonant_run, 0);
+
+        assert_eq!(Features::of("").length, 0);
+    }
+
+    #[test]
+    fn registrable_only() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let features = |name: &str| list.parse_dns_name(name).unwrap().features();
+
+        assert_eq!(features("a1b2c3.example.co.uk"), features("example.com"));
+        assert_eq!(features("example.com").unwrap().length, 7);
+        assert_eq!(features("co.uk"), None);
+        assert_eq!(features("."), None);
+    }
+}
@@ -0,0 +1,57 @@
+//! Conversions to and from hickory-dns names
+
+use std::io;
+
+use hickory_proto::rr::domain::Name;
+
+use crate::{DnsName, List};
+
+impl List {
+    /// Converts a hickory [`Name`] into a `DnsName`
+    ///
+    /// Requires the `hickory` feature.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// # use hickory_proto::rr::domain::Name;
+    /// # use std::str::FromStr;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = Name::from_str("www.example.com").unwrap();
+    /// let domain = list.from_hickory_name(&name).unwrap();
+    /// assert_eq!(domain.root(), Some("example.com"));
+    /// ```
+    pub fn from_hickory_name(&self, name: &Name) -> io::Result<DnsName> {
+        self.parse_dns_name(&name.to_ascii())
+    }
+}
+
+impl DnsName {
+    /// Converts the `DnsName` into a hickory [`Name`]
+    ///
+    /// The name is fully qualified if it was parsed with a trailing dot.
+    /// Requires the `hickory` feature.
+    pub fn to_hickory_name(&self) -> io::Result<Name> {
+        Name::from_ascii(self.name()).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn round_trip() -> Result<(), io::Error> {
+        let list = List::from_path("suffix-list.txt")?;
+
+        for input in ["www.example.co.uk", "www.example.co.uk.", "."] {
+            let name = Name::from_str(input).unwrap();
+            let domain = list.from_hickory_name(&name)?;
+            assert_eq!(domain.name(), input);
+            assert_eq!(domain.to_hickory_name()?, name);
+            assert_eq!(domain.to_hickory_name()?.is_fqdn(), name.is_fqdn());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,432 @@
+//! Relationships between names in the DNS tree
+
+use std::{iter::FusedIterator, ops::Range};
+
+use crate::{escape, DnsName};
+
+impl DnsName {
+    /// The name without its trailing dot, or `""` for the root name
+    fn relative_name(&self) -> &str {
+        escape::trim_dot(self.name())
+    }
+
+    /// Checks whether the Name is strictly below `other` in the DNS tree
+    ///
+    /// Labels are compared whole, case-insensitively, and trailing dots are
+    /// ignored. A name is not a subdomain of itself; every other name is a
+    /// subdomain of the root name.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::empty();
+    /// let parent = list.parse_dns_name("example.com").unwrap();
+    /// let child = list.parse_dns_name("www.Example.com.").unwrap();
+    /// let other = list.parse_dns_name("notexample.com").unwrap();
+    /// assert!(child.is_subdomain_of(&parent));
+    /// assert!(!other.is_subdomain_of(&parent));
+    /// assert!(!parent.is_subdomain_of(&parent));
+    /// ```
+    pub fn is_subdomain_of(&self, other: &DnsName) -> bool {
+        let name = self.relative_name();
+        let other = other.relative_name();
+        if name.len() <= other.len() {
+            return false;
+        }
+        if other.is_empty() {
+            return true;
+        }
+
+        let (head, tail) = name.split_at(name.len() - other.len());
+        head.ends_with('.')
+            && !escape::is_escaped(head, head.len() - 1)
+            && tail.eq_ignore_ascii_case(other)
+    }
+
+    /// Checks whether the Name is strictly above `other` in the DNS tree
+    ///
+    /// This is `other.is_subdomain_of(self)`.
+    pub fn is_parent_of(&self, other: &DnsName) -> bool {
+        other.is_subdomain_of(self)
+    }
+
+    /// Gets the Name with its leftmost label removed
+    ///
+    /// The suffix and root of the parent are derived from this Name without
+    /// consulting the list again: once the root is removed the remaining name
+    /// is treated as a public suffix. That is a guess, and wrong where the
+    /// suffix came from a wildcard rule: the parent of `x.kobe.jp` (matched
+    /// by `*.kobe.jp`) gets the suffix `kobe.jp`, where parsing `kobe.jp`
+    /// gives the suffix `jp`. Parse the parent's name again when the parts
+    /// must be exact. A suffix cut down this way has no `suffix_origin`, and
+    /// so no `List::rule_metadata`.
+    ///
+    /// The parent of a TLD is the root name, which has no parent. A trailing
+    /// dot is kept.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.example.co.uk").unwrap();
+    /// let parent = name.parent().unwrap();
+    /// assert_eq!(parent.name(), "example.co.uk");
+    /// assert_eq!(parent.root(), Some("example.co.uk"));
+    /// assert_eq!(parent.parent().unwrap().suffix(), Some("co.uk"));
+    /// ```
+    pub fn parent(&self) -> Option<DnsName> {
+        if self.name() == "." {
+            return None;
+        }
+
+        let Some((label, _)) = escape::split_first_label(self.relative_name()) else {
+            return Some(DnsName::new(".", None, None));
+        };
+
+        let removed = label.len() + 1;
+        let name = self.name()[removed..].to_owned();
+        let len = escape::trim_dot(&name).len();
+        let shift = |range: &Range<usize>| Range {
+            start: range.start - removed,
+            end: range.end - removed,
+        };
+
+        let suffix = self.suffix_range();
+        // the rule that matched no longer applies once the suffix is cut
+        let same_suffix = suffix
+            .as_ref()
+            .is_some_and(|suffix| suffix.start >= removed);
+        let suffix = suffix.map(|suffix| match same_suffix {
+            true => shift(&suffix),
+            false => Range { start: 0, end: len },
+        });
+        let root = self
+            .root_range()
+            .filter(|root| root.start >= removed)
+            .map(|root| shift(&root));
+
+        let mut name = DnsName::new(name, suffix, root);
+        if same_suffix {
+            name.origin = self.origin;
+        }
+        Some(name)
+    }
+
+    /// Gets the Name with any leading underscore-prefixed service labels
+    /// removed
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("_443._tcp.www.example.com").unwrap();
+    /// let base = name.strip_service_labels();
+    /// assert_eq!(base.name(), "www.example.com");
+    /// assert_eq!(base.root(), Some("example.com"));
+    /// ```
+    pub fn strip_service_labels(&self) -> DnsName {
+        let mut name = self.clone();
+        while name.name().starts_with('_') {
+            match name.parent() {
+                Some(parent) => name = parent,
+                None => break,
+            }
+        }
+        name
+    }
+
+    /// Walks from the Name up to the root name, one label at a time
+    ///
+    /// Like `Path::ancestors` the Name itself is yielded first.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("mail.a.example.com").unwrap();
+    /// let names = name.ancestors().map(|n| n.name().to_owned()).collect::<Vec<_>>();
+    /// assert_eq!(names, ["mail.a.example.com", "a.example.com", "example.com", "com", "."]);
+    /// ```
+    pub fn ancestors(&self) -> Ancestors {
+        Ancestors {
+            next: Some(self.clone()),
+        }
+    }
+
+    /// Lists the plausible delegation points of the Name, from the root name
+    /// down to the Name itself
+    ///
+    /// Every level may be a zone cut, but the list tells them apart: the
+    /// labels of the public suffix are run by registries, the registrable
+    /// domain is the zone most likely delegated to the owner, and the levels
+    /// below it are cuts only sometimes. A resolver can seed its NS queries
+    /// from the top and skip the levels it already knows.
+    ///
+    /// The levels come from `ancestors`, so above a suffix matched by a
+    /// wildcard rule they share the guesses `parent` makes: for
+    /// `a.b.c.kobe.jp`, `kobe.jp` is listed as a suffix level.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ZoneCutKind};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.example.co.uk").unwrap();
+    /// let cuts = name
+    ///     .likely_zone_cuts()
+    ///     .into_iter()
+    ///     .map(|cut| (cut.name.name().to_owned(), cut.kind))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     cuts,
+    ///     [
+    ///         (".".to_owned(), ZoneCutKind::Root),
+    ///         ("uk".to_owned(), ZoneCutKind::Suffix),
+    ///         ("co.uk".to_owned(), ZoneCutKind::Suffix),
+    ///         ("example.co.uk".to_owned(), ZoneCutKind::Registrable),
+    ///         ("www.example.co.uk".to_owned(), ZoneCutKind::Subdomain),
+    ///     ]
+    /// );
+    /// ```
+    pub fn likely_zone_cuts(&self) -> Vec<ZoneCut> {
+        let mut cuts = self
+            .ancestors()
+            .map(|name| {
+                let kind = if name.name() == "." {
+                    ZoneCutKind::Root
+                } else if name.root().is_some() && name.subdomain().is_none() {
+                    ZoneCutKind::Registrable
+                } else if name.root().is_none() && name.suffix().is_some() {
+                    ZoneCutKind::Suffix
+                } else {
+                    ZoneCutKind::Subdomain
+                };
+                ZoneCut { name, kind }
+            })
+            .collect::<Vec<_>>();
+        cuts.reverse();
+        cuts
+    }
+
+    /// Lists the names to query when walking from the Name toward the root
+    ///
+    /// With `Ns` this is the Name and each of its ancestors, and `Ds` leaves
+    /// out the root name. `Wildcard` gives, closest first, the `*` name below
+    /// each ancestor that a wildcard answer for the Name could come from.
+    /// Suffixes and roots are carried over as `parent` does.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, RecordTypeHint};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.example.com").unwrap();
+    /// let names = |hint| {
+    ///     name.query_ancestry(hint)
+    ///         .iter()
+    ///         .map(|n| n.name().to_owned())
+    ///         .collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(names(RecordTypeHint::Ns), ["www.example.com", "example.com", "com", "."]);
+    /// assert_eq!(names(RecordTypeHint::Ds), ["www.example.com", "example.com", "com"]);
+    /// assert_eq!(names(RecordTypeHint::Wildcard), ["*.example.com", "*.com", "*."]);
+    /// ```
+    pub fn query_ancestry(&self, hint: RecordTypeHint) -> Vec<DnsName> {
+        match hint {
+            RecordTypeHint::Ns => self.ancestors().collect(),
+            RecordTypeHint::Ds => self.ancestors().filter(|n| n.name() != ".").collect(),
+            RecordTypeHint::Wildcard => self
+                .ancestors()
+                .skip(1)
+                // a `*` label is always short enough, unless the name is
+                // already at the length limit
+                .filter_map(|parent| parent.prepend_unchecked("*").ok())
+                .collect(),
+        }
+    }
+}
+
+/// Which records a walk up the DNS tree is looking for, for
+/// `DnsName::query_ancestry`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordTypeHint {
+    /// NS records, at every level up to and including the root name
+    Ns,
+    /// DS records, at every level except the root name, which has none
+    Ds,
+    /// The wildcards that could synthesize an answer for the name
+    Wildcard,
+}
+
+/// Where a possible zone cut sits relative to the public suffix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ZoneCutKind {
+    /// The root name
+    Root,
+    /// A level of the public suffix, delegated by a registry
+    Suffix,
+    /// The registrable domain
+    Registrable,
+    /// A level below the registrable domain, or any level of a name the list
+    /// does not cover
+    Subdomain,
+}
+
+/// A possible zone cut, from `DnsName::likely_zone_cuts`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneCut {
+    pub name: DnsName,
+    pub kind: ZoneCutKind,
+}
+
+/// Iterator over a Name and its parents
+///
+/// This is created by `DnsName::ancestors`.
+#[derive(Debug, Clone)]
+pub struct Ancestors {
+    next: Option<DnsName>,
+}
+
+impl Iterator for Ancestors {
+    type Item = DnsName;
+
+    fn next(&mut self) -> Option<DnsName> {
+        let current = self.next.take()?;
+        self.next = current.parent();
+        Some(current)
+    }
+}
+
+impl FusedIterator for Ancestors {}
+
+#[cfg(test)]
+mod tests {
+    use super::{RecordTypeHint, ZoneCutKind};
+    use crate::List;
+
+    #[test]
+    fn subdomain_of() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let parse = |name: &str| list.parse_dns_name(name).unwrap();
+
+        let root = parse(".");
+        let com = parse("com.");
+        let example = parse("example.com");
+        let www = parse("a.www.example.com");
+
+        assert!(www.is_subdomain_of(&example));
+        assert!(www.is_subdomain_of(&com));
+        assert!(www.is_subdomain_of(&root));
+        assert!(example.is_subdomain_of(&com));
+        assert!(com.is_subdomain_of(&root));
+        assert!(!root.is_subdomain_of(&root));
+        assert!(!example.is_subdomain_of(&www));
+        assert!(!parse("ample.com").is_subdomain_of(&parse("example.com")));
+        assert!(!parse("example.com").is_subdomain_of(&parse("ample.com")));
+
+        assert!(root.is_parent_of(&www));
+        assert!(example.is_parent_of(&www));
+        assert!(!www.is_parent_of(&example));
+    }
+
+    #[test]
+    fn strip_service_labels() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let strip = |name: &str| {
+            let name = list.parse_dns_name(name).unwrap();
+            name.strip_service_labels().name().to_owned()
+        };
+
+        assert_eq!(strip("_dmarc.example.com."), "example.com.");
+        assert_eq!(strip("www._tcp.example.com"), "www._tcp.example.com");
+        assert_eq!(strip("_a._b"), ".");
+        assert_eq!(strip("example.com"), "example.com");
+    }
+
+    #[test]
+    fn parent() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+
+        // without wildcard rules every ancestor agrees with parsing it from
+        // scratch
+        for input in ["a.www.example.co.uk.", "a.b.example"] {
+            let name = list.parse_dns_name(input).unwrap();
+            for ancestor in name.ancestors().skip(1) {
+                let parsed = list.parse_dns_name(ancestor.name()).unwrap();
+                assert_eq!(ancestor, parsed);
+                assert_eq!(ancestor.suffix(), parsed.suffix());
+                assert_eq!(ancestor.root(), parsed.root());
+            }
+        }
+
+        // above a suffix from `*.kobe.jp` the parts are guessed; kobe.jp
+        // itself is not listed
+        let name = list.parse_dns_name("a.b.c.kobe.jp").unwrap();
+        let ancestors = name.ancestors().collect::<Vec<_>>();
+        assert_eq!(ancestors[2].suffix(), Some("c.kobe.jp"));
+        assert_eq!(ancestors[2].suffix_origin(), name.suffix_origin());
+        let kobe = &ancestors[3];
+        assert_eq!((kobe.suffix(), kobe.root()), (Some("kobe.jp"), None));
+        assert_eq!(kobe.suffix_origin(), None);
+        let parsed = list.parse_dns_name("kobe.jp").unwrap();
+        assert_eq!(
+            (parsed.suffix(), parsed.root()),
+            (Some("jp"), Some("kobe.jp"))
+        );
+
+        let empty = List::empty();
+        let name = empty.parse_dns_name("www.example.com.").unwrap();
+        let parent = name.parent().unwrap();
+        assert_eq!(parent, empty.parse_dns_name("example.com.").unwrap());
+        assert_eq!(parent.suffix(), None);
+        assert_eq!(name.ancestors().count(), 4);
+        assert_eq!(name.ancestors().last().unwrap().name(), ".");
+        assert!(empty.parse_dns_name(".").unwrap().parent().is_none());
+    }
+
+    #[test]
+    fn query_ancestry() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let name = list.parse_dns_name("a.example.co.uk.").unwrap();
+
+        let wildcards = name.query_ancestry(RecordTypeHint::Wildcard);
+        let names = wildcards.iter().map(|n| n.name()).collect::<Vec<_>>();
+        assert_eq!(names, ["*.example.co.uk.", "*.co.uk.", "*.uk.", "*."]);
+        assert_eq!(wildcards[0].root(), Some("example.co.uk."));
+        assert_eq!(wildcards[1].root(), Some("*.co.uk."));
+        assert_eq!(wildcards[1].suffix(), Some("co.uk."));
+
+        let root = list.parse_dns_name(".").unwrap();
+        assert_eq!(root.query_ancestry(RecordTypeHint::Ns).len(), 1);
+        assert!(root.query_ancestry(RecordTypeHint::Ds).is_empty());
+        assert!(root.query_ancestry(RecordTypeHint::Wildcard).is_empty());
+    }
+
+    #[test]
+    fn zone_cuts() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let kinds = |list: &List, name: &str| {
+            let name = list.parse_dns_name(name).unwrap();
+            name.likely_zone_cuts()
+                .into_iter()
+                .map(|cut| cut.kind)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(kinds(&list, "."), [ZoneCutKind::Root]);
+        assert_eq!(
+            kinds(&list, "a.b.example.com."),
+            [
+                ZoneCutKind::Root,
+                ZoneCutKind::Suffix,
+                ZoneCutKind::Registrable,
+                ZoneCutKind::Subdomain,
+                ZoneCutKind::Subdomain,
+            ]
+        );
+        assert_eq!(
+            kinds(&list, "co.uk"),
+            [ZoneCutKind::Root, ZoneCutKind::Suffix, ZoneCutKind::Suffix]
+        );
+        assert_eq!(
+            kinds(&List::empty(), "example.com"),
+            [
+                ZoneCutKind::Root,
+                ZoneCutKind::Subdomain,
+                ZoneCutKind::Subdomain,
+            ]
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Telling DNS names apart from IP address literals
+
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use crate::{DnsName, List, ParseOptions};
+
+/// A host: a DNS name, an IP address literal, or a name that looks like a
+/// malformed address
+///
+/// This is created by `List::parse_host`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Host {
+    /// A DNS name: `www.example.com`
+    Name(DnsName),
+    /// An IPv4 literal: `127.0.0.1`
+    Ipv4(Ipv4Addr),
+    /// An IPv6 literal, with or without brackets: `[::1]`, `::1`
+    Ipv6(Ipv6Addr),
+    /// A name whose last label is numeric, which no real domain has:
+    /// `127.0.0.256`, `10.1`
+    IpLike(DnsName),
+}
+
+impl Host {
+    /// Gets the DNS name, if the host is not an IP address
+    pub fn as_name(&self) -> Option<&DnsName> {
+        match self {
+            Host::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the host is an IP address literal
+    pub fn is_ip(&self) -> bool {
+        matches!(self, Host::Ipv4(_) | Host::Ipv6(_))
+    }
+
+    /// Returns true if the host is a name with a numeric last label
+    pub fn is_ip_like(&self) -> bool {
+        matches!(self, Host::IpLike(_))
+    }
+}
+
+impl List {
+    /// Parses a host, recognizing IPv4 and IPv6 literals
+    ///
+    /// Anything that is not an IP address is parsed as a DNS name using the
+    /// list. Names whose last label is numeric are returned as
+    /// `Host::IpLike` rather than `Host::Name`.
+    ///
+    /// ```rust
+    /// # use dns_name::{Host, List};
+    /// # use std::net::Ipv4Addr;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// assert_eq!(
+    ///     list.parse_host("127.38.53.247").unwrap(),
+    ///     Host::Ipv4(Ipv4Addr::new(127, 38, 53, 247))
+    /// );
+    /// let host = list.parse_host("www.example.com").unwrap();
+    /// assert_eq!(host.as_name().unwrap().root(), Some("example.com"));
+    /// ```
+    pub fn parse_host(&self, input: &str) -> io::Result<Host> {
+        if let Some(addr) = parse_ip(input) {
+            return Ok(addr);
+        }
+        if input.starts_with('[') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid ipv6 literal",
+            ));
+        }
+        let name = self.parse_dns_name(input)?;
+        if name.has_numeric_tld() {
+            return Ok(Host::IpLike(name));
+        }
+        Ok(Host::Name(name))
+    }
+}
+
+impl DnsName {
+    /// Gets the IP address the Name spells, if it is an IPv4 or (optionally
+    /// bracketed) IPv6 literal
+    ///
+    /// Addresses parse as names unless `IpPolicy` says otherwise; see
+    /// `ParseOptions::ip_literals`.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// # use std::net::{IpAddr, Ipv6Addr};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("[::1]").unwrap();
+    /// assert_eq!(name.ip_addr(), Some(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+    /// assert_eq!(list.parse_dns_name("example.com").unwrap().ip_addr(), None);
+    /// ```
+    pub fn ip_addr(&self) -> Option<IpAddr> {
+        match parse_ip(self.name())? {
+            Host::Ipv4(addr) => Some(IpAddr::V4(addr)),
+            Host::Ipv6(addr) => Some(IpAddr::V6(addr)),
+            _ => None,
+        }
+    }
+
+    /// Gets the port that followed the Name in the input, as allowed by
+    /// `ParseOptions::port_suffix`
+    ///
+    /// Names not parsed from text, like those made by `DnsName::parent`,
+    /// have no port.
+    pub fn port(&self) -> Option<u16> {
+        port_of(self.original(), self.flags)
+    }
+}
+
+/// The port ending `original`, if `flags` has `HAS_PORT` set
+pub(crate) fn port_of(original: &str, flags: u8) -> Option<u16> {
+    if flags & crate::HAS_PORT == 0 {
+        return None;
+    }
+    // the input is kept whenever it had a port, which ends it
+    let (_, port) = original.rsplit_once(':')?;
+    port.parse().ok()
+}
+
+/// Drops the brackets around an IPv6 literal and a trailing port, as far as
+/// `options` allows, returning the rest and the port
+pub(crate) fn strip_decoration<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> io::Result<(&'a str, Option<u16>)> {
+    let invalid = |msg| io::Error::new(io::ErrorKind::InvalidInput, msg);
+    let parse_port = |port: &str| {
+        port.bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| port.parse::<u16>().ok())
+            .flatten()
+            .ok_or_else(|| invalid("invalid port"))
+    };
+
+    if let Some(rest) = input.strip_prefix('[').filter(|_| options.bracketed_ipv6) {
+        let Some((addr, rest)) = rest
+            .split_once(']')
+            .filter(|(addr, _)| addr.parse::<Ipv6Addr>().is_ok())
+        else {
+            return Err(invalid("invalid ipv6 literal"));
+        };
+        return match rest.strip_prefix(':') {
+            _ if rest.is_empty() => Ok((addr, None)),
+            Some(port) if options.port_suffix => Ok((addr, Some(parse_port(port)?))),
+            _ => Err(invalid("invalid ipv6 literal")),
+        };
+    }
+
+    // more than one ':' is a bare IPv6 literal, not a port
+    match input.split_once(':') {
+        Some((name, port)) if options.port_suffix && !port.contains(':') => {
+            Ok((name, Some(parse_port(port)?)))
+        }
+        _ => Ok((input, None)),
+    }
+}
+
+/// Parses an IPv4 or (optionally bracketed) IPv6 literal
+pub(crate) fn parse_ip(input: &str) -> Option<Host> {
+    if let Some(inner) = input.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        return inner.parse().ok().map(Host::Ipv6);
+    }
+    if let Ok(addr) = input.parse() {
+        return Some(Host::Ipv4(addr));
+    }
+    input.parse().ok().map(Host::Ipv6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_host() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+
+        assert_eq!(
+            list.parse_host("fd79:cdcb:38cc:9dd:f686:e06d:32f3:c123")
+                .unwrap(),
+            Host::Ipv6("fd79:cdcb:38cc:9dd:f686:e06d:32f3:c123".parse().unwrap())
+        );
+        assert_eq!(
+            list.parse_host("[::1]").unwrap(),
+            Host::Ipv6(Ipv6Addr::LOCALHOST)
+        );
+        assert!(list.parse_host("[::1").is_err());
+        assert!(list.parse_host("[example.com]").is_err());
+        assert!(list.parse_host("[127.0.0.1]").is_err());
+
+        // not quite an address, nor a real domain
+        let host = list.parse_host("127.0.0.256").unwrap();
+        assert!(!host.is_ip());
+        assert!(host.is_ip_like());
+        assert_eq!(host.as_name(), None);
+        let Host::IpLike(name) = host else {
+            unreachable!()
+        };
+        assert_eq!(name.suffix(), Some("256"));
+        assert!(list.parse_host("10.1").unwrap().is_ip_like());
+        assert!(!list.parse_host("127.com").unwrap().is_ip_like());
+    }
+
+    #[test]
+    fn decoration() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let options = ParseOptions::new().bracketed_ipv6(true).port_suffix(true);
+        let parse = |input| {
+            let name = list.parse_dns_name_with(input, &options)?;
+            Ok::<_, io::Error>((name.name().to_owned(), name.port()))
+        };
+
+        assert_eq!(parse("example.com").unwrap(), ("example.com".into(), None));
+        assert_eq!(
+            parse("Example.com.:443").unwrap(),
+            ("example.com.".into(), Some(443))
+        );
+        assert_eq!(parse("[::1]").unwrap(), ("::1".into(), None));
+        assert_eq!(parse("[::1]:0").unwrap(), ("::1".into(), Some(0)));
+        assert_eq!(parse("::1").unwrap(), ("::1".into(), None));
+        for input in [
+            "example.com:",
+            "example.com:65536",
+            "example.com:+80",
+            "[::1",
+            "[::1]x",
+            "[127.0.0.1]",
+        ] {
+            assert!(parse(input).is_err(), "{input}");
+        }
+
+        // each is off unless asked for
+        let name = list.parse_dns_name("[::1]:53").unwrap();
+        assert_eq!((name.name(), name.port()), ("[::1]:53", None));
+        let options = ParseOptions::new().bracketed_ipv6(true);
+        assert!(list.parse_dns_name_with("[::1]:53", &options).is_err());
+        assert!(list
+            .parse_dns_name("example.com:80")
+            .unwrap()
+            .port()
+            .is_none());
+    }
+
+    #[test]
+    fn ip_policy() {
+        use crate::{IpPolicy, ParseError, ParseOptions};
+
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let parse = |input, policy| {
+            let options = ParseOptions::new().ip_literals(policy);
+            list.parse_dns_name_with(input, &options)
+        };
+
+        for input in ["10.0.0.1", "::1", "[2001:DB8::1]"] {
+            let name = parse(input, IpPolicy::ParseAsName).unwrap();
+            assert!(name.suffix().is_some(), "{input}");
+
+            let name = parse(input, IpPolicy::Classify).unwrap();
+            assert_eq!((name.suffix(), name.root()), (None, None), "{input}");
+            assert!(name.ip_addr().is_some(), "{input}");
+
+            let err = parse(input, IpPolicy::Reject).unwrap_err();
+            assert_eq!(ParseError::from_io(&err), Some(&ParseError::IpLiteral));
+        }
+
+        // names that only look numeric are still names
+        for input in ["10.1", "127.0.0.256", "1.2.3.4.", "example.com"] {
+            let name = parse(input, IpPolicy::Reject).unwrap();
+            assert!(name.suffix().is_some(), "{input}");
+            assert_eq!(name.ip_addr(), None, "{input}");
+        }
+    }
+}
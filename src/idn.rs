@@ -0,0 +1,171 @@
+//! A-label and U-label forms of internationalized names
+
+use std::borrow::Cow;
+
+use crate::{escape, validate, DnsName};
+
+/// Converts each label of a name with `convert`, keeping the trailing dot
+///
+/// Labels with escapes and labels `convert` rejects are kept as they are.
+fn map_labels(name: &str, convert: fn(&str) -> Option<String>) -> Cow<'_, str> {
+    let domain = escape::trim_dot(name);
+    let mut out = String::with_capacity(name.len());
+    let mut changed = false;
+    for (i, range) in escape::label_ranges(domain).into_iter().enumerate() {
+        if i > 0 {
+            out.push('.');
+        }
+        let label = &domain[range];
+        match convert(label).filter(|_| !label.contains('\\')) {
+            Some(converted) if converted != label => {
+                out.push_str(&converted);
+                changed = true;
+            }
+            _ => out.push_str(label),
+        }
+    }
+    if !changed {
+        return Cow::Borrowed(name);
+    }
+    out.push_str(&name[domain.len()..]);
+    Cow::Owned(out)
+}
+
+fn to_ascii(label: &str) -> Option<String> {
+    if label.is_ascii() {
+        return None;
+    }
+    idna::domain_to_ascii(label).ok()
+}
+
+fn to_unicode(label: &str) -> Option<String> {
+    if !label.get(..4)?.eq_ignore_ascii_case("xn--") {
+        return None;
+    }
+    match idna::domain_to_unicode(label) {
+        (unicode, Ok(())) => Some(unicode),
+        _ => None,
+    }
+}
+
+impl DnsName {
+    /// Get the DNS name with internationalized labels as A-labels
+    ///
+    /// Labels that are not valid IDNA are kept as they are.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.bücher.de").unwrap();
+    /// assert_eq!(name.name_ascii(), "www.xn--bcher-kva.de");
+    /// ```
+    pub fn name_ascii(&self) -> Cow<'_, str> {
+        map_labels(self.name(), to_ascii)
+    }
+
+    /// Get the DNS name with `xn--` labels decoded to U-labels
+    ///
+    /// Labels that are not valid punycode are kept as they are.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.xn--bcher-kva.de").unwrap();
+    /// assert_eq!(name.name_unicode(), "www.bücher.de");
+    /// ```
+    pub fn name_unicode(&self) -> Cow<'_, str> {
+        map_labels(self.name(), to_unicode)
+    }
+
+    /// Formats the name with U-labels, keeping any trailing dot
+    ///
+    /// This is `name_unicode` as an owned string.
+    pub fn format_unicode(&self) -> String {
+        self.name_unicode().into_owned()
+    }
+
+    /// Gets the root domain portion of the Name with U-labels
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.xn--e1afmkfd.xn--p1ai").unwrap();
+    /// assert_eq!(name.root_unicode().as_deref(), Some("пример.рф"));
+    /// ```
+    pub fn root_unicode(&self) -> Option<Cow<'_, str>> {
+        self.root().map(|root| map_labels(root, to_unicode))
+    }
+
+    /// Gets the suffix portion of the Name with U-labels
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.xn--e1afmkfd.xn--p1ai").unwrap();
+    /// assert_eq!(name.suffix_unicode().as_deref(), Some("рф"));
+    /// ```
+    pub fn suffix_unicode(&self) -> Option<Cow<'_, str>> {
+        self.suffix().map(|suffix| map_labels(suffix, to_unicode))
+    }
+
+    /// Returns true if an `xn--` label is not a valid IDNA A-label
+    ///
+    /// Labels with escapes are not checked. See
+    /// `ParseOptions::validate_punycode` to reject such names instead.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// assert!(list.parse_dns_name("www.xn--a.com").unwrap().has_invalid_punycode());
+    /// assert!(!list.parse_dns_name("xn--bcher-kva.de").unwrap().has_invalid_punycode());
+    /// ```
+    pub fn has_invalid_punycode(&self) -> bool {
+        let domain = escape::trim_dot(self.name());
+        escape::label_ranges(domain)
+            .into_iter()
+            .map(|range| &domain[range])
+            .any(|label| !label.contains('\\') && !validate::is_valid_punycode(label))
+    }
+
+    /// Gets the registrable portion of the Name with U-labels
+    pub fn registrable_unicode(&self) -> Option<Cow<'_, str>> {
+        self.registrable()
+            .map(|registrable| map_labels(registrable, to_unicode))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::List;
+
+    #[test]
+    fn dual_form() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+
+        let name = list.parse_dns_name("Mail.Bücher.de.").unwrap();
+        assert_eq!(name.name_ascii(), "mail.xn--bcher-kva.de.");
+        assert_eq!(name.name_unicode(), "mail.bücher.de.");
+        assert_eq!(name.root_unicode().as_deref(), Some("bücher.de."));
+        assert_eq!(name.suffix_unicode().as_deref(), Some("de."));
+
+        let name = list.parse_dns_name("mail.xn--bcher-kva.de").unwrap();
+        assert_eq!(name.name_unicode(), "mail.bücher.de");
+        assert_eq!(name.registrable_unicode().as_deref(), Some("bücher"));
+        assert_eq!(name.root_unicode().as_deref(), Some("bücher.de"));
+
+        // already in the requested form: nothing is allocated
+        let name = list.parse_dns_name("www.example.com").unwrap();
+        assert!(matches!(name.name_ascii(), std::borrow::Cow::Borrowed(_)));
+        assert!(matches!(name.name_unicode(), std::borrow::Cow::Borrowed(_)));
+
+        // invalid punycode and escaped labels are left alone
+        let name = list.parse_dns_name("xn--a.b\\.ü.de").unwrap();
+        assert_eq!(name.name_unicode(), "xn--a.b\\.ü.de");
+        assert_eq!(name.name_ascii(), "xn--a.b\\.ü.de");
+        assert!(name.has_invalid_punycode());
+        assert!(!list
+            .parse_dns_name("mail.xn--bcher-kva.de.")
+            .unwrap()
+            .has_invalid_punycode());
+    }
+}
@@ -0,0 +1,100 @@
+//! Name storage that keeps short names out of the heap
+
+use std::{fmt, ops::Deref};
+
+/// A string of up to `N` bytes kept inline, or a longer one on the heap
+///
+/// Most hostnames are short, so parsing them allocates nothing.
+#[derive(Clone)]
+pub(crate) enum InlineStr<const N: usize> {
+    Inline { len: u8, bytes: [u8; N] },
+    Heap(Box<str>),
+}
+
+impl<const N: usize> InlineStr<N> {
+    /// The lengths of inline strings must fit in a `u8`
+    const FITS: () = assert!(N <= u8::MAX as usize);
+
+    /// Joins two strings, keeping the result inline if it fits
+    pub(crate) fn concat(a: &str, b: &str) -> Self {
+        let len = a.len() + b.len();
+        if len > N {
+            return Self::Heap([a, b].concat().into_boxed_str());
+        }
+        let mut bytes = [0; N];
+        bytes[..a.len()].copy_from_slice(a.as_bytes());
+        bytes[a.len()..len].copy_from_slice(b.as_bytes());
+        #[allow(clippy::let_unit_value)]
+        let () = Self::FITS;
+        Self::Inline {
+            len: len as u8,
+            bytes,
+        }
+    }
+
+    /// Returns true if the string is kept inline
+    #[cfg(test)]
+    fn is_inline(&self) -> bool {
+        matches!(self, Self::Inline { .. })
+    }
+}
+
+impl<const N: usize> From<&str> for InlineStr<N> {
+    fn from(s: &str) -> Self {
+        Self::concat(s, "")
+    }
+}
+
+impl<const N: usize> From<String> for InlineStr<N> {
+    fn from(s: String) -> Self {
+        match s.len() <= N {
+            true => Self::from(s.as_str()),
+            false => Self::Heap(s.into_boxed_str()),
+        }
+    }
+}
+
+impl<const N: usize> Deref for InlineStr<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Self::Inline { len, bytes } => {
+                let bytes = &bytes[..usize::from(*len)];
+                // SAFETY: the bytes were copied from whole `str`s in `concat`
+                unsafe { std::str::from_utf8_unchecked(bytes) }
+            }
+            Self::Heap(s) => s,
+        }
+    }
+}
+
+impl<const N: usize> fmt::Debug for InlineStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_or_heap() {
+        let short = InlineStr::<8>::from("abc");
+        assert!(short.is_inline());
+        assert_eq!(&*short, "abc");
+
+        let full = InlineStr::<8>::concat("abcd", "éf");
+        assert!(full.is_inline());
+        assert_eq!(&*full, "abcdéf");
+
+        let long = InlineStr::<8>::from(String::from("abcdefghi"));
+        assert!(!long.is_inline());
+        assert_eq!(&*long, "abcdefghi");
+        assert_eq!(&*InlineStr::<8>::concat("abcde", "fghi"), "abcdefghi");
+
+        assert_eq!(&*InlineStr::<0>::from(""), "");
+        assert_eq!(format!("{:?}", InlineStr::<4>::from("a\"b")), r#""a\"b""#);
+    }
+}
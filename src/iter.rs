@@ -0,0 +1,140 @@
+//! Parsing streams of names lazily
+
+use std::{collections::HashMap, io, iter::FusedIterator};
+
+use crate::{aggregate, DnsName, List, ParseOptions, Scratch};
+
+/// Adds DNS name parsing to iterators of strings
+///
+/// ```rust
+/// # use dns_name::{DnsNameIterExt, List};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let log = "www.example.com\nmail.example.com\nexample.co.uk\n";
+///
+/// let roots = log
+///     .lines()
+///     .parse_dns_names(&list)
+///     .map(|name| name.unwrap().root().unwrap().to_owned())
+///     .collect::<Vec<_>>();
+/// assert_eq!(roots, ["example.com", "example.com", "example.co.uk"]);
+///
+/// let groups = log.lines().group_by_registrable(&list);
+/// assert_eq!(groups["example.com"].len(), 2);
+/// ```
+pub trait DnsNameIterExt: Iterator + Sized
+where
+    Self::Item: AsRef<str>,
+{
+    /// Parses each name lazily using the list
+    ///
+    /// Parse buffers are reused between names, as with `List::parse_many`.
+    fn parse_dns_names(self, list: &List) -> ParseDnsNames<'_, Self> {
+        ParseDnsNames {
+            names: self,
+            list,
+            options: ParseOptions::default(),
+            scratch: Scratch::default(),
+        }
+    }
+
+    /// Buckets names by root domain (eTLD+1), without the trailing dot
+    ///
+    /// Names without a root domain, such as public suffixes, are their own
+    /// bucket, as with `DnsName::same_site`. Names that fail to parse are
+    /// skipped; use `parse_dns_names` to see the errors.
+    fn group_by_registrable(self, list: &List) -> HashMap<String, Vec<DnsName>> {
+        aggregate::group(self.parse_dns_names(list).flatten())
+    }
+}
+
+impl<I> DnsNameIterExt for I
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+}
+
+/// An iterator parsing names, created by `DnsNameIterExt::parse_dns_names`
+#[derive(Debug)]
+pub struct ParseDnsNames<'a, I> {
+    names: I,
+    list: &'a List,
+    options: ParseOptions,
+    scratch: Scratch,
+}
+
+impl<I> ParseDnsNames<'_, I> {
+    /// Enables extra checks, as with `List::parse_dns_name_with`
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl<I> Iterator for ParseDnsNames<'_, I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = io::Result<DnsName>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let name = self.names.next()?;
+        Some(DnsName::find_match_in(
+            name.as_ref(),
+            self.list,
+            &self.options,
+            &mut self.scratch,
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.names.size_hint()
+    }
+}
+
+impl<I> FusedIterator for ParseDnsNames<'_, I>
+where
+    I: FusedIterator,
+    I::Item: AsRef<str>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParseError, ValidationProfile};
+
+    #[test]
+    fn stream() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let names = vec![
+            "www.example.com".to_owned(),
+            "a..b".to_owned(),
+            "Mail.Example.com.".to_owned(),
+            "co.uk".to_owned(),
+            "www.example.co.uk".to_owned(),
+        ];
+
+        let parsed = names.iter().parse_dns_names(&list).collect::<Vec<_>>();
+        assert_eq!(parsed.len(), 5);
+        assert!(parsed[1].is_err());
+        assert_eq!(parsed[2].as_ref().unwrap().name(), "mail.example.com.");
+
+        let options = ParseOptions::new().profile(ValidationProfile::Hostname);
+        let err = ["a_b.com"]
+            .into_iter()
+            .parse_dns_names(&list)
+            .with_options(options)
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(ParseError::from_io(&err).is_some());
+
+        let groups = names.into_iter().group_by_registrable(&list);
+        let mut keys = groups.keys().map(String::as_str).collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, ["co.uk", "example.co.uk", "example.com"]);
+        assert_eq!(groups["example.com"].len(), 2);
+    }
+}
@@ -32,33 +32,149 @@
 //! ```
 
 use std::{
+    borrow::{Borrow, Cow},
     collections::HashMap,
     fmt,
     fs::File,
-    io::{self, Read},
+    hash::{Hash, Hasher},
+    io::{self, BufRead, Read},
     ops::Range,
     path::Path,
+    sync::Arc,
 };
 
+use fnv::FnvBuildHasher;
+use inline::InlineStr;
+use observe::Observer;
+
+pub mod aggregate;
+mod arena;
+mod batch;
+mod builder;
+mod cache;
+mod canonical;
+pub mod cert;
+pub mod conformance;
+mod cookie;
+mod diff;
+mod domain_set;
+mod dual;
+mod error;
+mod escape;
+mod explain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod fingerprint;
+mod fnv;
+#[cfg(feature = "global")]
+mod global;
+#[cfg(feature = "heuristics")]
+pub mod heuristics;
+#[cfg(feature = "hickory")]
+mod hickory;
+mod hierarchy;
+mod host;
+#[cfg(feature = "idna")]
+mod idn;
+mod inline;
+mod iter;
+mod lenient;
+mod lint;
+mod list_builder;
+mod name_set;
+mod normalize;
+mod observe;
+mod options;
+mod overrides;
+mod parts;
+mod query;
+mod registry;
+mod reverse;
+#[cfg(feature = "security")]
+pub mod security;
+mod sha256;
+mod shared;
+mod source;
+mod spec;
+mod special;
+mod stats;
+mod store;
+#[cfg(feature = "async")]
+mod stream;
+mod tokens;
+mod update;
+#[cfg(feature = "url")]
+mod url_host;
+mod validate;
+mod wire;
+
+pub use arena::{DnsNameRef, NameArena};
+pub use builder::DnsNameBuilder;
+pub use cache::{CacheStats, CachedList};
+pub use canonical::CanonicalDnsName;
+pub use diff::ListDiff;
+pub use domain_set::{DomainSet, MatchKind};
+pub use dual::DualDnsName;
+pub use error::{LabelError, ParseError};
+pub use explain::{Explanation, Lookup, Step};
+#[cfg(feature = "global")]
+pub use global::{default_list, set_default_list};
+pub use hierarchy::{Ancestors, RecordTypeHint, ZoneCut, ZoneCutKind};
+pub use host::Host;
+pub use iter::{DnsNameIterExt, ParseDnsNames};
+pub use lint::{LintIssue, RuleError};
+pub use list_builder::ListBuilder;
+pub use name_set::NameSet;
+#[cfg(feature = "idna")]
+pub use normalize::IdnaMapping;
+pub use normalize::{Lowercase, Normalizer, NormalizerChain, PercentDecode, StripTrailingDot};
+pub use observe::ParseObserver;
+pub use options::{Algorithm, InvalidUtf8, IpPolicy, ParseOptions, ValidationProfile};
+pub use parts::{RegistrableDomain, Subdomain, Suffix};
+pub use query::{MatchInfo, SuffixOrigin};
+pub use registry::ListRegistry;
+pub use shared::SharedList;
+pub use source::ListSource;
+pub use special::SpecialUse;
+pub use stats::ListStats;
+pub use store::ListStore;
+pub use update::ListUpdater;
+
 const PREVAILING_STAR_RULE: &str = "*";
+/// Comments around the private domains section of a publicsuffix.org list
+const PRIVATE_BEGIN: &str = "===BEGIN PRIVATE DOMAINS===";
+const PRIVATE_END: &str = "===END PRIVATE DOMAINS===";
+/// Longest label permitted by RFC 1035
+const MAX_LABEL_LEN: usize = 63;
+/// Longest name permitted by RFC 1035, in wire format
+const MAX_WIRE_LEN: usize = 255;
 
 #[derive(Debug)]
 // A node leaf
 struct ListLeaf {
     is_exception_rule: bool,
+    /// attached to the rule when the list was loaded
+    metadata: Option<Arc<str>>,
+    /// whether the rule is in the private domains section
+    private: bool,
 }
 
 impl ListLeaf {
     /// Creates a new `ListLeaf`
-    fn new(is_exception_rule: bool) -> Self {
-        Self { is_exception_rule }
+    fn new(is_exception_rule: bool, metadata: Option<Arc<str>>, private: bool) -> Self {
+        Self {
+            is_exception_rule,
+            metadata,
+            private,
+        }
     }
 }
 
 #[derive(Debug)]
 /// A List node
 struct ListNode {
-    children: HashMap<String, ListNode>,
+    /// labels are shared between lists by `ListRegistry`
+    children: HashMap<Arc<str>, ListNode, FnvBuildHasher>,
     leaf: Option<ListLeaf>,
 }
 
@@ -66,37 +182,211 @@ impl ListNode {
     /// Creates a new `ListNode`
     fn new() -> Self {
         Self {
-            children: HashMap::new(),
+            children: HashMap::default(),
             leaf: None,
         }
     }
 }
 
 /// Stores the public suffix list
+///
+/// A `List` is immutable once built and is `Send + Sync`, so it can be
+/// shared between threads by reference or through a `SharedList`.
 #[derive(Debug)]
 pub struct List {
     root: ListNode,
+    /// rules that were appended more than once, in load order
+    duplicates: Vec<String>,
+    /// the `ListSource` the list was loaded from
+    source: Option<Box<str>>,
+    observer: Option<Observer>,
+}
+
+/// Reads a list one line at a time, keeping track of the private domains
+/// section
+struct RuleReader {
+    include_private: bool,
+    annotated: bool,
+    /// whether the lines read are in the private domains section
+    private: bool,
+}
+
+impl RuleReader {
+    fn new(include_private: bool, annotated: bool) -> Self {
+        Self {
+            include_private,
+            annotated,
+            private: false,
+        }
+    }
+
+    /// Appends the rules on `line` to `list`
+    fn read_line(&mut self, list: &mut List, line: &str) -> io::Result<()> {
+        let trimmed = line.trim();
+        if let Some(comment) = trimmed.strip_prefix("//") {
+            match comment.trim() {
+                PRIVATE_BEGIN => self.private = true,
+                PRIVATE_END => self.private = false,
+                _ => {}
+            }
+        } else if !trimmed.is_empty() && (self.include_private || !self.private) {
+            for rule in trimmed.split(',') {
+                // rules end at the first whitespace, the rest is ignored
+                // unless it is metadata
+                let Some(name) = rule.split_whitespace().next() else {
+                    continue;
+                };
+                let rest = rule.trim_start()[name.len()..].trim();
+                let metadata = (self.annotated && !rest.is_empty()).then(|| Arc::from(rest));
+                list.append_with(name, metadata, self.private)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Buffers reused between parses
+#[derive(Debug, Default)]
+struct Scratch {
+    /// the lowercased input
+    input: String,
+    /// byte ranges of the labels of `input`
+    labels: Vec<Range<usize>>,
+}
+
+/// A name matched against a list, before it is made into a `DnsName`
+#[derive(Clone)]
+struct Split<'a> {
+    /// the lowercased name
+    name: &'a str,
+    suffix: Option<Range<usize>>,
+    root: Option<Range<usize>>,
+    /// whether a rule other than the default `*` matched
+    listed: bool,
+    /// the kind of rule that gave the suffix, if there is one
+    origin: Option<SuffixOrigin>,
+    /// the port that followed the name
+    port: Option<u16>,
+    /// whether percent-encoding was decoded
+    percent_decoded: bool,
+}
+
+/// A name lowercased and checked, ready to be matched against a list
+enum Prepared<'a> {
+    /// the name needs no matching, like the root name
+    Done(Split<'a>),
+    Labels {
+        /// the lowercased name
+        input: &'a str,
+        /// byte ranges of every label of `input`
+        all_labels: &'a [Range<usize>],
+        /// the labels taking part in matching
+        labels: &'a [Range<usize>],
+        /// whether percent-encoding was decoded
+        decoded: bool,
+    },
+}
+
+impl<'a> Split<'a> {
+    /// A name with no suffix or root
+    fn whole(name: &'a str, listed: bool) -> Self {
+        Split {
+            name,
+            suffix: None,
+            root: None,
+            listed,
+            origin: None,
+            port: None,
+            percent_decoded: false,
+        }
+    }
+
+    /// Makes the split into a `DnsName`
+    ///
+    /// A port is only flagged: the name keeps the input it came from with
+    /// `with_original`, and the port is read back from there.
+    fn into_name(self) -> DnsName {
+        let flags = self.flags();
+        let mut name = DnsName::new(self.name, self.suffix, self.root);
+        name.origin = self.origin;
+        name.flags = flags;
+        name
+    }
+
+    /// The `DnsName::flags` bits for the port and percent-decoding
+    fn flags(&self) -> u8 {
+        let mut flags = 0;
+        if self.port.is_some() {
+            flags |= HAS_PORT;
+        }
+        if self.percent_decoded {
+            flags |= PERCENT_DECODED;
+        }
+        flags
+    }
+
+    /// The name from the start of `range` on, trailing dot included
+    fn part(&self, range: &Option<Range<usize>>) -> Option<String> {
+        range
+            .as_ref()
+            .map(|range| self.name[range.start..].to_owned())
+    }
 }
 
+/// Marks a missing part of a `DnsName`
+const NO_OFFSET: u16 = u16::MAX;
+/// Longest name a `DnsName` holds, so every offset into it fits in a `u16`
+const MAX_NAME_BYTES: usize = NO_OFFSET as usize - 1;
+/// Names this long or shorter, counting the original input if it is kept,
+/// are stored without allocating
+const INLINE_NAME_BYTES: usize = 64;
+/// `DnsName::flags` bit: `%XX` escapes were decoded from the input
+const PERCENT_DECODED: u8 = 1;
+/// `DnsName::flags` bit: the input ended in a port, which the original input
+/// still has
+const HAS_PORT: u8 = 2;
+
 /// Holds information about a particular DNS name
 ///
-/// This is created by `List::parse_domain`.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// This is created by `List::parse_domain`. Parts of the name are stored as
+/// offsets into it to keep the struct small.
+///
+/// Names are compared and hashed by their normalized form, as a `str`
+/// would be: the original spelling, and the list that split the name into
+/// suffix and root, do not take part.
+#[derive(Clone)]
 pub struct DnsName {
-    /// full name: foo.example.com
-    name: String,
-    /// name, reversed by character: moc.elpmaxe.oof
-    rname: String,
-    /// suffix: com
-    suffix: Option<Range<usize>>,
-    /// root: example.com
-    root: Option<Range<usize>>,
-    /// registrable: example
-    registrable: Option<Range<usize>>,
+    /// full name: foo.example.com, followed by the original input if it was
+    /// spelled differently: FOO.Example.com
+    buf: InlineStr<INLINE_NAME_BYTES>,
+    /// start of the suffix (com), or `NO_OFFSET`
+    suffix: u16,
+    /// start of the root (example.com), or `NO_OFFSET`; the registrable
+    /// label (example) runs from here to the dot before the suffix
+    root: u16,
+    /// start of the original input in `buf`, or `NO_OFFSET` if it is the
+    /// same as the name
+    original: u16,
+    /// the kind of rule that gave the suffix
+    origin: Option<SuffixOrigin>,
+    /// `PERCENT_DECODED` and `HAS_PORT`
+    flags: u8,
 }
 
 impl List {
-    fn append(&mut self, mut rule: &str) -> io::Result<()> {
+    fn append(&mut self, rule: &str) -> io::Result<()> {
+        self.append_with(rule, None, false)
+    }
+
+    /// Appends a rule with metadata, replacing any the rule already had, and
+    /// whether it is a private domain
+    fn append_with(
+        &mut self,
+        mut rule: &str,
+        metadata: Option<Arc<str>>,
+        private: bool,
+    ) -> io::Result<()> {
+        let original = rule;
         let mut is_exception_rule = false;
         if rule.starts_with('!') {
             is_exception_rule = true;
@@ -112,11 +402,17 @@ impl List {
             let cur = current;
             current = cur
                 .children
-                .entry(label.to_owned())
+                .entry(Arc::from(label))
                 .or_insert_with(ListNode::new);
         }
 
-        current.leaf = Some(ListLeaf::new(is_exception_rule));
+        // a rule listed outside the private section is never private
+        let mut private = private;
+        if let Some(leaf) = &current.leaf {
+            self.duplicates.push(original.to_owned());
+            private &= leaf.private;
+        }
+        current.leaf = Some(ListLeaf::new(is_exception_rule, metadata, private));
 
         Ok(())
     }
@@ -126,20 +422,113 @@ impl List {
         for rule in res.split(',') {
             list.append(rule)?;
         }
-        if list.root.children.is_empty() {
-            return Err(io::Error::new(io::ErrorKind::NotFound, "invalid list"));
-        }
-        list.append(PREVAILING_STAR_RULE)?; // add the default rule
+        list.finish(true)?;
         Ok(list)
     }
 
+    /// Finds the rule deciding the suffix of `domain`, whose labels are at
+    /// `labels`
+    fn rule_for(
+        &self,
+        domain: &str,
+        labels: &[Range<usize>],
+        algorithm: Algorithm,
+    ) -> Option<(&ListLeaf, usize, bool)> {
+        match algorithm {
+            Algorithm::Legacy => {
+                self.longest_rule(labels.iter().rev().map(|label| &domain[label.clone()]))
+            }
+            Algorithm::PslSpec => self.prevailing_rule(labels.len(), |i| {
+                &domain[labels[labels.len() - 1 - i].clone()]
+            }),
+        }
+    }
+
+    /// Walks the trie over `labels`, given right to left, to the longest
+    /// matching rule: its leaf, how many labels it has, and whether a
+    /// wildcard matched any of them
+    fn longest_rule<'a, 'b>(
+        &'a self,
+        labels: impl Iterator<Item = &'b str>,
+    ) -> Option<(&'a ListLeaf, usize, bool)> {
+        let mut longest_valid = None;
+        let mut current = &self.root;
+        let mut s_labels_len = 0;
+        let mut wildcard = false;
+
+        for label in labels {
+            if let Some(child) = current.children.get(label) {
+                current = child;
+                s_labels_len += 1;
+            } else if let Some(child) = current.children.get("*") {
+                // wildcard rule
+                current = child;
+                s_labels_len += 1;
+                wildcard = true;
+            } else {
+                // no match rules
+                break;
+            }
+
+            if let Some(list_leaf) = &current.leaf {
+                longest_valid = Some((list_leaf, s_labels_len, wildcard));
+            }
+        }
+        longest_valid
+    }
+
+    fn has_prevailing_rule(&self) -> bool {
+        self.root
+            .children
+            .get(PREVAILING_STAR_RULE)
+            .is_some_and(|node| node.leaf.is_some())
+    }
+
+    /// Describes the `ListSource` the List was loaded from, if any
+    ///
+    /// When a source has fallbacks this is the one that was used.
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
     /// Creates an empty List without any rules
+    ///
+    /// No name parsed with it has a suffix; `List::minimal` has the default
+    /// `*` rule.
     pub fn empty() -> List {
         List {
             root: ListNode::new(),
+            duplicates: Vec::new(),
+            source: None,
+            observer: None,
         }
     }
 
+    /// Creates a List with only the default `*` rule
+    ///
+    /// This is the publicsuffix.org algorithm for a name under a TLD the
+    /// list does not know, applied to every name: the last label is the
+    /// suffix and the root is one label more. Without a list file this gives
+    /// sane results for most names, though not for those under multi-label
+    /// suffixes such as `co.uk`.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::minimal();
+    /// let name = list.parse_dns_name("www.example.com").unwrap();
+    /// assert_eq!(name.suffix(), Some("com"));
+    /// assert_eq!(name.root(), Some("example.com"));
+    /// let name = list.parse_dns_name("www.example.co.uk").unwrap();
+    /// assert_eq!(name.root(), Some("co.uk"));
+    /// assert_eq!(List::empty().parse_dns_name("example.com").unwrap().suffix(), None);
+    /// ```
+    pub fn minimal() -> List {
+        let mut list = List::empty();
+        // `*` is a valid rule, so this cannot fail
+        let _ = list.append(PREVAILING_STAR_RULE);
+        list
+    }
+
     /// Fetch the list from a local file
     pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<List> {
         File::open(path)
@@ -163,24 +552,156 @@ impl List {
         Self::build(&res)
     }
 
+    /// Build the list one line at a time from anything that implements
+    /// `std::io::BufRead`
+    ///
+    /// Unlike `from_reader` the input is never buffered as a whole, so lists
+    /// of any size can be streamed in from pipes or sockets. Both the
+    /// comma-separated format and the publicsuffix.org format (one rule per
+    /// line, `//` comments) are accepted.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let data = "// comment\ncom\nuk.com,*.ck\n\n!www.ck\n";
+    /// let list = List::from_buf_reader(data.as_bytes()).unwrap();
+    /// let name = list.parse_dns_name("www.example.uk.com").unwrap();
+    /// assert_eq!(name.suffix(), Some("uk.com"));
+    /// ```
+    pub fn from_buf_reader<R: BufRead>(reader: R) -> io::Result<List> {
+        let mut list = List::empty();
+        list.read_rules(reader, true, false)?;
+        list.finish(true)?;
+        Ok(list)
+    }
+
+    /// Appends the rules read one line at a time from `reader`, skipping
+    /// the private domains section of a publicsuffix.org list unless
+    /// `include_private` is set
+    ///
+    /// With `annotated`, the text after a rule becomes its metadata.
+    fn read_rules<R: BufRead>(
+        &mut self,
+        mut reader: R,
+        include_private: bool,
+        annotated: bool,
+    ) -> io::Result<()> {
+        let mut rules = RuleReader::new(include_private, annotated);
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 {
+            rules.read_line(self, &line)?;
+            line.clear();
+        }
+        Ok(())
+    }
+
+    /// Checks that rules were added, adding the default `*` rule if
+    /// `star_rule` is set and the list has none
+    fn finish(&mut self, star_rule: bool) -> io::Result<()> {
+        if self.root.children.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "invalid list"));
+        }
+        if star_rule && !self.has_prevailing_rule() {
+            self.append(PREVAILING_STAR_RULE)?; // add the default rule
+        }
+        #[cfg(feature = "tracing")]
+        tracing::debug!(duplicates = self.duplicates.len(), "built list");
+        Ok(())
+    }
+
     /// Parses a domain using the list (API backwards compat)
     pub fn parse_domain(&self, domain: &str) -> io::Result<DnsName> {
-        DnsName::parse(domain, self)
+        DnsName::find_match(domain, self, &ParseOptions::default())
     }
 
     /// Parses a DNS name using the list
     pub fn parse_dns_name(&self, domain: &str) -> io::Result<DnsName> {
-        DnsName::parse(domain, self)
+        DnsName::find_match(domain, self, &ParseOptions::default())
+    }
+
+    /// Parses a DNS name from raw bytes using the list
+    ///
+    /// For untrusted input: any byte string is either parsed or rejected
+    /// with an error, never a panic. Bytes that are not UTF-8 are rejected
+    /// with `io::ErrorKind::InvalidData`; use `\DDD` escapes for arbitrary
+    /// octets.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// assert!(list.parse_dns_name_bytes(b"www.example.com").is_ok());
+    /// assert!(list.parse_dns_name_bytes(b"\xff.example.com").is_err());
+    /// ```
+    pub fn parse_dns_name_bytes(&self, domain: &[u8]) -> io::Result<DnsName> {
+        self.parse_dns_name_bytes_with(domain, &ParseOptions::default())
+    }
+
+    /// Parses a DNS name from raw bytes using the list, with extra checks
+    /// enabled by `options`
+    ///
+    /// `ParseOptions::invalid_utf8` decides whether bytes that are not UTF-8
+    /// are rejected, escaped or replaced.
+    pub fn parse_dns_name_bytes_with(
+        &self,
+        domain: &[u8],
+        options: &ParseOptions,
+    ) -> io::Result<DnsName> {
+        let domain = match (std::str::from_utf8(domain), options.invalid_utf8) {
+            (Ok(domain), _) => Cow::Borrowed(domain),
+            (Err(err), InvalidUtf8::Reject) => {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, err))
+            }
+            (Err(_), InvalidUtf8::Escape) => Cow::Owned(escape::escape_invalid_utf8(domain)),
+            (Err(_), InvalidUtf8::Lossy) => String::from_utf8_lossy(domain),
+        };
+        self.parse_dns_name_with(&domain, options)
+    }
+
+    /// Parses a DNS name using the list, with extra checks enabled by
+    /// `options`
+    pub fn parse_dns_name_with(&self, domain: &str, options: &ParseOptions) -> io::Result<DnsName> {
+        DnsName::find_match(domain, self, options)
+    }
+
+    /// Gets the public suffix of a name without building a `DnsName`
+    ///
+    /// This is `parse_dns_name(domain)?.suffix()`, with a trailing dot if
+    /// the name has one, but only the suffix is copied. Parses made this
+    /// way are not reported to a `ParseObserver`.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// assert_eq!(list.suffix_of("www.Example.co.uk").unwrap().as_deref(), Some("co.uk"));
+    /// assert_eq!(list.root_of("www.example.co.uk.").unwrap().as_deref(), Some("example.co.uk."));
+    /// assert_eq!(list.root_of("co.uk").unwrap(), None);
+    /// ```
+    pub fn suffix_of(&self, domain: &str) -> io::Result<Option<String>> {
+        let mut scratch = Scratch::default();
+        let split = DnsName::split(domain, self, None, &ParseOptions::default(), &mut scratch)?;
+        Ok(split.part(&split.suffix))
+    }
+
+    /// Gets the root domain of a name without building a `DnsName`
+    ///
+    /// This is `parse_dns_name(domain)?.root()`, as with `suffix_of`.
+    pub fn root_of(&self, domain: &str) -> io::Result<Option<String>> {
+        let mut scratch = Scratch::default();
+        let split = DnsName::split(domain, self, None, &ParseOptions::default(), &mut scratch)?;
+        Ok(split.part(&split.root))
     }
 
     /// Converts a TrustDNS [`Name`] into a `DnsName`
     ///
-    /// [`Name`]: trust_dns_proto::rr::domain::Name
+    /// Requires the `trust-dns` feature (enabled by default). trust-dns has
+    /// been renamed to hickory-dns, new code should use `from_hickory_name`.
+    ///
+    /// [`Name`]: hickory_proto::rr::domain::Name
+    #[cfg(feature = "trust-dns")]
     pub fn from_trustdns_name(
         &self,
         name: &hickory_proto::rr::domain::Name,
     ) -> io::Result<DnsName> {
-        self.parse_dns_name(&name.to_ascii())
+        self.from_hickory_name(name)
     }
 }
 
@@ -193,121 +714,368 @@ impl std::str::FromStr for List {
 }
 
 impl DnsName {
-    fn new(name: String, suffix: Option<Range<usize>>, root: Option<Range<usize>>) -> DnsName {
-        let rname = name.chars().rev().collect::<String>();
+    /// Creates a name from the ranges of its suffix and root, which end at
+    /// the end of the name without its trailing dot
+    fn new(
+        name: impl Into<InlineStr<INLINE_NAME_BYTES>>,
+        suffix: Option<Range<usize>>,
+        root: Option<Range<usize>>,
+    ) -> DnsName {
+        let (suffix, root) = Self::offsets(suffix, root);
+        DnsName {
+            buf: name.into(),
+            suffix,
+            root,
+            original: NO_OFFSET,
+            origin: None,
+            flags: 0,
+        }
+    }
 
-        let registrable = if let (Some(suffix), Some(root)) = (suffix.as_ref(), root.as_ref()) {
-            Some(Range {
-                start: root.start,
-                end: suffix.start - 1,
-            })
-        } else {
-            None
+    /// Offsets of the suffix and root, or `NO_OFFSET`
+    fn offsets(suffix: Option<Range<usize>>, root: Option<Range<usize>>) -> (u16, u16) {
+        let offset = |range: Option<Range<usize>>| {
+            range
+                .and_then(|range| u16::try_from(range.start).ok())
+                .unwrap_or(NO_OFFSET)
         };
+        let suffix = offset(suffix);
+        // a root always starts before its suffix
+        let root = match offset(root) {
+            root if root < suffix => root,
+            _ => NO_OFFSET,
+        };
+        (suffix, root)
+    }
 
-        DnsName {
-            name,
-            rname,
-            root,
-            suffix,
-            registrable,
+    /// Keeps the input the name was parsed from, if it is spelled
+    /// differently, in place of any kept before
+    fn with_original(mut self, original: &str) -> DnsName {
+        if original == self.original() {
+            return self;
         }
+        let name = self.name();
+        let len = name.len();
+        if original == name {
+            self.buf = InlineStr::from(name);
+            self.original = NO_OFFSET;
+        } else {
+            self.buf = InlineStr::concat(name, original);
+            // names are never longer than `MAX_NAME_BYTES`
+            self.original = len as u16;
+        }
+        self
     }
 
-    /// Counts the length of 1 or more labels, counting from reverse
-    ///
-    /// ("b.example.uk.com", 2) -> "uk.com" -> 6
-    fn subname_length(input: &str, s_len: usize) -> usize {
-        let len = input
-            .trim_end_matches('.')
-            .split('.')
-            .rev()
-            .take(s_len)
-            .fold(0, |acc, part| acc + part.len());
+    /// Range of a part of the name starting at `offset`
+    fn range(&self, offset: u16) -> Option<Range<usize>> {
+        let start = usize::from(offset);
+        (offset != NO_OFFSET && start < self.name().len())
+            .then(|| start..escape::trim_dot(self.name()).len())
+    }
 
-        // Add in "." seperators
-        len + (s_len - 1)
+    /// Range of the suffix, without the trailing dot
+    fn suffix_range(&self) -> Option<Range<usize>> {
+        self.range(self.suffix)
+    }
+
+    /// Range of the root domain, without the trailing dot
+    fn root_range(&self) -> Option<Range<usize>> {
+        self.range(self.root)
+    }
+
+    /// Range of the registrable label, the first label of the root
+    ///
+    /// Taken from the root alone rather than from the offsets of the root
+    /// and suffix, so it holds however the two are spelled.
+    fn registrable_range(&self) -> Option<Range<usize>> {
+        let root = self.root_range()?;
+        let (label, _) = escape::split_first_label(&self.name()[root.clone()])?;
+        Some(root.start..root.start + label.len())
     }
 
     /// Finds a match in the Public Suffix list
-    fn find_match(input: &str, list: &List) -> io::Result<DnsName> {
+    fn find_match(input: &str, list: &List, options: &ParseOptions) -> io::Result<DnsName> {
+        Self::find_match_in(input, list, options, &mut Scratch::default())
+    }
+
+    /// Finds a match in the Public Suffix list, reusing `scratch` for the
+    /// lowercased name and its labels
+    fn find_match_in(
+        input: &str,
+        list: &List,
+        options: &ParseOptions,
+        scratch: &mut Scratch,
+    ) -> io::Result<DnsName> {
+        Self::find_match_layered(input, list, None, options, scratch)
+    }
+
+    /// Finds a match in the Public Suffix list, with `overrides` taking
+    /// precedence over rules of the same length or shorter
+    fn find_match_layered(
+        input: &str,
+        list: &List,
+        overrides: Option<&List>,
+        options: &ParseOptions,
+        scratch: &mut Scratch,
+    ) -> io::Result<DnsName> {
+        let result = Self::match_rules(input, list, overrides, options, scratch)
+            .map(|(name, listed)| (name.with_original(input), listed));
+
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok((name, false)) => tracing::trace!(name = name.name(), "no listed suffix"),
+            Ok(_) => {}
+            Err(err) => tracing::debug!(input, error = %err, "rejected name"),
+        }
+        if let Some(observer) = &list.observer {
+            match &result {
+                Ok((name, listed)) => observer.parsed(name, *listed),
+                Err(err) => observer.rejected(input, err),
+            }
+        }
+
+        result.map(|(name, _)| name)
+    }
+
+    /// Matches a name against the list, and whether its suffix is listed
+    /// rather than given by the default `*` rule
+    fn match_rules(
+        input: &str,
+        list: &List,
+        overrides: Option<&List>,
+        options: &ParseOptions,
+        scratch: &mut Scratch,
+    ) -> io::Result<(DnsName, bool)> {
+        let split = Self::split(input, list, overrides, options, scratch)?;
+        let listed = split.listed;
+        Ok((split.into_name(), listed))
+    }
+
+    /// Matches a name against the list without building a `DnsName`
+    fn split<'a>(
+        input: &'a str,
+        list: &List,
+        overrides: Option<&List>,
+        options: &ParseOptions,
+        scratch: &'a mut Scratch,
+    ) -> io::Result<Split<'a>> {
+        let (input, port) = host::strip_decoration(input, options)?;
+        let mut split = Self::split_name(input, list, overrides, options, scratch)?;
+        split.port = port;
+        Ok(split)
+    }
+
+    /// Matches a name, without brackets or port, against the list
+    // `Option::is_none_or` needs Rust 1.82
+    #[allow(clippy::unnecessary_map_or)]
+    fn split_name<'a>(
+        input: &'a str,
+        list: &List,
+        overrides: Option<&List>,
+        options: &ParseOptions,
+        scratch: &'a mut Scratch,
+    ) -> io::Result<Split<'a>> {
+        let (input, all_labels, labels, decoded) = match Self::prepare(input, options, scratch)? {
+            Prepared::Done(split) => return Ok(split),
+            Prepared::Labels {
+                input,
+                all_labels,
+                labels,
+                decoded,
+            } => (input, all_labels, labels, decoded),
+        };
+        let domain = escape::trim_dot(input);
+
+        let algorithm = options.algorithm;
+        let mut longest_valid = list.rule_for(domain, labels, algorithm);
+        if let Some(found) =
+            overrides.and_then(|overrides| overrides.rule_for(domain, labels, algorithm))
+        {
+            if longest_valid.map_or(true, |(_, len, _)| found.1 >= len) {
+                longest_valid = Some(found);
+            }
+        }
+
+        let mut split = Self::split_at(input, all_labels, labels, longest_valid, options)?;
+        split.percent_decoded = decoded;
+        Ok(split)
+    }
+
+    /// Lowercases and checks a name, and finds its labels
+    fn prepare<'a>(
+        input: &'a str,
+        options: &ParseOptions,
+        scratch: &'a mut Scratch,
+    ) -> io::Result<Prepared<'a>> {
         // root domain is permitted
         if input.len() == 1 && input.starts_with('.') {
-            return Ok(DnsName::new(input.to_owned(), None, None));
+            return Ok(Prepared::Done(Split::whole(input, true)));
+        }
+        if input.is_empty() {
+            return match options.empty_as_root {
+                true => Ok(Prepared::Done(Split::whole(".", true))),
+                false => Err(ParseError::EmptyName.into()),
+            };
         }
 
+        let input = match input.strip_prefix('.') {
+            Some(rest) if options.leading_dot => rest,
+            _ => input,
+        };
         // a name cannot start with '.'
         if input.starts_with('.') {
-            return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid name"));
+            return Err(ParseError::InvalidLabel {
+                index: 0,
+                offset: 0,
+                reason: LabelError::Empty,
+            }
+            .into());
         }
 
-        let mut longest_valid = None;
-        let mut current = &list.root;
-        let mut s_labels_len = 0;
-
-        let input = input.to_ascii_lowercase();
-        let domain = input.trim_end_matches('.');
+        let Scratch { input: buf, labels } = scratch;
+        let decode = options.percent_decoding && input.contains('%');
+        let mut decoded = false;
+        // names are nearly always lowercase already, so only copy the input
+        // when it has to be rewritten
+        let input = if decode || input.bytes().any(|b| b.is_ascii_uppercase() || b == b'\\') {
+            buf.clear();
+            match decode {
+                true => decoded = escape::percent_decode_into(input, buf),
+                false => buf.push_str(input),
+            }
+            buf.make_ascii_lowercase();
+            if buf.contains('\\') {
+                *buf = escape::canonicalize(buf)?;
+            }
+            buf.as_str()
+        } else {
+            input
+        };
+        if input.len() > MAX_NAME_BYTES {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "name too long"));
+        }
+        if options.ip_policy != IpPolicy::ParseAsName && host::parse_ip(input).is_some() {
+            return match options.ip_policy {
+                IpPolicy::Reject => Err(ParseError::IpLiteral.into()),
+                _ => Ok(Prepared::Done(Split {
+                    percent_decoded: decoded,
+                    ..Split::whole(input, false)
+                })),
+            };
+        }
+        let domain = escape::trim_dot(input);
+        escape::label_ranges_into(domain, labels);
+        let mut labels = &labels[..];
+        let all_labels = labels;
 
         // very basic sanity check the labels
-        for label in domain.split('.') {
-            if label.is_empty() || label.contains(' ') {
-                return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid name"));
+        for (index, range) in labels.iter().enumerate() {
+            let label = &domain[range.clone()];
+            let invalid = |offset, reason| ParseError::InvalidLabel {
+                index,
+                offset: range.start + offset,
+                reason,
+            };
+            if label.is_empty() {
+                return Err(invalid(0, LabelError::Empty).into());
+            }
+            if let Some(space) = label.find(' ') {
+                return Err(invalid(space, LabelError::InvalidCharacter(' ')).into());
             }
         }
-
-        for label in domain.rsplit('.') {
-            if let Some(child) = current.children.get(label) {
-                current = child;
-                s_labels_len += 1;
-            } else if let Some(child) = current.children.get("*") {
-                // wildcard rule
-                current = child;
-                s_labels_len += 1;
-            } else {
-                // no match rules
-                break;
+        if labels.len() > options.label_limit() {
+            return Err(ParseError::TooManyLabels {
+                count: labels.len(),
+                max: options.label_limit(),
             }
+            .into());
+        }
 
-            if let Some(list_leaf) = &current.leaf {
-                longest_valid = Some((list_leaf, s_labels_len));
-            }
+        let wildcard =
+            options.wildcard_names && labels.len() > 1 && &domain[labels[0].clone()] == "*";
+        validate::check(domain, labels, wildcard, options)?;
+
+        // a wildcard stays in the name but takes no part in matching
+        if wildcard {
+            labels = &labels[1..];
         }
 
-        match longest_valid {
-            Some((leaf, suffix_len)) => {
+        Ok(Prepared::Labels {
+            input,
+            all_labels,
+            labels,
+            decoded,
+        })
+    }
+
+    /// Splits a prepared name at the suffix given by `rule`
+    fn split_at<'a>(
+        input: &'a str,
+        all_labels: &[Range<usize>],
+        labels: &[Range<usize>],
+        rule: Option<(&ListLeaf, usize, bool)>,
+        options: &ParseOptions,
+    ) -> io::Result<Split<'a>> {
+        let domain = escape::trim_dot(input);
+        match rule {
+            Some((leaf, suffix_len, wildcard)) => {
+                let listed = !(wildcard && suffix_len == 1);
+                let origin = match (leaf.is_exception_rule, wildcard) {
+                    (true, _) => SuffixOrigin::Exception,
+                    (false, true) if !listed => SuffixOrigin::Default,
+                    (false, true) => SuffixOrigin::Wildcard,
+                    (false, false) => SuffixOrigin::Exact,
+                };
                 let suffix_len = if leaf.is_exception_rule {
                     suffix_len - 1
                 } else {
                     suffix_len
                 };
+                if suffix_len == 0 {
+                    // a top level exception rule leaves no suffix
+                    return Ok(Split::whole(input, listed));
+                }
+
+                let d_labels_len = labels.len();
 
                 let suffix = Some(Range {
-                    start: domain.len() - Self::subname_length(domain, suffix_len),
+                    start: labels[d_labels_len - suffix_len].start,
                     end: domain.len(),
                 });
 
-                let d_labels_len = domain.match_indices('.').count() + 1;
-
                 let registrable = if d_labels_len > suffix_len {
                     Some(Range {
-                        start: domain.len() - Self::subname_length(domain, suffix_len + 1),
+                        start: labels[d_labels_len - suffix_len - 1].start,
                         end: domain.len(),
                     })
                 } else {
                     None
                 };
+                if let (Some(max), Some(root)) = (options.max_depth, &registrable) {
+                    let depth = all_labels
+                        .iter()
+                        .take_while(|label| label.start < root.start)
+                        .count();
+                    if depth > max {
+                        return Err(ParseError::TooDeep { depth, max }.into());
+                    }
+                }
 
-                Ok(DnsName::new(input, suffix, registrable))
+                Ok(Split {
+                    name: input,
+                    suffix,
+                    root: registrable,
+                    listed,
+                    origin: Some(origin),
+                    port: None,
+                    percent_decoded: false,
+                })
             }
-            None => Ok(DnsName::new(input, None, None)),
+            None => Ok(Split::whole(input, false)),
         }
     }
 
-    /// Parses a DNS name using the list
-    fn parse(domain: &str, list: &List) -> io::Result<DnsName> {
-        Self::find_match(domain, list)
-    }
-
     /// Get the DNS name
     ///
     /// ```rust
@@ -317,18 +1085,209 @@ impl DnsName {
     /// assert_eq!(name.name(), "www.example.com");
     /// ```
     pub fn name(&self) -> &str {
-        &self.name
+        match self.original {
+            NO_OFFSET => &self.buf,
+            end => &self.buf[..usize::from(end)],
+        }
+    }
+
+    /// Get the input the name was parsed from, before it was lowercased and
+    /// its escapes canonicalized
+    ///
+    /// Names that were not parsed from text, such as those made by
+    /// `DnsName::parent`, return the same as `name`.
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::empty();
+    /// let name = list.parse_domain("WWW.Example.com").unwrap();
+    /// assert_eq!(name.original(), "WWW.Example.com");
+    /// assert_eq!(name.name(), "www.example.com");
+    /// assert_eq!(name, list.parse_domain("www.example.com").unwrap());
+    /// ```
+    pub fn original(&self) -> &str {
+        match self.original {
+            NO_OFFSET => &self.buf,
+            start => &self.buf[usize::from(start)..],
+        }
     }
 
     /// Get the DNS name in character reversed order
+    ///
+    /// This is computed on each call rather than stored, so it is only
+    /// borrowed from the name when the name reads the same reversed, like
+    /// the root name.
     /// ```rust
     /// # use dns_name::{List, DnsName};
     /// let list = List::empty();
     /// let name = list.parse_domain("www.example.com").unwrap();
     /// assert_eq!(name.rname(), "moc.elpmaxe.www");
     /// ```
-    pub fn rname(&self) -> &str {
-        &self.rname
+    pub fn rname(&self) -> Cow<'_, str> {
+        let name = self.name();
+        if name.chars().eq(name.chars().rev()) {
+            return Cow::Borrowed(name);
+        }
+        Cow::Owned(name.chars().rev().collect())
+    }
+
+    /// Gets a key for ordering names in an index, with the labels in reverse
+    /// order and no trailing dot
+    ///
+    /// Names under a domain share the key prefix of that domain followed by
+    /// a dot, so a B-tree range scan over `com.example.` finds every name
+    /// under `example.com`. Names are already lowercase, and escapes are
+    /// kept, so distinct names have distinct keys. The root name's key is
+    /// empty.
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::empty();
+    /// let name = list.parse_domain("www.Example.com.").unwrap();
+    /// assert_eq!(name.sort_key(), "com.example.www");
+    /// ```
+    pub fn sort_key(&self) -> String {
+        let domain = escape::trim_dot(self.name());
+        let mut key = String::with_capacity(domain.len());
+        for label in escape::label_ranges(domain).into_iter().rev() {
+            if !key.is_empty() {
+                key.push('.');
+            }
+            key.push_str(&domain[label]);
+        }
+        key
+    }
+
+    /// Returns true if this is the root name `.`
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::empty();
+    /// assert!(list.parse_domain(".").unwrap().is_root());
+    /// assert!(!list.parse_domain("com.").unwrap().is_root());
+    /// ```
+    pub fn is_root(&self) -> bool {
+        self.name() == "."
+    }
+
+    /// Returns true if a leading dot was dropped from the input, as allowed
+    /// by `ParseOptions::leading_dot`
+    pub fn had_leading_dot(&self) -> bool {
+        self.original().starts_with('.') && !self.is_root()
+    }
+
+    /// Returns true if `%XX` escapes were decoded from the input, as allowed
+    /// by `ParseOptions::percent_decoding`
+    pub fn was_percent_decoded(&self) -> bool {
+        self.flags & PERCENT_DECODED != 0
+    }
+
+    /// Returns true if the name was given with a trailing dot
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::empty();
+    /// assert!(list.parse_domain("www.example.com.").unwrap().is_fqdn());
+    /// assert!(!list.parse_domain("www.example.com").unwrap().is_fqdn());
+    /// ```
+    pub fn is_fqdn(&self) -> bool {
+        self.name() == "." || escape::trim_dot(self.name()).len() < self.name().len()
+    }
+
+    /// Get the DNS name with a trailing dot
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::empty();
+    /// let name = list.parse_domain("www.example.com").unwrap();
+    /// assert_eq!(name.to_fqdn(), "www.example.com.");
+    /// ```
+    pub fn to_fqdn(&self) -> String {
+        if self.is_fqdn() {
+            self.name().to_string()
+        } else {
+            format!("{}.", self.name())
+        }
+    }
+
+    /// Formats the name as a fully qualified name, with a trailing dot
+    ///
+    /// This is the same as `to_fqdn`.
+    pub fn format_fqdn(&self) -> String {
+        self.to_fqdn()
+    }
+
+    /// Formats the name without a trailing dot
+    ///
+    /// The root name is formatted as `"."`, as with `without_trailing_dot`.
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::empty();
+    /// let name = list.parse_domain("www.example.com.").unwrap();
+    /// assert_eq!(name.format_relative(), "www.example.com");
+    /// assert_eq!(name.format_fqdn(), "www.example.com.");
+    /// assert_eq!(name.to_string(), "www.example.com.");
+    /// ```
+    pub fn format_relative(&self) -> String {
+        self.without_trailing_dot().to_owned()
+    }
+
+    /// Returns true if the name has a single label, like `localhost` or
+    /// `com`
+    ///
+    /// Such names parse as a bare suffix with no root domain. The root name
+    /// is not dotless.
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// assert!(list.parse_dns_name("localhost.").unwrap().is_dotless());
+    /// assert!(!list.parse_dns_name("example.com").unwrap().is_dotless());
+    /// ```
+    pub fn is_dotless(&self) -> bool {
+        let domain = escape::trim_dot(self.name());
+        !domain.is_empty() && escape::split_first_label(domain).is_none()
+    }
+
+    /// Returns true if the leftmost label is `*`, like `*.example.com`
+    ///
+    /// See `ParseOptions::wildcard_names` for parsing such names.
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::empty();
+    /// assert!(list.parse_dns_name("*.example.com").unwrap().is_wildcard());
+    /// assert!(!list.parse_dns_name("www.example.com").unwrap().is_wildcard());
+    /// ```
+    pub fn is_wildcard(&self) -> bool {
+        let domain = escape::trim_dot(self.name());
+        escape::split_first_label(domain).map_or(domain, |(label, _)| label) == "*"
+    }
+
+    /// Returns true if the last label is all digits, like `247` in
+    /// `127.38.53.247`
+    ///
+    /// No top-level domain is numeric, so such a name is likely a mangled
+    /// IPv4 address rather than a real domain.
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// assert!(list.parse_dns_name("127.0.0.256").unwrap().has_numeric_tld());
+    /// assert!(!list.parse_dns_name("127.com").unwrap().has_numeric_tld());
+    /// ```
+    pub fn has_numeric_tld(&self) -> bool {
+        let domain = escape::trim_dot(self.name());
+        let tld = escape::label_ranges(domain).pop().unwrap_or_default();
+        validate::is_numeric(&domain[tld])
+    }
+
+    /// Get the DNS name without a trailing dot
+    ///
+    /// The root name has nothing but the dot, so it is returned as `"."`.
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::empty();
+    /// let name = list.parse_domain("www.example.com.").unwrap();
+    /// assert_eq!(name.without_trailing_dot(), "www.example.com");
+    /// ```
+    pub fn without_trailing_dot(&self) -> &str {
+        match escape::trim_dot(self.name()) {
+            "" => self.name(),
+            name => name,
+        }
     }
 
     /// Gets the root domain portion of the Name
@@ -339,10 +1298,7 @@ impl DnsName {
     /// assert_eq!(name.root(), Some("example.com"));
     /// ```
     pub fn root(&self) -> Option<&str> {
-        match self.root {
-            Some(ref root) if root.start < self.name.len() => Some(&self.name[root.start..]),
-            _ => None,
-        }
+        self.root_range().map(|root| &self.name()[root.start..])
     }
 
     /// Gets the suffix portion of the Name
@@ -353,13 +1309,38 @@ impl DnsName {
     /// assert_eq!(name.root(), Some("com"));
     /// ```
     pub fn suffix(&self) -> Option<&str> {
-        match self.suffix {
-            Some(ref suffix) if suffix.start < self.name.len() => Some(&self.name[suffix.start..]),
-            _ => None,
-        }
+        self.suffix_range()
+            .map(|suffix| &self.name()[suffix.start..])
+    }
+
+    /// Gets the root domain portion of the Name, never with a trailing dot
+    ///
+    /// `root` keeps the dot of a fully qualified name; this does not, so it
+    /// compares directly against rules and other names. `is_fqdn` tells
+    /// whether the dot was there.
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_domain("www.example.uk.com.").unwrap();
+    /// assert_eq!(name.root(), Some("example.uk.com."));
+    /// assert_eq!(name.root_relative(), Some("example.uk.com"));
+    /// assert_eq!(name.suffix_relative(), Some("uk.com"));
+    /// assert!(name.is_fqdn());
+    /// ```
+    pub fn root_relative(&self) -> Option<&str> {
+        self.root_range().map(|root| &self.name()[root])
+    }
+
+    /// Gets the suffix portion of the Name, never with a trailing dot
+    ///
+    /// See `root_relative`.
+    pub fn suffix_relative(&self) -> Option<&str> {
+        self.suffix_range().map(|suffix| &self.name()[suffix])
     }
 
     /// Gets the registrable portion of the Name
+    ///
+    /// This is the registrable label; see `registrable_label`.
     /// ```should_panic
     /// # use dns_name::{List, DnsName};
     /// let list = List::empty();
@@ -367,20 +1348,213 @@ impl DnsName {
     /// assert_eq!(name.root(), Some("example"));
     /// ```
     pub fn registrable(&self) -> Option<&str> {
-        match self.registrable {
-            Some(ref registrable)
-                if registrable.start < self.name.len() && registrable.end < self.name.len() =>
-            {
-                Some(&self.name[registrable.start..registrable.end])
-            }
+        self.registrable_label()
+    }
+
+    /// Gets the label registered under the suffix, like `example` in
+    /// `www.example.co.uk`
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.example.co.uk.").unwrap();
+    /// assert_eq!(name.registrable_label(), Some("example"));
+    /// assert_eq!(list.parse_dns_name("co.uk").unwrap().registrable_label(), None);
+    /// ```
+    pub fn registrable_label(&self) -> Option<&str> {
+        self.registrable_range().map(|label| &self.name()[label])
+    }
+
+    /// Gets the registrable domain, like `example.co.uk` in
+    /// `www.example.co.uk`
+    ///
+    /// Other public suffix libraries call this the registrable domain or
+    /// eTLD+1. It is the same as `root`, trailing dot included.
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.example.co.uk").unwrap();
+    /// assert_eq!(name.registrable_domain(), Some("example.co.uk"));
+    /// ```
+    pub fn registrable_domain(&self) -> Option<&str> {
+        self.root()
+    }
+
+    /// Gets the subdomain portion of the Name, the labels left of the root
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("a.b.example.co.uk").unwrap();
+    /// assert_eq!(name.subdomain(), Some("a.b"));
+    /// let name = list.parse_dns_name("example.co.uk").unwrap();
+    /// assert_eq!(name.subdomain(), None);
+    /// ```
+    pub fn subdomain(&self) -> Option<&str> {
+        match self.root_range() {
+            Some(root) if root.start > 1 => Some(&self.name()[..root.start - 1]),
             _ => None,
         }
     }
+
+    /// Gets the DMARC Organizational Domain of the Name
+    ///
+    /// Implements [RFC 7489 section 3.2]: the public suffix plus one label.
+    /// A name that is itself a public suffix is its own Organizational
+    /// Domain. When no rule matched at all (an empty list), the last label is
+    /// treated as the public suffix. The root name has none.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, DnsName};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("mail.example.co.uk").unwrap();
+    /// assert_eq!(name.organizational_domain(), Some("example.co.uk"));
+    ///
+    /// let name = List::empty().parse_dns_name("mail.example.com").unwrap();
+    /// assert_eq!(name.organizational_domain(), Some("example.com"));
+    /// ```
+    ///
+    /// [RFC 7489 section 3.2]: https://www.rfc-editor.org/rfc/rfc7489#section-3.2
+    pub fn organizational_domain(&self) -> Option<&str> {
+        if let Some(root) = self.root() {
+            return Some(root);
+        }
+        if self.suffix_range().is_some() {
+            return Some(self.name());
+        }
+        if self.name() == "." {
+            return None;
+        }
+        let labels = escape::label_ranges(escape::trim_dot(self.name()));
+        let start = labels[labels.len().saturating_sub(2)].start;
+        Some(&self.name()[start..])
+    }
+
+    /// Checks whether two Names are the same site
+    ///
+    /// Names are the same site when their root domains are equal. A name
+    /// without a root domain (a public suffix, the root name) is only the
+    /// same site as itself. Trailing dots are ignored.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let a = list.parse_dns_name("www.example.co.uk").unwrap();
+    /// let b = list.parse_dns_name("login.example.co.uk.").unwrap();
+    /// let c = list.parse_dns_name("www.other.co.uk").unwrap();
+    /// assert!(a.same_site(&b));
+    /// assert!(!a.same_site(&c));
+    /// ```
+    pub fn same_site(&self, other: &DnsName) -> bool {
+        self.org_key() == other.org_key()
+    }
+
+    /// Gets a key for the organization the Name belongs to, such as for
+    /// rate limiting or sharding a cache
+    ///
+    /// The key is the root domain without its trailing dot. Names without
+    /// a root domain fall back to the whole name without its trailing dot:
+    /// a public suffix (`co.uk`), a name under no rule at all, and the root
+    /// name, whose key is `.`. The key is lowercased, non-ASCII characters
+    /// included, so it is only borrowed from the name when there is nothing
+    /// left to lowercase.
+    ///
+    /// Two names have the same key exactly when they are the same site.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let key = |name: &str| list.parse_dns_name(name).unwrap().org_key().into_owned();
+    /// assert_eq!(key("WWW.Example.co.uk."), "example.co.uk");
+    /// assert_eq!(key("co.uk."), "co.uk");
+    /// assert_eq!(key("."), ".");
+    /// ```
+    pub fn org_key(&self) -> Cow<'_, str> {
+        let key = match self.root() {
+            Some(root) => escape::trim_dot(root),
+            None => self.without_trailing_dot(),
+        };
+        if key.chars().any(char::is_uppercase) {
+            return Cow::Owned(key.to_lowercase());
+        }
+        Cow::Borrowed(key)
+    }
+}
+
+impl PartialEq for DnsName {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
 }
 
+impl Eq for DnsName {}
+
+impl Hash for DnsName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name().hash(state);
+    }
+}
+
+impl AsRef<str> for DnsName {
+    fn as_ref(&self) -> &str {
+        self.name()
+    }
+}
+
+/// Lets names be looked up by `&str` in maps and sets keyed by `DnsName`
+///
+/// ```rust
+/// # use std::collections::HashSet;
+/// # use dns_name::{DnsName, List};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let names = ["www.example.com", "Mail.Example.com"]
+///     .into_iter()
+///     .map(|name| DnsName::try_from((&list, name)))
+///     .collect::<Result<HashSet<_>, _>>()
+///     .unwrap();
+/// assert!(names.contains("mail.example.com"));
+/// assert!(!names.contains("Mail.Example.com"));
+/// ```
+impl Borrow<str> for DnsName {
+    fn borrow(&self) -> &str {
+        self.name()
+    }
+}
+
+impl From<DnsName> for String {
+    fn from(name: DnsName) -> String {
+        match name.buf {
+            InlineStr::Heap(buf) if name.original == NO_OFFSET => buf.into_string(),
+            _ => name.name().to_owned(),
+        }
+    }
+}
+
+impl TryFrom<(&List, &str)> for DnsName {
+    type Error = io::Error;
+
+    /// Parses a name with `List::parse_dns_name`
+    fn try_from((list, name): (&List, &str)) -> io::Result<DnsName> {
+        list.parse_dns_name(name)
+    }
+}
+
+impl fmt::Debug for DnsName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DnsName")
+            .field("name", &self.name())
+            .field("original", &self.original())
+            .field("suffix", &self.suffix)
+            .field("root", &self.root)
+            .field("port", &self.port())
+            .field("percent_decoded", &self.was_percent_decoded())
+            .finish()
+    }
+}
+
+/// Writes `name()` exactly, trailing dot included; see `format_relative`
+/// and `format_fqdn` for a fixed form
 impl fmt::Display for DnsName {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.name.trim_end_matches('.').to_lowercase())
+        f.write_str(self.name())
     }
 }
 
@@ -419,6 +1593,162 @@ mod unit_tests {
     }
 
     #[test]
+    fn organizational_domain() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let org = |name: &str| {
+            list.parse_dns_name(name)
+                .unwrap()
+                .organizational_domain()
+                .map(str::to_owned)
+        };
+
+        // examples from RFC 7489 section 4.3 and appendix B
+        assert_eq!(org("example.com").as_deref(), Some("example.com"));
+        assert_eq!(org("news.example.com").as_deref(), Some("example.com"));
+        assert_eq!(org("a.b.c.example.com").as_deref(), Some("example.com"));
+        assert_eq!(
+            org("mail.example.co.uk.").as_deref(),
+            Some("example.co.uk.")
+        );
+        // public suffixes and unlisted TLDs
+        assert_eq!(org("co.uk").as_deref(), Some("co.uk"));
+        assert_eq!(org("a.b.example").as_deref(), Some("b.example"));
+        assert_eq!(org(".").as_deref(), None);
+
+        let empty = List::empty();
+        let org = |name: &str| {
+            empty
+                .parse_dns_name(name)
+                .unwrap()
+                .organizational_domain()
+                .map(str::to_owned)
+        };
+        assert_eq!(org("com").as_deref(), Some("com"));
+        assert_eq!(org("a.b.example.com.").as_deref(), Some("example.com."));
+    }
+
+    #[test]
+    fn same_site() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let same_site = |a: &str, b: &str| {
+            let a = list.parse_dns_name(a).unwrap();
+            let b = list.parse_dns_name(b).unwrap();
+            a.same_site(&b) && b.same_site(&a)
+        };
+
+        assert!(same_site("example.com", "a.b.EXAMPLE.com."));
+        assert!(!same_site("example.com", "example.net"));
+        assert!(!same_site("a.github.io", "b.github.io"));
+        assert!(same_site("github.io", "github.io."));
+        assert!(!same_site("github.io", "a.github.io"));
+        assert!(same_site(".", "."));
+        assert!(!same_site(".", "com"));
+    }
+
+    #[test]
+    fn org_key() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let key = |name: &str| list.parse_dns_name(name).unwrap().org_key().into_owned();
+
+        assert_eq!(key("a.b.Example.COM."), "example.com");
+        assert_eq!(key("x.y.c.kobe.jp"), "y.c.kobe.jp");
+        assert_eq!(key("www.city.kobe.jp"), "city.kobe.jp");
+        assert_eq!(key("github.io."), "github.io");
+        assert_eq!(key("ex\\.ample.com"), "ex\\.ample.com");
+        assert_eq!(key("."), ".");
+        assert_eq!(key("www.BÜCHER.de"), "bücher.de");
+        assert_eq!(key("BÜCHER.de"), key("bücher.de"));
+        assert!(matches!(
+            list.parse_dns_name("bücher.de").unwrap().org_key(),
+            Cow::Borrowed(_)
+        ));
+        let empty = List::empty();
+        assert_eq!(
+            empty.parse_dns_name("www.example.com.").unwrap().org_key(),
+            "www.example.com"
+        );
+    }
+
+    #[test]
+    fn subdomain() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let subdomain = |name: &str| {
+            list.parse_dns_name(name)
+                .unwrap()
+                .subdomain()
+                .map(str::to_owned)
+        };
+
+        assert_eq!(subdomain("www.example.com").as_deref(), Some("www"));
+        assert_eq!(subdomain("a.b.example.com.").as_deref(), Some("a.b"));
+        assert_eq!(subdomain("x.a.b.c.kobe.jp").as_deref(), Some("x.a"));
+        assert_eq!(subdomain("example.com").as_deref(), None);
+        assert_eq!(subdomain("com").as_deref(), None);
+        assert_eq!(subdomain(".").as_deref(), None);
+    }
+
+    #[test]
+    fn fqdn() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+
+        let name = list.parse_dns_name("Example.COM.").unwrap();
+        assert!(name.is_fqdn());
+        assert_eq!(name.to_fqdn(), "example.com.");
+        assert_eq!(name.without_trailing_dot(), "example.com");
+
+        let name = list.parse_dns_name(".").unwrap();
+        assert!(name.is_fqdn());
+        assert_eq!(name.to_fqdn(), ".");
+        assert_eq!(name.without_trailing_dot(), ".");
+    }
+
+    #[test]
+    fn strict_lengths() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let strict = ParseOptions::new().strict_lengths(true);
+        let error = |name: &str| {
+            let err = list.parse_dns_name_with(name, &strict).unwrap_err();
+            ParseError::from_io(&err).cloned()
+        };
+
+        let label = "a".repeat(63);
+        assert!(list.parse_dns_name_with(&label, &strict).is_ok());
+        assert_eq!(
+            error(&format!("a.{label}b.com")),
+            Some(ParseError::LabelTooLong { index: 1, len: 64 })
+        );
+        // escapes count as the octet they stand for
+        let escaped = "\\065".repeat(63);
+        assert!(list.parse_dns_name_with(&escaped, &strict).is_ok());
+
+        // 4 * 63 label octets + 4 length octets + the root = 257
+        let name = [label.as_str(); 4].join(".");
+        assert_eq!(error(&name), Some(ParseError::NameTooLong { len: 257 }));
+        let name = [&label[..61], &label, &label, &label].join(".");
+        assert!(list.parse_dns_name_with(&name, &strict).is_ok());
+        assert!(list
+            .parse_dns_name_with(&format!("{name}."), &strict)
+            .is_ok());
+
+        // lenient by default
+        assert!(list.parse_dns_name(&format!("{label}b.com")).is_ok());
+    }
+
+    #[test]
+    fn buf_reader() {
+        let data = File::open("suffix-list.txt")
+            .map(io::BufReader::new)
+            .unwrap();
+        let list = List::from_buf_reader(data).unwrap();
+        let domain = list.parse_dns_name("a.b.c.kobe.jp").unwrap();
+        assert_eq!(domain.root(), Some("b.c.kobe.jp"));
+
+        assert!(List::from_buf_reader("// nothing here\n\n".as_bytes()).is_err());
+        assert!(List::from_buf_reader("com\na..b\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "trust-dns")]
     fn trustdns() -> Result<(), std::io::Error> {
         use hickory_proto::rr::domain::Name;
         use std::str::FromStr;
@@ -441,86 +1771,9 @@ mod unit_tests {
 
     fn make_list() -> List {
         let list = List::from_path("suffix-list.txt").unwrap();
-
-        let body = File::open("tests.txt")
-            .and_then(|mut data| {
-                let mut res = String::new();
-                data.read_to_string(&mut res)?;
-                Ok(res)
-            })
-            .unwrap();
-
-        let mut parse = false;
-
-        for (i, line) in body.lines().enumerate() {
-            match line {
-                line if line.trim().is_empty() => {
-                    parse = true;
-                    continue;
-                }
-                line if line.starts_with("//") => {
-                    continue;
-                }
-                line => {
-                    if !parse {
-                        continue;
-                    }
-                    let mut test = line.split_whitespace().peekable();
-                    if test.peek().is_none() {
-                        continue;
-                    }
-                    let input = match test.next() {
-                        Some("null") => "",
-                        Some(res) => res,
-                        None => {
-                            panic!("line {i} of the test file doesn't seem to be valid");
-                        }
-                    };
-                    let (expected_root, expected_suffix) = match test.next() {
-                        Some("null") => (None, None),
-                        Some(root) => {
-                            let suffix = {
-                                let parts: Vec<&str> = root.split('.').rev().collect();
-                                parts[..parts.len() - 1]
-                                    .iter()
-                                    .rev()
-                                    .copied()
-                                    .collect::<Vec<_>>()
-                                    .join(".")
-                            };
-                            (Some(root.to_string()), Some(suffix.to_string()))
-                        }
-                        None => {
-                            panic!("line {i} of the test file doesn't seem to be valid");
-                        }
-                    };
-                    let (found_root, found_suffix) = match list.parse_domain(input) {
-                        Ok(domain) => {
-                            let found_root = domain.root().map(|found| found.to_string());
-                            let found_suffix = domain.suffix().map(|found| found.to_string());
-                            (found_root, found_suffix)
-                        }
-                        Err(_) => (None, None),
-                    };
-                    if expected_root != found_root
-                        || (expected_root.is_some() && expected_suffix != found_suffix)
-                    {
-                        let msg = format!(
-                            "\n\nGiven `{}`:\nWe expected root domain to be `{:?}` and suffix be \
-                             `{:?}`\nBut instead, we have `{:?}` as root domain and `{:?}` as \
-                             suffix.\nWe are on line {} of `test_psl.txt`.\n\n",
-                            input,
-                            expected_root,
-                            expected_suffix,
-                            found_root,
-                            found_suffix,
-                            i + 1
-                        );
-                        panic!("{}", msg);
-                    }
-                }
-            }
-        }
+        let tests = io::BufReader::new(File::open("tests.txt").unwrap());
+        let report = conformance::run(&list, tests).unwrap();
+        assert!(report.is_ok(), "{report}");
         list
     }
 
@@ -592,8 +1845,20 @@ mod unit_tests {
         for _ in 0..126 {
             too_many_labels_domain.push_str(".a");
         }
-        too_many_labels_domain.push_str(".com");
         assert!(list.parse_domain(&too_many_labels_domain).is_ok());
+        too_many_labels_domain.push_str(".com");
+        let err = list.parse_domain(&too_many_labels_domain).unwrap_err();
+        assert_eq!(
+            ParseError::from_io(&err),
+            Some(&ParseError::TooManyLabels {
+                count: 128,
+                max: 127
+            })
+        );
+        let options = ParseOptions::new().max_labels(128);
+        assert!(list
+            .parse_dns_name_with(&too_many_labels_domain, &options)
+            .is_ok());
     }
 
     #[test]
@@ -613,4 +1878,234 @@ mod unit_tests {
         let list = make_list();
         assert!(list.parse_domain("127.com").is_ok());
     }
+
+    /// Random byte strings, biased toward the characters names are made of
+    fn random_names(count: usize) -> impl Iterator<Item = Vec<u8>> {
+        const ALPHABET: &[u8] = b"..\\\\09ab-_*! xkjpcomuk\xc3\xbc\xff";
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        (0..count).map(move |_| {
+            let len = (next() % 24) as usize;
+            (0..len)
+                .map(|_| ALPHABET[(next() % ALPHABET.len() as u64) as usize])
+                .collect()
+        })
+    }
+
+    #[test]
+    fn display() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        for input in ["www.example.com", "www.example.com.", ".", "a\\.b.com"] {
+            let name = list.parse_dns_name(input).unwrap();
+            let shown = name.to_string();
+            assert_eq!(shown, name.name());
+            assert_eq!(list.parse_dns_name(&shown).unwrap(), name);
+        }
+
+        let name = list.parse_dns_name("WWW.Example.com").unwrap();
+        assert_eq!(name.to_string(), "www.example.com");
+        assert_eq!(name.format_fqdn(), "www.example.com.");
+        assert_eq!(name.format_relative(), "www.example.com");
+        let root = list.parse_dns_name(".").unwrap();
+        assert_eq!(
+            (root.format_fqdn(), root.format_relative()),
+            (".".into(), ".".into())
+        );
+    }
+
+    #[test]
+    fn registrable_parts() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        for (input, label, domain) in [
+            ("www.example.co.uk", Some("example"), Some("example.co.uk")),
+            ("example.co.uk.", Some("example"), Some("example.co.uk.")),
+            ("a.b.c.kobe.jp", Some("b"), Some("b.c.kobe.jp")),
+            ("www.city.kobe.jp", Some("city"), Some("city.kobe.jp")),
+            ("ex\\.ample.com", Some("ex\\.ample"), Some("ex\\.ample.com")),
+            ("co.uk", None, None),
+            ("com.", None, None),
+            (".", None, None),
+        ] {
+            let name = list.parse_dns_name(input).unwrap();
+            assert_eq!(name.registrable_label(), label, "{input}");
+            assert_eq!(name.registrable(), label, "{input}");
+            assert_eq!(name.registrable_domain(), domain, "{input}");
+        }
+    }
+
+    #[test]
+    fn relative_parts() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        for input in ["www.example.co.uk", "www.example.co.uk."] {
+            let name = list.parse_dns_name(input).unwrap();
+            assert_eq!(name.root_relative(), Some("example.co.uk"));
+            assert_eq!(name.suffix_relative(), Some("co.uk"));
+        }
+        let name = list.parse_dns_name("co.uk.").unwrap();
+        assert_eq!(
+            (name.root_relative(), name.suffix_relative()),
+            (None, Some("co.uk"))
+        );
+        let name = list.parse_dns_name(".").unwrap();
+        assert_eq!((name.root_relative(), name.suffix_relative()), (None, None));
+    }
+
+    #[test]
+    fn suffix_and_root_of() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        for input in [
+            "www.example.co.uk",
+            "WWW.EXAMPLE.COM.",
+            "a.b.c.kobe.jp",
+            "www.city.kobe.jp",
+            "co.uk",
+            "a\\046b.com",
+            ".",
+        ] {
+            let name = list.parse_dns_name(input).unwrap();
+            assert_eq!(list.suffix_of(input).unwrap().as_deref(), name.suffix());
+            assert_eq!(list.root_of(input).unwrap().as_deref(), name.root());
+        }
+        assert!(list.suffix_of("a..b").is_err());
+        assert!(list.root_of("").is_err());
+    }
+
+    #[test]
+    fn conversions() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let name = DnsName::try_from((&list, "WWW.Example.com")).unwrap();
+        assert_eq!(name.as_ref(), "www.example.com");
+        assert_eq!(String::from(name.clone()), "www.example.com");
+        assert_eq!(String::from(name.parent().unwrap()), "example.com");
+        assert!(DnsName::try_from((&list, "a..b")).is_err());
+
+        let mut roots = HashMap::new();
+        roots.insert(name, 1);
+        assert_eq!(roots.get("www.example.com"), Some(&1));
+
+        // names are equal by their text, whichever list split them
+        let empty = List::empty();
+        assert_eq!(
+            list.parse_dns_name("example.co.uk").unwrap(),
+            empty.parse_dns_name("example.co.uk").unwrap()
+        );
+    }
+
+    #[test]
+    fn original() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let upper = list.parse_dns_name("WWW.Example.CO.uk.").unwrap();
+        let lower = list.parse_dns_name("www.example.co.uk.").unwrap();
+
+        assert_eq!(upper.original(), "WWW.Example.CO.uk.");
+        assert_eq!(upper.name(), "www.example.co.uk.");
+        assert_eq!(upper.root(), Some("example.co.uk."));
+        assert_eq!(lower.original(), lower.name());
+        assert_eq!(upper, lower);
+        let mut names = std::collections::HashSet::new();
+        names.insert(upper.clone());
+        assert!(!names.insert(lower));
+
+        let escaped = list.parse_dns_name("a\\066c.com").unwrap();
+        assert_eq!(escaped.name(), "abc.com");
+        assert_eq!(escaped.original(), "a\\066c.com");
+        assert_eq!(upper.parent().unwrap().original(), "example.co.uk.");
+    }
+
+    #[test]
+    fn sort_key() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let key = |name: &str| list.parse_dns_name(name).unwrap().sort_key();
+
+        assert_eq!(key("www.example.co.uk"), "uk.co.example.www");
+        assert_eq!(key("COM."), "com");
+        assert_eq!(key("."), "");
+        assert_eq!(key("a\\.b.example.com"), "com.example.a\\.b");
+
+        let mut keys = [
+            "example-a.com",
+            "b.example.com",
+            "example.com",
+            "a.example.com",
+        ]
+        .map(key)
+        .to_vec();
+        keys.sort();
+        let under = keys
+            .iter()
+            .filter(|key| key.starts_with("com.example."))
+            .collect::<Vec<_>>();
+        assert_eq!(under, ["com.example.a", "com.example.b"]);
+    }
+
+    #[test]
+    fn minimal() {
+        let list = List::minimal();
+        assert!(list.has_prevailing_rule());
+        assert_eq!(list.stats().rules, 1);
+
+        let name = list.parse_dns_name("Example.COM.").unwrap();
+        assert_eq!(name.suffix(), Some("com."));
+        assert_eq!(name.root(), Some("example.com."));
+        let name = list.parse_dns_name("com").unwrap();
+        assert_eq!((name.suffix(), name.root()), (Some("com"), None));
+        assert!(list.parse_dns_name(".").unwrap().is_root());
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn compact() {
+        // room for a short name inline and three offsets; keep it that way
+        assert_eq!(std::mem::size_of::<DnsName>(), 80);
+        assert_eq!(std::mem::size_of::<Option<DnsName>>(), 80);
+    }
+
+    #[test]
+    fn longest_name() {
+        let list = "bc".parse::<List>().unwrap();
+        let name = "a.".repeat(MAX_NAME_BYTES / 2 - 1) + "bc";
+        assert_eq!(name.len(), MAX_NAME_BYTES);
+        let options = ParseOptions::new().max_labels(usize::MAX);
+        let parsed = list.parse_dns_name_with(&name, &options).unwrap();
+        assert_eq!(parsed.suffix(), Some("bc"));
+        assert_eq!(parsed.root(), Some("a.bc"));
+
+        assert!(list
+            .parse_dns_name_with(&format!("a{name}"), &options)
+            .is_err());
+    }
+
+    #[test]
+    fn never_panics() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let strict = ParseOptions::new()
+            .strict_lengths(true)
+            .profile(ValidationProfile::Hostname);
+
+        for bytes in random_names(20_000) {
+            let _ = DnsName::from_wire(&bytes, &list);
+            let _ = DnsName::from_wire_message(&bytes, 0, &list);
+            let Ok(name) = list.parse_dns_name_bytes(&bytes) else {
+                continue;
+            };
+            let _ = list.parse_dns_name_with(name.name(), &strict);
+
+            let full = name.name();
+            for part in [name.suffix(), name.root()].into_iter().flatten() {
+                assert!(full.ends_with(part), "{full:?} {part:?}");
+            }
+            if let Some(registrable) = name.registrable() {
+                assert!(name.root().unwrap().starts_with(registrable));
+            }
+            let _ = (name.subdomain(), name.organizational_domain());
+            let _ = (name.to_escaped(), name.to_wire(), name.parent());
+            assert!(name.ancestors().count() > 0);
+        }
+    }
 }
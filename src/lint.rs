@@ -0,0 +1,209 @@
+//! Rule validation and list hygiene checks
+
+use std::{error::Error, fmt};
+
+use crate::{List, ListNode};
+
+/// Reasons a single rule is malformed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuleError {
+    /// The rule has no labels
+    Empty,
+    /// The rule contains an empty label: `a..b`, `.a`, `a.`
+    EmptyLabel,
+    /// The rule contains whitespace
+    Whitespace,
+    /// A `*` appears anywhere other than as the whole leftmost label
+    MisplacedWildcard,
+    /// An exception rule starts with a wildcard: `!*.a`
+    WildcardException,
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            RuleError::Empty => "empty rule",
+            RuleError::EmptyLabel => "rule contains an empty label",
+            RuleError::Whitespace => "rule contains whitespace",
+            RuleError::MisplacedWildcard => "wildcard is only allowed as the leftmost label",
+            RuleError::WildcardException => "exception rules cannot be wildcards",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl Error for RuleError {}
+
+/// A hygiene problem found by [`List::lint`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LintIssue {
+    /// A rule in the list does not pass [`List::validate_rule`]
+    Malformed { rule: String, error: RuleError },
+    /// An exception rule (`!www.ck`) has no wildcard (`*.ck`) for it to except
+    UnmatchedException { rule: String },
+    /// The same rule was loaded more than once
+    Duplicate { rule: String },
+}
+
+impl LintIssue {
+    /// The rule the issue refers to
+    pub fn rule(&self) -> &str {
+        match self {
+            LintIssue::Malformed { rule, .. }
+            | LintIssue::UnmatchedException { rule }
+            | LintIssue::Duplicate { rule } => rule,
+        }
+    }
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LintIssue::Malformed { rule, error } => write!(f, "{rule}: {error}"),
+            LintIssue::UnmatchedException { rule } => {
+                write!(f, "{rule}: exception without a matching wildcard rule")
+            }
+            LintIssue::Duplicate { rule } => write!(f, "{rule}: duplicate rule"),
+        }
+    }
+}
+
+impl List {
+    /// Checks a candidate rule for syntax problems without adding it
+    ///
+    /// ```rust
+    /// # use dns_name::{List, RuleError};
+    /// assert!(List::validate_rule("*.example.com").is_ok());
+    /// assert_eq!(List::validate_rule("a.*.com"), Err(RuleError::MisplacedWildcard));
+    /// ```
+    pub fn validate_rule(rule: &str) -> Result<(), RuleError> {
+        let (is_exception_rule, rule) = match rule.strip_prefix('!') {
+            Some(rule) => (true, rule),
+            None => (false, rule),
+        };
+
+        if rule.is_empty() {
+            return Err(RuleError::Empty);
+        }
+        if rule.contains(char::is_whitespace) {
+            return Err(RuleError::Whitespace);
+        }
+
+        for (i, label) in rule.split('.').enumerate() {
+            if label.is_empty() {
+                return Err(RuleError::EmptyLabel);
+            }
+            if label.contains('*') {
+                if i != 0 || label != "*" {
+                    return Err(RuleError::MisplacedWildcard);
+                }
+                if is_exception_rule {
+                    return Err(RuleError::WildcardException);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reports malformed, unmatched exception, and duplicate rules
+    ///
+    /// Issues are sorted by rule so the output is stable between runs.
+    pub fn lint(&self) -> Vec<LintIssue> {
+        let mut issues = self
+            .duplicates
+            .iter()
+            .map(|rule| LintIssue::Duplicate { rule: rule.clone() })
+            .collect::<Vec<_>>();
+
+        let mut labels = Vec::new();
+        lint_node(&self.root, &mut labels, &mut issues);
+
+        issues.sort_by(|a, b| a.rule().cmp(b.rule()));
+        issues
+    }
+}
+
+/// Walks the trie depth first, collecting issues for each rule found
+fn lint_node<'a>(node: &'a ListNode, labels: &mut Vec<&'a str>, issues: &mut Vec<LintIssue>) {
+    for (label, child) in &node.children {
+        labels.push(label);
+
+        if let Some(leaf) = &child.leaf {
+            let name = labels.iter().rev().copied().collect::<Vec<_>>().join(".");
+            let rule = if leaf.is_exception_rule {
+                format!("!{name}")
+            } else {
+                name
+            };
+
+            if let Err(error) = List::validate_rule(&rule) {
+                issues.push(LintIssue::Malformed {
+                    rule: rule.clone(),
+                    error,
+                });
+            }
+
+            let has_wildcard = node
+                .children
+                .get("*")
+                .is_some_and(|sibling| sibling.leaf.is_some());
+            if leaf.is_exception_rule && !has_wildcard {
+                issues.push(LintIssue::UnmatchedException { rule });
+            }
+        }
+
+        lint_node(child, labels, issues);
+        labels.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rule() {
+        assert!(List::validate_rule("com").is_ok());
+        assert!(List::validate_rule("!www.ck").is_ok());
+        assert!(List::validate_rule("*").is_ok());
+        assert_eq!(List::validate_rule(""), Err(RuleError::Empty));
+        assert_eq!(List::validate_rule("!"), Err(RuleError::Empty));
+        assert_eq!(List::validate_rule("a..com"), Err(RuleError::EmptyLabel));
+        assert_eq!(List::validate_rule("a.com."), Err(RuleError::EmptyLabel));
+        assert_eq!(List::validate_rule("a b.com"), Err(RuleError::Whitespace));
+        assert_eq!(
+            List::validate_rule("a*.com"),
+            Err(RuleError::MisplacedWildcard)
+        );
+        assert_eq!(
+            List::validate_rule("!*.com"),
+            Err(RuleError::WildcardException)
+        );
+    }
+
+    #[test]
+    fn lint() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        assert_eq!(list.lint(), vec![]);
+
+        let list = "com,uk.com,com,!www.ck,*.kh,!a.kh,b.*.org"
+            .parse::<List>()
+            .unwrap();
+        assert_eq!(
+            list.lint(),
+            vec![
+                LintIssue::UnmatchedException {
+                    rule: "!www.ck".to_owned()
+                },
+                LintIssue::Malformed {
+                    rule: "b.*.org".to_owned(),
+                    error: RuleError::MisplacedWildcard
+                },
+                LintIssue::Duplicate {
+                    rule: "com".to_owned()
+                },
+            ]
+        );
+    }
+}
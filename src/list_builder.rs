@@ -0,0 +1,320 @@
+//! Building lists with build-time policy
+
+use std::{fmt, io, path::Path, sync::Arc};
+
+use crate::{List, ListSource};
+
+/// Builds a `List` from files, downloads and individual rules
+///
+/// Sources are read in the order they were added, followed by the rules
+/// added with `add_rules` and `add_rule_with_metadata`. Nothing is read until
+/// `build`.
+///
+/// ```rust
+/// # use dns_name::ListBuilder;
+/// let data = "com\n// ===BEGIN PRIVATE DOMAINS===\nblogspot.com\n// ===END PRIVATE DOMAINS===\n";
+/// let list = ListBuilder::new()
+///     .from_bytes(data.as_bytes())
+///     .include_private(false)
+///     .add_rules(["corp", "internal.corp"])
+///     .build()
+///     .unwrap();
+/// let name = list.parse_dns_name("www.example.blogspot.com").unwrap();
+/// assert_eq!(name.root(), Some("blogspot.com"));
+/// let name = list.parse_dns_name("app.team.internal.corp").unwrap();
+/// assert_eq!(name.root(), Some("team.internal.corp"));
+/// ```
+#[derive(Clone)]
+pub struct ListBuilder {
+    sources: Vec<ListSource>,
+    rules: Vec<(String, Option<Arc<str>>)>,
+    include_private: bool,
+    star_rule: bool,
+    annotated: bool,
+}
+
+impl Default for ListBuilder {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            rules: Vec::new(),
+            include_private: true,
+            star_rule: true,
+            annotated: false,
+        }
+    }
+}
+
+impl ListBuilder {
+    /// Creates a builder without any rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads rules from a local file
+    pub fn from_path<P: AsRef<Path>>(self, path: P) -> Self {
+        self.from_source(ListSource::path(path))
+    }
+
+    /// Reads rules from a list already in memory
+    pub fn from_bytes(self, bytes: &[u8]) -> Self {
+        self.from_source(ListSource::bytes(bytes.to_vec()))
+    }
+
+    /// Reads rules downloaded from `url` by `fetch`
+    ///
+    /// This crate has no HTTP client, so `fetch` does the download with
+    /// whichever one the application already uses.
+    pub fn from_url(
+        self,
+        url: &str,
+        fetch: impl Fn(&str) -> io::Result<Vec<u8>> + Send + Sync + 'static,
+    ) -> Self {
+        let owned = url.to_owned();
+        self.from_source(ListSource::fetch(url, move || fetch(&owned)))
+    }
+
+    /// Reads rules from a `ListSource`, with its checksum and signature
+    /// checks
+    ///
+    /// Fallbacks added with `ListSource::or` are not tried.
+    pub fn from_source(mut self, source: ListSource) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Adds rules, such as `corp` or `*.internal.corp`
+    pub fn add_rules<I>(mut self, rules: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.rules.extend(
+            rules
+                .into_iter()
+                .map(|rule| (rule.as_ref().to_owned(), None)),
+        );
+        self
+    }
+
+    /// Adds a rule with metadata, such as a rank or a policy tag, which
+    /// `List::rule_metadata` gives for names whose suffix it decides
+    pub fn add_rule_with_metadata(mut self, rule: &str, metadata: &str) -> Self {
+        self.rules
+            .push((rule.to_owned(), Some(Arc::from(metadata))));
+        self
+    }
+
+    /// Sets whether the text after a rule in the sources, up to the end of
+    /// the line or the next comma, is kept as its metadata
+    ///
+    /// By default it is ignored, as publicsuffix.org lists have none. With
+    /// this set, a line `xyz risk=high` gives the rule `xyz` the metadata
+    /// `risk=high`.
+    pub fn annotated(mut self, annotated: bool) -> Self {
+        self.annotated = annotated;
+        self
+    }
+
+    /// Sets whether the private domains section of a publicsuffix.org
+    /// list is used, which it is by default
+    ///
+    /// The section is marked by `===BEGIN PRIVATE DOMAINS===` and
+    /// `===END PRIVATE DOMAINS===` comments. Rules from `add_rules` are
+    /// always used.
+    pub fn include_private(mut self, include: bool) -> Self {
+        self.include_private = include;
+        self
+    }
+
+    /// Leaves out the default `*` rule, so names under an unlisted
+    /// top-level domain have no suffix
+    ///
+    /// A `*` rule in the sources or added rules is still used.
+    pub fn without_star_rule(mut self) -> Self {
+        self.star_rule = false;
+        self
+    }
+
+    /// Reads the sources and builds the list
+    ///
+    /// Fails with the first source that can't be read or verified, naming
+    /// it, or the first invalid rule. A list without any rules fails with
+    /// `io::ErrorKind::NotFound`.
+    pub fn build(&self) -> io::Result<List> {
+        let mut list = List::empty();
+        for source in &self.sources {
+            source
+                .read_verified()
+                .and_then(|bytes| list.read_rules(&bytes[..], self.include_private, self.annotated))
+                .map_err(|err| io::Error::new(err.kind(), format!("{source}: {err}")))?;
+        }
+        for (rule, metadata) in &self.rules {
+            list.append_with(rule, metadata.clone(), false)?;
+        }
+        list.finish(self.star_rule)?;
+        Ok(list)
+    }
+}
+
+impl fmt::Debug for ListBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ListBuilder")
+            .field("sources", &self.sources)
+            .field("rules", &self.rules)
+            .field("include_private", &self.include_private)
+            .field("star_rule", &self.star_rule)
+            .field("annotated", &self.annotated)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PSL: &str = "\
+// ===BEGIN ICANN DOMAINS===
+com
+uk
+co.uk
+// ===END ICANN DOMAINS===
+
+// ===BEGIN PRIVATE DOMAINS===
+blogspot.com
+*.compute.amazonaws.com
+// ===END PRIVATE DOMAINS===
+";
+
+    #[test]
+    fn private_section() {
+        let with = ListBuilder::new()
+            .from_bytes(PSL.as_bytes())
+            .build()
+            .unwrap();
+        let without = ListBuilder::new()
+            .from_bytes(PSL.as_bytes())
+            .include_private(false)
+            .build()
+            .unwrap();
+        let root = |list: &List, name| list.parse_dns_name(name).unwrap().root().map(str::to_owned);
+
+        assert_eq!(
+            root(&with, "a.b.blogspot.com").as_deref(),
+            Some("b.blogspot.com")
+        );
+        assert_eq!(
+            root(&without, "a.b.blogspot.com").as_deref(),
+            Some("blogspot.com")
+        );
+        assert_eq!(
+            root(&without, "x.y.compute.amazonaws.com").as_deref(),
+            Some("amazonaws.com")
+        );
+        assert_eq!(
+            root(&without, "www.example.co.uk").as_deref(),
+            Some("example.co.uk")
+        );
+    }
+
+    #[test]
+    fn star_rule_and_rules() {
+        let list = ListBuilder::new()
+            .add_rules(["com", "corp"])
+            .without_star_rule()
+            .build()
+            .unwrap();
+        assert!(!list.has_prevailing_rule());
+        let name = list.parse_dns_name("www.example.nope").unwrap();
+        assert_eq!((name.suffix(), name.root()), (None, None));
+        assert_eq!(
+            list.parse_dns_name("a.b.corp").unwrap().root(),
+            Some("b.corp")
+        );
+
+        let list = ListBuilder::new().add_rules(["com"]).build().unwrap();
+        assert!(list.has_prevailing_rule());
+
+        let list = ListBuilder::new()
+            .add_rules(["com", "*"])
+            .without_star_rule()
+            .build()
+            .unwrap();
+        assert!(list.has_prevailing_rule());
+
+        assert!(ListBuilder::new().add_rules(["a..b"]).build().is_err());
+        let err = ListBuilder::new().build().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn sources() {
+        let list = ListBuilder::new()
+            .from_path("suffix-list.txt")
+            .from_url("https://example.net/list.dat", |url| {
+                assert_eq!(url, "https://example.net/list.dat");
+                Ok(b"example.net".to_vec())
+            })
+            .build()
+            .unwrap();
+        assert_eq!(
+            list.parse_dns_name("www.example.co.uk").unwrap().root(),
+            Some("example.co.uk")
+        );
+        assert_eq!(
+            list.parse_dns_name("a.b.example.net").unwrap().root(),
+            Some("b.example.net")
+        );
+
+        let err = ListBuilder::new()
+            .from_url("https://example.net/list.dat", |_| {
+                Err(io::Error::new(io::ErrorKind::TimedOut, "timed out"))
+            })
+            .build()
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(err.to_string(), "https://example.net/list.dat: timed out");
+    }
+
+    #[test]
+    fn metadata() {
+        let data = "com rank=1, net\n*.ck  risk=high  \n!www.ck allowed\n";
+        let list = ListBuilder::new()
+            .from_bytes(data.as_bytes())
+            .annotated(true)
+            .add_rule_with_metadata("co.com", "private")
+            .build()
+            .unwrap();
+        let metadata = |name: &str| {
+            let name = list.parse_dns_name(name).unwrap();
+            list.rule_metadata(&name).map(str::to_owned)
+        };
+
+        assert_eq!(metadata("example.com").as_deref(), Some("rank=1"));
+        assert_eq!(metadata("example.co.com").as_deref(), Some("private"));
+        assert_eq!(metadata("example.net"), None);
+        assert_eq!(metadata("a.b.ck").as_deref(), Some("risk=high"));
+        assert_eq!(metadata("a.www.ck").as_deref(), Some("allowed"));
+        assert_eq!(metadata("example.org"), None);
+        let name = list.parse_dns_name("www.example.com").unwrap();
+        assert_eq!(list.rule_metadata(&name.parent().unwrap()), Some("rank=1"));
+        assert_eq!(
+            list.rule_metadata(&name.parent().unwrap().parent().unwrap()),
+            Some("rank=1")
+        );
+        // a suffix cut down by `parent` came from no rule
+        let name = list.parse_dns_name("example.co.com").unwrap();
+        assert_eq!(
+            list.rule_metadata(&name.parent().unwrap().parent().unwrap()),
+            None
+        );
+
+        // without `annotated` the text is ignored
+        let list = ListBuilder::new()
+            .from_bytes(data.as_bytes())
+            .build()
+            .unwrap();
+        let name = list.parse_dns_name("example.com").unwrap();
+        assert_eq!(list.rule_metadata(&name), None);
+    }
+}
@@ -0,0 +1,219 @@
+//! Sets of names queried by the domain they are under
+
+use std::collections::BTreeMap;
+
+use crate::{escape, DnsName};
+
+/// Labels of a name in reverse order, without the trailing dot and joined
+/// by dots: `com.example.www`
+fn reversed(name: &str) -> String {
+    let domain = escape::trim_dot(name);
+    let mut key = String::with_capacity(domain.len());
+    for range in escape::label_ranges(domain).into_iter().rev() {
+        if !key.is_empty() {
+            key.push('.');
+        }
+        key.push_str(&domain[range]);
+    }
+    key
+}
+
+/// A set of names that finds those under a domain without visiting the
+/// others
+///
+/// Names are kept sorted by their labels from right to left, so every name
+/// under a domain is in one range. A name and its fully qualified form are
+/// both kept, as they are different names.
+///
+/// ```rust
+/// # use dns_name::{List, NameSet};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let set = ["www.example.com", "mail.example.com", "example.co.uk", "notexample.com"]
+///     .into_iter()
+///     .map(|name| list.parse_dns_name(name).unwrap())
+///     .collect::<NameSet>();
+///
+/// let under = set.names_under("Example.com").map(|name| name.name()).collect::<Vec<_>>();
+/// assert_eq!(under, ["mail.example.com", "www.example.com"]);
+/// assert_eq!(set.names_with_suffix("co.uk").count(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NameSet {
+    /// names by reversed labels; one name, or two if both it and its fully
+    /// qualified form were added
+    names: BTreeMap<String, Vec<DnsName>>,
+    len: usize,
+}
+
+impl NameSet {
+    /// Creates an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a name, returning false if it was already in the set
+    pub fn insert(&mut self, name: DnsName) -> bool {
+        let names = self.names.entry(reversed(name.name())).or_default();
+        if names.contains(&name) {
+            return false;
+        }
+        names.push(name);
+        self.len += 1;
+        true
+    }
+
+    /// Removes a name, returning false if it was not in the set
+    pub fn remove(&mut self, name: &DnsName) -> bool {
+        let key = reversed(name.name());
+        let Some(names) = self.names.get_mut(&key) else {
+            return false;
+        };
+        let Some(i) = names.iter().position(|found| found == name) else {
+            return false;
+        };
+        names.remove(i);
+        if names.is_empty() {
+            self.names.remove(&key);
+        }
+        self.len -= 1;
+        true
+    }
+
+    /// Returns true if the name is in the set
+    pub fn contains(&self, name: &DnsName) -> bool {
+        self.names
+            .get(&reversed(name.name()))
+            .is_some_and(|names| names.contains(name))
+    }
+
+    /// Number of names
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no names
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the names, sorted by their labels from right to left
+    pub fn iter(&self) -> impl Iterator<Item = &DnsName> {
+        self.names.values().flatten()
+    }
+
+    /// Iterates over the names equal to or beneath `domain`
+    ///
+    /// `domain` is matched ignoring ASCII case and a trailing dot, on label
+    /// boundaries: `example.com` does not find `notexample.com`. The root
+    /// name `.` finds every name.
+    pub fn names_under(&self, domain: &str) -> impl Iterator<Item = &DnsName> {
+        let base = reversed(&domain.to_ascii_lowercase());
+        let (exact, below) = if base.is_empty() {
+            (None, self.names.range::<str, _>(..))
+        } else {
+            // `/` is the character after `.`, so this is every key that
+            // starts with `base.`
+            let (start, end) = (format!("{base}."), format!("{base}/"));
+            (self.names.get(&base), self.names.range(start..end))
+        };
+        exact
+            .into_iter()
+            .chain(below.map(|(_, names)| names))
+            .flatten()
+    }
+
+    /// Iterates over the names whose public suffix is `suffix`, like every
+    /// name under `co.uk` but not those under `uk` alone
+    ///
+    /// `suffix` is matched ignoring ASCII case and a trailing dot.
+    pub fn names_with_suffix<'a>(&'a self, suffix: &str) -> impl Iterator<Item = &'a DnsName> {
+        let suffix = escape::trim_dot(suffix).to_ascii_lowercase();
+        self.names_under(&suffix)
+            .filter(move |name| name.suffix_relative() == Some(suffix.as_str()))
+    }
+}
+
+impl FromIterator<DnsName> for NameSet {
+    fn from_iter<I: IntoIterator<Item = DnsName>>(iter: I) -> Self {
+        let mut set = NameSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<DnsName> for NameSet {
+    fn extend<I: IntoIterator<Item = DnsName>>(&mut self, iter: I) {
+        for name in iter {
+            self.insert(name);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a NameSet {
+    type Item = &'a DnsName;
+    type IntoIter = Box<dyn Iterator<Item = &'a DnsName> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::List;
+
+    #[test]
+    fn range_queries() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let parse = |name: &str| list.parse_dns_name(name).unwrap();
+        let mut set = [
+            "example.com",
+            "example.com.",
+            "www.example.com",
+            "a.b.example.com",
+            "example-a.com",
+            "notexample.com",
+            "example.co.uk",
+            "www.example.co.uk",
+            "uk",
+            "ex\\.ample.com",
+        ]
+        .into_iter()
+        .map(parse)
+        .collect::<NameSet>();
+        assert_eq!(set.len(), 10);
+        assert!(!set.insert(parse("WWW.example.com")));
+
+        fn names<'a>(found: impl Iterator<Item = &'a DnsName>) -> Vec<&'a str> {
+            found.map(|name| name.name()).collect()
+        }
+        assert_eq!(
+            names(set.names_under("EXAMPLE.com.")),
+            [
+                "example.com",
+                "example.com.",
+                "a.b.example.com",
+                "www.example.com"
+            ]
+        );
+        assert_eq!(names(set.names_under("ex\\.ample.com")), ["ex\\.ample.com"]);
+        assert_eq!(set.names_under("b.example.com").count(), 1);
+        assert_eq!(set.names_under("nope").count(), 0);
+        assert_eq!(set.names_under(".").count(), set.len());
+
+        assert_eq!(
+            names(set.names_with_suffix("co.uk")),
+            ["example.co.uk", "www.example.co.uk"]
+        );
+        assert_eq!(names(set.names_with_suffix("uk")), ["uk"]);
+        assert_eq!(set.names_with_suffix("com.").count(), 7);
+
+        assert!(set.remove(&parse("example.com.")));
+        assert!(!set.remove(&parse("example.com.")));
+        assert!(set.contains(&parse("example.com")));
+        assert!(!set.contains(&parse("example.com.")));
+        assert_eq!(set.len(), 9);
+        assert_eq!(set.iter().count(), 9);
+    }
+}
@@ -0,0 +1,247 @@
+//! Rewriting names before they are parsed
+//!
+//! Parsing always lowercases ASCII and canonicalizes escapes. A
+//! `NormalizerChain` runs further steps first, in order, so that
+//! applications can decode, map or trim names the same way everywhere
+//! instead of in a layer in front of every call.
+
+use std::{borrow::Cow, fmt, io};
+
+use crate::{escape, DnsName, List, ParseOptions};
+
+/// A step rewriting a name before it is parsed
+///
+/// A step returns the name borrowed if it has nothing to change. Closures
+/// taking a `&str` and returning `Some` rewritten name, or `None` to leave
+/// it, are steps too.
+pub trait Normalizer {
+    /// Rewrites `name`
+    fn normalize<'a>(&self, name: &'a str) -> io::Result<Cow<'a, str>>;
+}
+
+impl<F> Normalizer for F
+where
+    F: Fn(&str) -> Option<String>,
+{
+    fn normalize<'a>(&self, name: &'a str) -> io::Result<Cow<'a, str>> {
+        Ok(self(name).map_or(Cow::Borrowed(name), Cow::Owned))
+    }
+}
+
+/// Lowercases the name, including non-ASCII letters
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lowercase;
+
+impl Normalizer for Lowercase {
+    fn normalize<'a>(&self, name: &'a str) -> io::Result<Cow<'a, str>> {
+        if name.chars().any(char::is_uppercase) {
+            return Ok(Cow::Owned(name.to_lowercase()));
+        }
+        Ok(Cow::Borrowed(name))
+    }
+}
+
+/// Removes a trailing dot, so `example.com.` and `example.com` parse the
+/// same
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StripTrailingDot;
+
+impl Normalizer for StripTrailingDot {
+    fn normalize<'a>(&self, name: &'a str) -> io::Result<Cow<'a, str>> {
+        match name.strip_suffix('.') {
+            Some(stripped) if !stripped.is_empty() && !escape::is_escaped(name, stripped.len()) => {
+                Ok(Cow::Borrowed(stripped))
+            }
+            _ => Ok(Cow::Borrowed(name)),
+        }
+    }
+}
+
+/// Decodes `%XX` escapes, as found in URLs
+///
+/// A `%` not followed by two hex digits is kept. Decoded octets stay inside
+/// their label, as with `ParseOptions::percent_decoding`: a decoded `.` or
+/// `\` is escaped, and octets that are not UTF-8 become `\DDD`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PercentDecode;
+
+impl Normalizer for PercentDecode {
+    fn normalize<'a>(&self, name: &'a str) -> io::Result<Cow<'a, str>> {
+        let mut out = String::with_capacity(name.len());
+        if escape::percent_decode_into(name, &mut out) {
+            return Ok(Cow::Owned(out));
+        }
+        Ok(Cow::Borrowed(name))
+    }
+}
+
+/// Maps the name to its IDNA ASCII form (UTS #46), as browsers do before
+/// looking a host up
+///
+/// Requires the `idna` feature.
+#[cfg(feature = "idna")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdnaMapping;
+
+#[cfg(feature = "idna")]
+impl Normalizer for IdnaMapping {
+    fn normalize<'a>(&self, name: &'a str) -> io::Result<Cow<'a, str>> {
+        if name.is_ascii() && !name.bytes().any(|b| b.is_ascii_uppercase()) {
+            return Ok(Cow::Borrowed(name));
+        }
+        idna::domain_to_ascii(name)
+            .map(Cow::Owned)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid IDNA name"))
+    }
+}
+
+/// Steps run, in order, on names before they are parsed
+///
+/// The default chain decodes `%XX` escapes and lowercases. `new` starts an
+/// empty chain.
+///
+/// ```rust
+/// # use dns_name::{List, NormalizerChain};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let chain = NormalizerChain::default()
+///     .then(|name: &str| name.strip_prefix("www.").map(str::to_owned));
+/// let name = list.parse_normalized("WWW.%C3%BCber.example.com", &chain).unwrap();
+/// assert_eq!(name.name(), "über.example.com");
+/// assert_eq!(name.root(), Some("example.com"));
+/// ```
+pub struct NormalizerChain {
+    steps: Vec<Box<dyn Normalizer + Send + Sync>>,
+}
+
+impl NormalizerChain {
+    /// Creates a chain without any steps
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    /// Adds a step after those already in the chain
+    pub fn then(mut self, step: impl Normalizer + Send + Sync + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// Runs every step on `name`
+    pub fn normalize<'a>(&self, name: &'a str) -> io::Result<Cow<'a, str>> {
+        let mut name = Cow::Borrowed(name);
+        for step in &self.steps {
+            // a step borrowing all of its input changed nothing
+            let next = match step.normalize(&name)? {
+                Cow::Borrowed(next) if next.len() == name.len() => continue,
+                next => next.into_owned(),
+            };
+            name = Cow::Owned(next);
+        }
+        Ok(name)
+    }
+}
+
+impl Default for NormalizerChain {
+    fn default() -> Self {
+        Self::new().then(PercentDecode).then(Lowercase)
+    }
+}
+
+impl fmt::Debug for NormalizerChain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NormalizerChain")
+            .field("steps", &self.steps.len())
+            .finish()
+    }
+}
+
+impl List {
+    /// Parses a DNS name after running it through `chain`
+    ///
+    /// The original spelling of the name, as `DnsName::original` gives it,
+    /// is the output of the chain.
+    pub fn parse_normalized(&self, domain: &str, chain: &NormalizerChain) -> io::Result<DnsName> {
+        self.parse_normalized_with(domain, chain, &ParseOptions::default())
+    }
+
+    /// Parses a DNS name after running it through `chain`, with extra checks
+    /// enabled by `options`
+    pub fn parse_normalized_with(
+        &self,
+        domain: &str,
+        chain: &NormalizerChain,
+        options: &ParseOptions,
+    ) -> io::Result<DnsName> {
+        self.parse_dns_name_with(&chain.normalize(domain)?, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps() {
+        let run = |step: &dyn Normalizer, name| step.normalize(name).unwrap().into_owned();
+
+        assert_eq!(run(&Lowercase, "BÜCHER.de"), "bücher.de");
+        assert_eq!(run(&StripTrailingDot, "example.com."), "example.com");
+        assert_eq!(run(&StripTrailingDot, "."), ".");
+        assert_eq!(run(&StripTrailingDot, "a\\."), "a\\.");
+        assert_eq!(run(&StripTrailingDot, "a\\\\."), "a\\\\");
+        assert_eq!(run(&PercentDecode, "a%2Eb%zz%4"), "a\\.b%zz%4");
+        assert_eq!(run(&PercentDecode, "a%5C.b"), "a\\\\.b");
+        assert_eq!(run(&PercentDecode, "%ff.com"), "\\255.com");
+        assert!(matches!(
+            PercentDecode.normalize("example.com").unwrap(),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn chain() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let chain = NormalizerChain::new()
+            .then(StripTrailingDot)
+            .then(|name: &str| name.strip_prefix("www.").map(str::to_owned));
+        let name = list.parse_normalized("www.example.co.uk.", &chain).unwrap();
+        assert_eq!(name.name(), "example.co.uk");
+        assert!(!name.is_fqdn());
+
+        // without steps the name is parsed as it is
+        let chain = NormalizerChain::new();
+        assert!(matches!(
+            chain.normalize("A.b").unwrap(),
+            Cow::Borrowed("A.b")
+        ));
+        assert!(list.parse_normalized("a%2Eb.com", &chain).is_ok());
+    }
+
+    #[test]
+    fn decoded_separators() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let chain = NormalizerChain::default();
+        let options = ParseOptions::new().percent_decoding(true);
+        for input in ["a%5C.bank.com", "login.bank.com%2Eevil.co.uk"] {
+            let normalized = list.parse_normalized(input, &chain).unwrap();
+            let decoded = list.parse_dns_name_with(input, &options).unwrap();
+            assert_eq!(normalized.root(), decoded.root(), "{input}");
+        }
+
+        let name = list.parse_normalized("a%5C.bank.com", &chain).unwrap();
+        assert_eq!(name.root(), Some("bank.com"));
+        let name = list
+            .parse_normalized("login.bank.com%2Eevil.co.uk", &chain)
+            .unwrap();
+        assert_eq!(name.name(), "login.bank.com\\.evil.co.uk");
+        assert_eq!(name.root(), Some("com\\.evil.co.uk"));
+    }
+
+    #[cfg(feature = "idna")]
+    #[test]
+    fn idna() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let chain = NormalizerChain::default().then(IdnaMapping);
+        let name = list.parse_normalized("www.Bücher.de", &chain).unwrap();
+        assert_eq!(name.name(), "www.xn--bcher-kva.de");
+    }
+}
@@ -0,0 +1,138 @@
+//! Hooks for counting parses
+
+use std::{fmt, io, sync::Arc};
+
+use crate::{DnsName, List};
+
+/// Receives the outcome of every parse made with a `List`
+///
+/// Implement this to feed counters such as `parse_total` and
+/// `unknown_suffix_total` without wrapping each call. Methods are called on
+/// the parsing thread, so they should be cheap.
+///
+/// ```rust
+/// # use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+/// # use dns_name::{DnsName, List, ParseObserver};
+/// #[derive(Default)]
+/// struct Counters {
+///     parsed: AtomicUsize,
+///     unknown_suffix: AtomicUsize,
+/// }
+///
+/// impl ParseObserver for Counters {
+///     fn parsed(&self, _name: &DnsName, listed_suffix: bool) {
+///         self.parsed.fetch_add(1, Ordering::Relaxed);
+///         if !listed_suffix {
+///             self.unknown_suffix.fetch_add(1, Ordering::Relaxed);
+///         }
+///     }
+/// }
+///
+/// let counters = Arc::new(Counters::default());
+/// let mut list = List::from_path("suffix-list.txt").unwrap();
+/// list.set_observer(counters.clone());
+/// list.parse_dns_name("www.example.com").unwrap();
+/// list.parse_dns_name("www.example.invalid").unwrap();
+/// assert_eq!(counters.parsed.load(Ordering::Relaxed), 2);
+/// assert_eq!(counters.unknown_suffix.load(Ordering::Relaxed), 1);
+/// ```
+pub trait ParseObserver: Send + Sync {
+    /// Called when a name parses
+    ///
+    /// `listed_suffix` is false when no rule in the list matched and the
+    /// suffix is just the last label, by the default `*` rule.
+    fn parsed(&self, name: &DnsName, listed_suffix: bool) {
+        let _ = (name, listed_suffix);
+    }
+
+    /// Called when `input` fails to parse
+    fn rejected(&self, input: &str, err: &io::Error) {
+        let _ = (input, err);
+    }
+}
+
+/// The observer installed on a `List`
+#[derive(Clone)]
+pub(crate) struct Observer(Arc<dyn ParseObserver>);
+
+impl std::ops::Deref for Observer {
+    type Target = dyn ParseObserver;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl fmt::Debug for Observer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ParseObserver")
+    }
+}
+
+impl List {
+    /// Reports every parse made with the List to `observer`
+    ///
+    /// This replaces any observer set before.
+    pub fn set_observer(&mut self, observer: Arc<dyn ParseObserver>) {
+        self.observer = Some(Observer(observer));
+    }
+
+    /// Stops reporting parses
+    pub fn clear_observer(&mut self) {
+        self.observer = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<String>>);
+
+    impl ParseObserver for Log {
+        fn parsed(&self, name: &DnsName, listed_suffix: bool) {
+            let line = format!("{} {listed_suffix}", name.name());
+            self.0.lock().unwrap().push(line);
+        }
+
+        fn rejected(&self, input: &str, err: &io::Error) {
+            self.0.lock().unwrap().push(format!("{input}: {err}"));
+        }
+    }
+
+    #[test]
+    fn observe() {
+        let log = Arc::new(Log::default());
+        let mut list = List::from_path("suffix-list.txt").unwrap();
+        list.set_observer(log.clone());
+
+        for name in ["www.example.co.uk", "a.b.kobe.jp", "host.lan", ".", "a..b"] {
+            let _ = list.parse_dns_name(name);
+        }
+        let _ = list.parse_many(["www.example.com"]);
+        assert_eq!(
+            *log.0.lock().unwrap(),
+            [
+                "www.example.co.uk true",
+                "a.b.kobe.jp true",
+                "host.lan false",
+                ". true",
+                "a..b: label 1 is invalid at byte 2: empty",
+                "www.example.com true",
+            ]
+        );
+
+        list.clear_observer();
+        list.parse_dns_name("example.com").unwrap();
+        assert_eq!(log.0.lock().unwrap().len(), 6);
+
+        // without a list every suffix is unknown
+        let mut empty = List::empty();
+        empty.set_observer(log.clone());
+        empty.parse_dns_name("example.com").unwrap();
+        assert_eq!(log.0.lock().unwrap()[6], "example.com false");
+    }
+}
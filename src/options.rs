@@ -0,0 +1,465 @@
+//! Options controlling how names are parsed
+
+/// Options for `List::parse_dns_name_with`
+///
+/// The defaults match `List::parse_dns_name`.
+///
+/// ```rust
+/// # use dns_name::{List, ParseOptions};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let options = ParseOptions::new().strict_lengths(true);
+/// assert!(list.parse_dns_name_with("www.example.com", &options).is_ok());
+/// assert!(list.parse_dns_name_with(&"a".repeat(64), &options).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    pub(crate) strict_lengths: bool,
+    pub(crate) profile: ValidationProfile,
+    pub(crate) underscore_labels: Option<bool>,
+    pub(crate) reject_dotless: bool,
+    pub(crate) reject_numeric_tld: bool,
+    pub(crate) wildcard_names: bool,
+    pub(crate) empty_as_root: bool,
+    pub(crate) algorithm: Algorithm,
+    pub(crate) leading_dot: bool,
+    pub(crate) max_labels: Option<usize>,
+    pub(crate) max_depth: Option<usize>,
+    #[cfg(feature = "idna")]
+    pub(crate) validate_punycode: bool,
+    pub(crate) invalid_utf8: InvalidUtf8,
+    pub(crate) ip_policy: IpPolicy,
+    pub(crate) bracketed_ipv6: bool,
+    pub(crate) port_suffix: bool,
+    pub(crate) percent_decoding: bool,
+}
+
+/// How inputs that are IP address literals, like `127.0.0.1` or `[::1]`,
+/// are parsed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum IpPolicy {
+    /// Parses the address as any other name, so `127.0.0.1` gets the suffix
+    /// `1` from the default `*` rule
+    #[default]
+    ParseAsName,
+    /// Keeps the address as a name without a suffix or root, skipping the
+    /// label checks; `DnsName::ip_addr` gives the address
+    Classify,
+    /// Rejects the address as `ParseError::IpLiteral`
+    Reject,
+}
+
+/// What `List::parse_dns_name_bytes_with` does with bytes that are not
+/// UTF-8
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InvalidUtf8 {
+    /// Rejects the name with `io::ErrorKind::InvalidData`
+    #[default]
+    Reject,
+    /// Writes each offending byte as a `\DDD` escape, so the name keeps
+    /// its exact octets
+    Escape,
+    /// Replaces each offending sequence with U+FFFD, as
+    /// `String::from_utf8_lossy` does
+    Lossy,
+}
+
+/// Most labels a name may have unless `ParseOptions::max_labels` says
+/// otherwise: as many as fit in 255 octets in wire format
+pub(crate) const DEFAULT_MAX_LABELS: usize = 127;
+
+/// Which characters labels may contain
+///
+/// Violations are reported as `ParseError::InvalidLabel`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ValidationProfile {
+    /// Almost anything: labels must not be empty or contain spaces
+    #[default]
+    Dns,
+    /// RFC 952/1123 host names: letters, digits and hyphens, not starting
+    /// or ending with a hyphen
+    Hostname,
+    /// `Hostname`, and labels with hyphens in the third and fourth positions
+    /// must be valid IDNA A-labels (`xn--`)
+    ///
+    /// Requires the `idna` feature.
+    #[cfg(feature = "idna")]
+    StrictIdna,
+}
+
+/// How the rule deciding a name's suffix is chosen
+///
+/// The two differ only for lists where an exact label leads away from a
+/// wildcard rule, where an exception rule is shorter than another matching
+/// rule, or that lack the `*` rule. Lists built by this crate always have
+/// the `*` rule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Algorithm {
+    /// Walks the list label by label from the right, taking a wildcard only
+    /// where no rule has the exact label, and uses the last rule passed
+    #[default]
+    Legacy,
+    /// The [publicsuffix.org algorithm]: every matching rule is considered,
+    /// an exception rule prevails over the others, and otherwise the rule
+    /// with the most labels does; the `*` rule applies if none matches
+    ///
+    /// This agrees with browsers, and is checked against the official
+    /// `tests.txt` with `conformance::run_with`.
+    ///
+    /// [publicsuffix.org algorithm]: https://github.com/publicsuffix/list/wiki/Format#formal-algorithm
+    PslSpec,
+}
+
+impl ParseOptions {
+    /// Creates the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The label limit in effect
+    pub(crate) fn label_limit(&self) -> usize {
+        self.max_labels.unwrap_or(DEFAULT_MAX_LABELS)
+    }
+
+    /// Enforces the RFC 1035 limits: 63 octets per label and 255 octets for
+    /// the whole name in wire format
+    ///
+    /// Violations are reported as `ParseError::LabelTooLong` and
+    /// `ParseError::NameTooLong`.
+    pub fn strict_lengths(mut self, strict: bool) -> Self {
+        self.strict_lengths = strict;
+        self
+    }
+
+    /// Sets which characters labels may contain
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseOptions, ValidationProfile};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new().profile(ValidationProfile::Hostname);
+    /// assert!(list.parse_dns_name_with("exa/mple.com", &options).is_err());
+    /// ```
+    pub fn profile(mut self, profile: ValidationProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Explicitly permits or rejects underscore-prefixed service labels
+    /// (`_dmarc`, `_443._tcp`)
+    ///
+    /// When permitted, the rest of the label must still satisfy the profile.
+    /// When rejected, they are reported as `LabelError::UnderscoreLabel`.
+    /// Left unset, the profile decides: `Dns` accepts them and `Hostname`
+    /// does not.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseOptions, ValidationProfile};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new()
+    ///     .profile(ValidationProfile::Hostname)
+    ///     .underscore_labels(true);
+    /// assert!(list.parse_dns_name_with("_443._tcp.example.com", &options).is_ok());
+    /// ```
+    pub fn underscore_labels(mut self, allow: bool) -> Self {
+        self.underscore_labels = Some(allow);
+        self
+    }
+
+    /// Permits or rejects single-label names such as `localhost` or `com`
+    ///
+    /// They are permitted by default and parse as a bare suffix with no
+    /// root domain; `DnsName::is_dotless` flags them. When rejected, they
+    /// are reported as `ParseError::DotlessDomain`. The root name `.` is
+    /// always permitted.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseError, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new().dotless_names(false);
+    /// let err = list.parse_dns_name_with("localhost.", &options).unwrap_err();
+    /// assert_eq!(ParseError::from_io(&err), Some(&ParseError::DotlessDomain));
+    /// assert!(list.parse_dns_name_with("example.com", &options).is_ok());
+    /// ```
+    pub fn dotless_names(mut self, allow: bool) -> Self {
+        self.reject_dotless = !allow;
+        self
+    }
+
+    /// Permits or rejects names whose last label is all digits
+    ///
+    /// No top-level domain is numeric, so such names are usually mangled
+    /// IPv4 addresses (`127.0.0.256`). They are permitted by default, with
+    /// the number as the suffix; `DnsName::has_numeric_tld` flags them.
+    /// When rejected, they are reported as `ParseError::NumericTld`.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseError, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new().numeric_tlds(false);
+    /// let err = list.parse_dns_name_with("127.38.53.247", &options).unwrap_err();
+    /// assert_eq!(ParseError::from_io(&err), Some(&ParseError::NumericTld));
+    /// ```
+    pub fn numeric_tlds(mut self, allow: bool) -> Self {
+        self.reject_numeric_tld = !allow;
+        self
+    }
+
+    /// Accepts a leading `*` label, as in certificates and zone files
+    ///
+    /// The rest of the name is validated and matched as usual, and the `*`
+    /// is kept in the name as part of the subdomain; `DnsName::is_wildcard`
+    /// flags it. Without this a `*` label is an ordinary label, matched
+    /// against the list like any other.
+    ///
+    /// The `*` still counts as a label: `*.com` is not a dotless name, it
+    /// adds to lengths and `max_labels`, and label indexes in errors include
+    /// it. Only the validation profile leaves it unchecked.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseOptions, ValidationProfile};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new()
+    ///     .profile(ValidationProfile::Hostname)
+    ///     .wildcard_names(true);
+    /// let name = list.parse_dns_name_with("*.example.co.uk", &options).unwrap();
+    /// assert!(name.is_wildcard());
+    /// assert_eq!(name.root(), Some("example.co.uk"));
+    /// assert_eq!(name.subdomain(), Some("*"));
+    /// ```
+    pub fn wildcard_names(mut self, allow: bool) -> Self {
+        self.wildcard_names = allow;
+        self
+    }
+
+    /// Takes empty input as the root name `.`, or rejects it
+    ///
+    /// Empty input is rejected by default, reported as
+    /// `ParseError::EmptyName`. Either way `DnsName::is_root` tells the root
+    /// name apart.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseError, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let err = list.parse_dns_name("").unwrap_err();
+    /// assert_eq!(ParseError::from_io(&err), Some(&ParseError::EmptyName));
+    ///
+    /// let options = ParseOptions::new().empty_as_root(true);
+    /// let name = list.parse_dns_name_with("", &options).unwrap();
+    /// assert!(name.is_root());
+    /// assert_eq!(name, list.parse_dns_name(".").unwrap());
+    /// ```
+    pub fn empty_as_root(mut self, root: bool) -> Self {
+        self.empty_as_root = root;
+        self
+    }
+
+    /// Sets how the rule deciding a name's suffix is chosen
+    ///
+    /// ```rust
+    /// # use dns_name::{Algorithm, List, ParseOptions};
+    /// let list = "*.b,d.a.b".parse::<List>().unwrap();
+    /// let options = ParseOptions::new().algorithm(Algorithm::PslSpec);
+    /// let name = list.parse_dns_name_with("c.a.b", &options).unwrap();
+    /// assert_eq!(name.suffix(), Some("a.b"));
+    /// // the walk follows `d.a.b` and never reaches `*.b`
+    /// assert_eq!(list.parse_dns_name("c.a.b").unwrap().suffix(), None);
+    /// ```
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Accepts a single leading dot, as in a cookie's `Domain=.example.com`
+    ///
+    /// The dot is dropped from the name and `DnsName::had_leading_dot`
+    /// records it. Without this a leading dot is an error.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new().leading_dot(true);
+    /// let name = list.parse_dns_name_with(".example.com", &options).unwrap();
+    /// assert_eq!(name.name(), "example.com");
+    /// assert!(name.had_leading_dot());
+    /// assert!(list.parse_dns_name(".example.com").is_err());
+    /// ```
+    pub fn leading_dot(mut self, allow: bool) -> Self {
+        self.leading_dot = allow;
+        self
+    }
+
+    /// Sets the most labels a name may have, 127 by default
+    ///
+    /// Longer names are reported as `ParseError::TooManyLabels` before they
+    /// are matched against the list. A leading `*` label allowed by
+    /// `wildcard_names` counts.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseError, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let err = list.parse_dns_name(&"a.".repeat(500)).unwrap_err();
+    /// assert_eq!(
+    ///     ParseError::from_io(&err),
+    ///     Some(&ParseError::TooManyLabels { count: 500, max: 127 })
+    /// );
+    /// let options = ParseOptions::new().max_labels(4);
+    /// assert!(list.parse_dns_name_with("a.b.c.d.e", &options).is_err());
+    /// ```
+    pub fn max_labels(mut self, max: usize) -> Self {
+        self.max_labels = Some(max);
+        self
+    }
+
+    /// Sets the most labels a name may have left of its root domain, which
+    /// is unlimited by default
+    ///
+    /// Deeper names are reported as `ParseError::TooDeep`. Names without a
+    /// root domain, such as public suffixes, have no depth.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseError, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new().max_depth(2);
+    /// assert!(list.parse_dns_name_with("a.b.example.co.uk", &options).is_ok());
+    /// let err = list.parse_dns_name_with("a.b.c.example.co.uk", &options).unwrap_err();
+    /// assert_eq!(
+    ///     ParseError::from_io(&err),
+    ///     Some(&ParseError::TooDeep { depth: 3, max: 2 })
+    /// );
+    /// ```
+    pub fn max_depth(mut self, max: usize) -> Self {
+        self.max_depth = Some(max);
+        self
+    }
+
+    /// Sets what `List::parse_dns_name_bytes_with` does with bytes that are
+    /// not UTF-8, such as names taken from packet captures
+    ///
+    /// ```rust
+    /// # use dns_name::{InvalidUtf8, List, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new().invalid_utf8(InvalidUtf8::Escape);
+    /// let name = list.parse_dns_name_bytes_with(b"w\xffw.example.com", &options).unwrap();
+    /// assert_eq!(name.name(), "w\\255w.example.com");
+    /// assert!(list.parse_dns_name_bytes(b"w\xffw.example.com").is_err());
+    /// ```
+    pub fn invalid_utf8(mut self, handling: InvalidUtf8) -> Self {
+        self.invalid_utf8 = handling;
+        self
+    }
+
+    /// Sets how IPv4 and IPv6 address literals are parsed, as names by
+    /// default
+    ///
+    /// ```rust
+    /// # use dns_name::{IpPolicy, List, ParseError, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// assert_eq!(list.parse_dns_name("127.0.0.1").unwrap().suffix(), Some("1"));
+    ///
+    /// let options = ParseOptions::new().ip_literals(IpPolicy::Classify);
+    /// let name = list.parse_dns_name_with("127.0.0.1", &options).unwrap();
+    /// assert_eq!(name.suffix(), None);
+    /// assert!(name.ip_addr().is_some());
+    ///
+    /// let options = ParseOptions::new().ip_literals(IpPolicy::Reject);
+    /// let err = list.parse_dns_name_with("[::1]", &options).unwrap_err();
+    /// assert_eq!(ParseError::from_io(&err), Some(&ParseError::IpLiteral));
+    /// ```
+    pub fn ip_literals(mut self, policy: IpPolicy) -> Self {
+        self.ip_policy = policy;
+        self
+    }
+
+    /// Accepts an IPv6 literal in brackets, as in `[2001:db8::1]`, and drops
+    /// the brackets
+    ///
+    /// Anything other than an IPv6 address in brackets is an error. The
+    /// address is then parsed as `ip_literals` says.
+    ///
+    /// ```rust
+    /// # use dns_name::{IpPolicy, List, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new()
+    ///     .bracketed_ipv6(true)
+    ///     .ip_literals(IpPolicy::Classify);
+    /// let name = list.parse_dns_name_with("[2001:DB8::1]", &options).unwrap();
+    /// assert_eq!(name.name(), "2001:db8::1");
+    /// assert_eq!(name.original(), "[2001:DB8::1]");
+    /// assert!(list.parse_dns_name_with("[example.com]", &options).is_err());
+    /// ```
+    pub fn bracketed_ipv6(mut self, allow: bool) -> Self {
+        self.bracketed_ipv6 = allow;
+        self
+    }
+
+    /// Accepts a port after the name, as in `example.com:8080` or, with
+    /// `bracketed_ipv6`, `[::1]:443`
+    ///
+    /// The port is dropped from the name and `DnsName::port` records it. A
+    /// port that is not a number up to 65535 is an error.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new().port_suffix(true);
+    /// let name = list.parse_dns_name_with("www.example.com:8080", &options).unwrap();
+    /// assert_eq!(name.name(), "www.example.com");
+    /// assert_eq!(name.port(), Some(8080));
+    /// assert!(list.parse_dns_name_with("www.example.com:http", &options).is_err());
+    /// ```
+    pub fn port_suffix(mut self, allow: bool) -> Self {
+        self.port_suffix = allow;
+        self
+    }
+
+    /// Decodes `%XX` escapes, as in `ex%61mple.com` from a URL, before the
+    /// name is checked
+    ///
+    /// Decoded octets stay inside their label: an encoded dot or backslash
+    /// becomes the escape `\.` or `\\`, and octets that are not UTF-8 become
+    /// `\DDD`. `DnsName::was_percent_decoded` records that anything was
+    /// decoded. A `%` not followed by two hex digits is kept.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new().percent_decoding(true);
+    /// let name = list.parse_dns_name_with("www.ex%61mple.com", &options).unwrap();
+    /// assert_eq!(name.name(), "www.example.com");
+    /// assert!(name.was_percent_decoded());
+    ///
+    /// // an encoded dot does not split a label
+    /// let name = list.parse_dns_name_with("evil%2Eexample.com", &options).unwrap();
+    /// assert_eq!(name.name(), "evil\\.example.com");
+    /// assert_eq!(name.root(), Some("evil\\.example.com"));
+    /// ```
+    pub fn percent_decoding(mut self, decode: bool) -> Self {
+        self.percent_decoding = decode;
+        self
+    }
+
+    /// Rejects `xn--` labels that are not valid IDNA A-labels, whatever the
+    /// profile
+    ///
+    /// Malformed punycode is often used to slip names past filters. Such
+    /// labels are reported as `LabelError::InvalidPunycode`; without this
+    /// they are accepted and `DnsName::has_invalid_punycode` flags them.
+    ///
+    /// Requires the `idna` feature.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, ParseOptions};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let options = ParseOptions::new().validate_punycode(true);
+    /// assert!(list.parse_dns_name_with("www.xn--bcher-kva.de", &options).is_ok());
+    /// assert!(list.parse_dns_name_with("www.xn--a.de", &options).is_err());
+    /// ```
+    #[cfg(feature = "idna")]
+    pub fn validate_punycode(mut self, validate: bool) -> Self {
+        self.validate_punycode = validate;
+        self
+    }
+}
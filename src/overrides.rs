@@ -0,0 +1,80 @@
+//! Rules layered over a list for a single parse
+
+use std::io;
+
+use crate::{DnsName, List, ParseOptions, Scratch};
+
+impl List {
+    /// Parses a DNS name as if `overrides` were added to the list
+    ///
+    /// An override wins over a list rule that matches as many labels or
+    /// fewer, so it can both add a suffix (`internal.corp`) and replace a
+    /// listed one (`!www.ck`). The List itself is neither changed nor
+    /// copied; only the overrides are built into a small trie for the call.
+    /// Overrides are checked with `List::validate_rule`.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list
+    ///     .parse_with_overrides("app.team.internal.corp", &["internal.corp"])
+    ///     .unwrap();
+    /// assert_eq!(name.suffix(), Some("internal.corp"));
+    /// assert_eq!(name.root(), Some("team.internal.corp"));
+    ///
+    /// let name = list.parse_dns_name("app.team.internal.corp").unwrap();
+    /// assert_eq!(name.suffix(), Some("corp"));
+    /// ```
+    pub fn parse_with_overrides(&self, domain: &str, overrides: &[&str]) -> io::Result<DnsName> {
+        let mut layer = List::empty();
+        for rule in overrides {
+            List::validate_rule(rule).map_err(|err| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("{rule}: {err}"))
+            })?;
+            layer.append(&rule.to_ascii_lowercase())?;
+        }
+        DnsName::find_match_layered(
+            domain,
+            self,
+            Some(&layer),
+            &ParseOptions::default(),
+            &mut Scratch::default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let parse = |name: &str, overrides: &[&str]| {
+            let name = list.parse_with_overrides(name, overrides).unwrap();
+            (
+                name.suffix().map(str::to_owned),
+                name.root().map(str::to_owned),
+            )
+        };
+        let some = |suffix: &str, root: &str| (Some(suffix.to_owned()), Some(root.to_owned()));
+
+        assert_eq!(parse("a.b.example.com", &[]), some("com", "example.com"));
+        assert_eq!(
+            parse("a.b.example.com", &["Example.com"]),
+            some("example.com", "b.example.com")
+        );
+        // a longer list rule still wins
+        assert_eq!(
+            parse("www.example.co.uk", &["uk"]),
+            some("co.uk", "example.co.uk")
+        );
+        // an exception replaces the listed wildcard
+        assert_eq!(parse("a.www.ck", &[]), some("ck", "www.ck"));
+        assert_eq!(parse("a.b.foo.ck", &["!foo.ck"]), some("ck", "foo.ck"));
+        assert_eq!(parse("a.b.c.d", &["*.c.d"]), some("b.c.d", "a.b.c.d"));
+
+        assert!(list.parse_with_overrides("a.com", &["a..b"]).is_err());
+        assert!(list.parse_with_overrides("a..com", &["com"]).is_err());
+    }
+}
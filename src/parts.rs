@@ -0,0 +1,253 @@
+//! Typed views of the parts of a name
+
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use crate::{escape, DnsName};
+
+/// The public suffix of a name, like `co.uk` in `www.example.co.uk`
+///
+/// Suffixes compare equal whether or not either has a trailing dot, and
+/// display without one.
+#[derive(Debug, Clone, Copy)]
+pub struct Suffix<'a>(&'a str);
+
+/// The registrable domain of a name, its suffix and one more label, like
+/// `example.co.uk` in `www.example.co.uk`
+///
+/// Compared and displayed like a `Suffix`.
+#[derive(Debug, Clone, Copy)]
+pub struct RegistrableDomain<'a> {
+    domain: &'a str,
+    /// length of the registrable label, without the dot after it
+    label: usize,
+}
+
+/// The labels of a name left of its registrable domain, like `a.b` in
+/// `a.b.example.co.uk`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Subdomain<'a>(&'a str);
+
+impl<'a> Suffix<'a> {
+    /// The suffix as it is in the name, trailing dot included
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// The suffix without a trailing dot
+    pub fn relative(&self) -> &'a str {
+        escape::trim_dot(self.0)
+    }
+
+    /// Number of labels
+    pub fn labels(&self) -> usize {
+        escape::label_ranges(self.relative()).len()
+    }
+}
+
+impl<'a> RegistrableDomain<'a> {
+    /// The domain as it is in the name, trailing dot included
+    pub fn as_str(&self) -> &'a str {
+        self.domain
+    }
+
+    /// The domain without a trailing dot
+    pub fn relative(&self) -> &'a str {
+        escape::trim_dot(self.domain)
+    }
+
+    /// The label registered under the suffix, like `example`
+    pub fn label(&self) -> &'a str {
+        &self.domain[..self.label]
+    }
+
+    /// The public suffix the domain is registered under
+    pub fn suffix(&self) -> Suffix<'a> {
+        Suffix(&self.domain[self.label + 1..])
+    }
+}
+
+impl<'a> Subdomain<'a> {
+    /// The subdomain as a string
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// Number of labels, how deep the name is below its registrable domain
+    pub fn depth(&self) -> usize {
+        escape::label_ranges(self.0).len()
+    }
+}
+
+impl PartialEq for Suffix<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.relative() == other.relative()
+    }
+}
+
+impl Eq for Suffix<'_> {}
+
+impl Hash for Suffix<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.relative().hash(state);
+    }
+}
+
+impl PartialEq<str> for Suffix<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.relative() == escape::trim_dot(other)
+    }
+}
+
+impl PartialEq<&str> for Suffix<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl fmt::Display for Suffix<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.relative())
+    }
+}
+
+impl PartialEq for RegistrableDomain<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.relative() == other.relative()
+    }
+}
+
+impl Eq for RegistrableDomain<'_> {}
+
+impl Hash for RegistrableDomain<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.relative().hash(state);
+    }
+}
+
+impl PartialEq<str> for RegistrableDomain<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.relative() == escape::trim_dot(other)
+    }
+}
+
+impl PartialEq<&str> for RegistrableDomain<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl fmt::Display for RegistrableDomain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.relative())
+    }
+}
+
+impl PartialEq<str> for Subdomain<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Subdomain<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for Subdomain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl DnsName {
+    /// Gets the public suffix as a `Suffix`
+    ///
+    /// This is `suffix` for signatures that should say which part of a
+    /// name they take.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let a = list.parse_dns_name("www.example.co.uk.").unwrap();
+    /// let b = list.parse_dns_name("other.co.uk").unwrap();
+    /// assert_eq!(a.typed_suffix(), b.typed_suffix());
+    /// assert_eq!(a.typed_suffix().unwrap().to_string(), "co.uk");
+    /// ```
+    pub fn typed_suffix(&self) -> Option<Suffix<'_>> {
+        self.suffix().map(Suffix)
+    }
+
+    /// Gets the registrable domain as a `RegistrableDomain`
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.example.co.uk").unwrap();
+    /// let domain = name.typed_registrable_domain().unwrap();
+    /// assert_eq!(domain, "example.co.uk.");
+    /// assert_eq!(domain.label(), "example");
+    /// assert_eq!(domain.suffix(), "co.uk");
+    /// ```
+    pub fn typed_registrable_domain(&self) -> Option<RegistrableDomain<'_>> {
+        let label = self.registrable_label()?.len();
+        self.root()
+            .map(|domain| RegistrableDomain { domain, label })
+    }
+
+    /// Gets the subdomain as a `Subdomain`
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("a.b.example.co.uk").unwrap();
+    /// assert_eq!(name.typed_subdomain().unwrap().depth(), 2);
+    /// ```
+    pub fn typed_subdomain(&self) -> Option<Subdomain<'_>> {
+        self.subdomain().map(Subdomain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::List;
+
+    #[test]
+    fn typed_parts() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let name = list.parse_dns_name("x.www.Example.co.uk.").unwrap();
+
+        let suffix = name.typed_suffix().unwrap();
+        assert_eq!(suffix.as_str(), "co.uk.");
+        assert_eq!(suffix.relative(), "co.uk");
+        assert_eq!(suffix.labels(), 2);
+        assert!(suffix == "co.uk" && suffix == "co.uk.");
+
+        let domain = name.typed_registrable_domain().unwrap();
+        assert_eq!(domain.as_str(), "example.co.uk.");
+        assert_eq!(domain.to_string(), "example.co.uk");
+        assert_eq!(domain.suffix(), suffix);
+
+        let subdomain = name.typed_subdomain().unwrap();
+        assert_eq!(subdomain, "x.www");
+        assert_eq!(subdomain.to_string(), "x.www");
+
+        let relative = list.parse_dns_name("example.co.uk").unwrap();
+        let domains = [&name, &relative]
+            .into_iter()
+            .filter_map(|name| name.typed_registrable_domain())
+            .collect::<HashSet<_>>();
+        assert_eq!(domains.len(), 1);
+        assert_eq!(relative.typed_subdomain(), None);
+
+        let suffix_only = list.parse_dns_name("co.uk").unwrap();
+        assert!(suffix_only.typed_registrable_domain().is_none());
+        assert_eq!(suffix_only.typed_suffix().unwrap(), "co.uk");
+        assert!(list.parse_dns_name(".").unwrap().typed_suffix().is_none());
+    }
+}
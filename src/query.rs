@@ -0,0 +1,377 @@
+//! Queries over the rules of a list
+
+use std::borrow::Cow;
+
+use crate::{escape, DnsName, List, ListLeaf, ListNode, PREVAILING_STAR_RULE};
+
+/// The rule a name matched, found by `List::longest_suffix_match`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchInfo<'a> {
+    /// the trailing labels of the name the rule covered
+    matched: Cow<'a, str>,
+    labels: usize,
+    wildcard: bool,
+    exception: bool,
+}
+
+/// The kind of rule a name's suffix came from, from
+/// `DnsName::suffix_origin`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SuffixOrigin {
+    /// a rule naming the suffix, like `co.uk`
+    Exact,
+    /// a wildcard rule in the list, like `*.kobe.jp`
+    Wildcard,
+    /// an exception rule, like `!city.kobe.jp`; the suffix is the rule
+    /// without its first label
+    Exception,
+    /// no rule in the list matched and the default `*` rule made the last
+    /// label the suffix
+    Default,
+}
+
+impl DnsName {
+    /// Gets the kind of rule the suffix came from, or `None` if there is no
+    /// suffix
+    ///
+    /// Suffixes from wildcard rules and the default rule are guesses about
+    /// names the list does not spell out, so they deserve less confidence
+    /// than those from exact and exception rules.
+    ///
+    /// ```rust
+    /// # use dns_name::{List, SuffixOrigin};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let origin = |name: &str| list.parse_dns_name(name).unwrap().suffix_origin();
+    /// assert_eq!(origin("www.example.co.uk"), Some(SuffixOrigin::Exact));
+    /// assert_eq!(origin("www.example.kobe.jp"), Some(SuffixOrigin::Wildcard));
+    /// assert_eq!(origin("www.city.kobe.jp"), Some(SuffixOrigin::Exception));
+    /// assert_eq!(origin("www.example.nope"), Some(SuffixOrigin::Default));
+    /// assert_eq!(origin("."), None);
+    /// ```
+    pub fn suffix_origin(&self) -> Option<SuffixOrigin> {
+        self.suffix_range().and(self.origin)
+    }
+}
+
+impl MatchInfo<'_> {
+    /// The matched rule, as it appears in the list: `co.uk`, `*.kobe.jp`,
+    /// `!city.kobe.jp`
+    pub fn rule(&self) -> String {
+        let mut rule = String::new();
+        if self.exception {
+            rule.push('!');
+        }
+        match (self.wildcard, escape::split_first_label(&self.matched)) {
+            (true, Some((_, rest))) => {
+                rule.push_str("*.");
+                rule.push_str(rest);
+            }
+            (true, None) => rule.push('*'),
+            (false, _) => rule.push_str(&self.matched),
+        }
+        rule
+    }
+
+    /// Number of labels in the rule
+    pub fn rule_labels(&self) -> usize {
+        self.labels
+    }
+
+    /// Number of labels in the public suffix: one less than the rule for an
+    /// exception rule
+    pub fn suffix_labels(&self) -> usize {
+        self.labels - usize::from(self.exception)
+    }
+
+    /// Returns true if a wildcard in the rule matched a label
+    pub fn is_wildcard(&self) -> bool {
+        self.wildcard
+    }
+
+    /// Returns true if the rule is an exception rule
+    pub fn is_exception(&self) -> bool {
+        self.exception
+    }
+}
+
+impl List {
+    /// Gets every rule beneath a suffix, sorted
+    ///
+    /// The rule for `prefix` itself is not included. Exception rules keep
+    /// their `!`. Returns nothing if no rule is beneath `prefix`.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = "uk,co.uk,*.sch.uk,!www.sch.uk,com".parse::<List>().unwrap();
+    /// assert_eq!(
+    ///     list.suffixes_under("UK."),
+    ///     ["!www.sch.uk", "*.sch.uk", "co.uk"]
+    /// );
+    /// ```
+    pub fn suffixes_under(&self, prefix: &str) -> Vec<String> {
+        let prefix = prefix.trim_end_matches('.').to_ascii_lowercase();
+        let mut labels = prefix.rsplit('.').collect::<Vec<_>>();
+
+        let mut node = &self.root;
+        for label in &labels {
+            match node.children.get(*label) {
+                Some(child) => node = child,
+                None => return Vec::new(),
+            }
+        }
+
+        let mut rules = Vec::new();
+        collect_rules(node, &mut labels, &mut rules);
+        rules.sort();
+        rules
+    }
+
+    /// Finds the longest rule matching a name, without parsing it into a
+    /// `DnsName`
+    ///
+    /// Returns `None` for the root name and names with empty labels. Names
+    /// that are already lowercase are not copied.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let found = list.longest_suffix_match("www.example.co.uk").unwrap();
+    /// assert_eq!(found.rule(), "co.uk");
+    /// assert_eq!(found.suffix_labels(), 2);
+    ///
+    /// let found = list.longest_suffix_match("www.city.kobe.jp").unwrap();
+    /// assert_eq!(found.rule(), "!city.kobe.jp");
+    /// assert!(found.is_exception());
+    /// assert_eq!(found.suffix_labels(), 2);
+    /// ```
+    pub fn longest_suffix_match<'a>(&self, name: &'a str) -> Option<MatchInfo<'a>> {
+        let domain = escape::trim_dot(name);
+        let domain = if domain.bytes().any(|b| b.is_ascii_uppercase()) {
+            Cow::Owned(domain.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(domain)
+        };
+        let labels = escape::label_ranges(&domain);
+        if labels.iter().any(|label| label.is_empty()) {
+            return None;
+        }
+
+        let (leaf, len, wildcard) =
+            self.longest_rule(labels.iter().rev().map(|label| &domain[label.clone()]))?;
+        let start = labels[labels.len() - len].start;
+        let matched = match domain {
+            Cow::Borrowed(domain) => Cow::Borrowed(&domain[start..]),
+            Cow::Owned(domain) => Cow::Owned(domain[start..].to_owned()),
+        };
+        Some(MatchInfo {
+            matched,
+            labels: len,
+            wildcard,
+            exception: leaf.is_exception_rule,
+        })
+    }
+
+    /// Gets the metadata attached to the rule `name`'s suffix came from
+    ///
+    /// Metadata is attached when the list is built, with
+    /// `ListBuilder::annotated` or `ListBuilder::add_rule_with_metadata`. The
+    /// rule is found again from the suffix and `DnsName::suffix_origin`, so
+    /// `name` should have been parsed with this list. Names without a suffix
+    /// origin, like a `parent` whose suffix was cut down, have no metadata.
+    ///
+    /// ```rust
+    /// # use dns_name::ListBuilder;
+    /// let data = "com 0.1\nxyz 0.8\nco.uk\n";
+    /// let list = ListBuilder::new()
+    ///     .from_bytes(data.as_bytes())
+    ///     .annotated(true)
+    ///     .build()
+    ///     .unwrap();
+    /// let metadata = |name: &str| {
+    ///     let name = list.parse_dns_name(name).unwrap();
+    ///     list.rule_metadata(&name).map(str::to_owned)
+    /// };
+    /// assert_eq!(metadata("www.example.xyz").as_deref(), Some("0.8"));
+    /// assert_eq!(metadata("www.example.co.uk"), None);
+    /// ```
+    pub fn rule_metadata(&self, name: &DnsName) -> Option<&str> {
+        let origin = name.suffix_origin()?;
+        // an exception rule also names the label left of the suffix
+        let rule = match origin {
+            SuffixOrigin::Exception => name.root()?,
+            _ => name.suffix()?,
+        };
+        let rule = escape::trim_dot(rule);
+        let labels = escape::label_ranges(rule);
+        let labels = labels
+            .iter()
+            .rev()
+            .map(|label| &rule[label.clone()])
+            .collect::<Vec<_>>();
+        let leaf = find_leaf(&self.root, &labels, false, origin)?;
+        leaf.metadata.as_deref()
+    }
+
+    /// Iterates over the top-level rules, in no particular order
+    ///
+    /// The implicit `*` rule is not included.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = "com,co.uk,net".parse::<List>().unwrap();
+    /// let mut tlds = list.tlds().collect::<Vec<_>>();
+    /// tlds.sort();
+    /// assert_eq!(tlds, ["com", "net"]);
+    /// ```
+    pub fn tlds(&self) -> impl Iterator<Item = &str> {
+        self.root
+            .children
+            .iter()
+            .filter(|(label, node)| &***label != PREVAILING_STAR_RULE && node.leaf.is_some())
+            .map(|(label, _)| &**label)
+    }
+}
+
+/// Collects the rules strictly beneath `node`, whose labels are `labels` in
+/// reverse order
+pub(crate) fn collect_rules<'a>(
+    node: &'a ListNode,
+    labels: &mut Vec<&'a str>,
+    rules: &mut Vec<String>,
+) {
+    for (label, child) in &node.children {
+        labels.push(label);
+        if let Some(leaf) = &child.leaf {
+            let name = labels.iter().rev().copied().collect::<Vec<_>>().join(".");
+            rules.push(if leaf.is_exception_rule {
+                format!("!{name}")
+            } else {
+                name
+            });
+        }
+        collect_rules(child, labels, rules);
+        labels.pop();
+    }
+}
+
+/// Finds the rule of the kind `origin` covering `labels`, given right to
+/// left, where `wildcard` is whether a `*` took a label on the way here
+///
+/// Exact labels are tried before wildcards, at every level.
+fn find_leaf<'a>(
+    node: &'a ListNode,
+    labels: &[&str],
+    wildcard: bool,
+    origin: SuffixOrigin,
+) -> Option<&'a ListLeaf> {
+    let Some((label, rest)) = labels.split_first() else {
+        let exception = origin == SuffixOrigin::Exception;
+        let by_wildcard = matches!(origin, SuffixOrigin::Wildcard | SuffixOrigin::Default);
+        return node
+            .leaf
+            .as_ref()
+            .filter(|leaf| leaf.is_exception_rule == exception)
+            .filter(|_| exception || wildcard == by_wildcard);
+    };
+    node.children
+        .get(*label)
+        .and_then(|child| find_leaf(child, rest, wildcard, origin))
+        .or_else(|| find_leaf(node.children.get(PREVAILING_STAR_RULE)?, rest, true, origin))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suffixes_under() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+
+        let uk = list.suffixes_under("uk");
+        assert!(uk.contains(&"co.uk".to_owned()));
+        assert!(!uk.contains(&"uk".to_owned()));
+        assert!(uk.windows(2).all(|w| w[0] < w[1]));
+
+        let kobe = list.suffixes_under("kobe.jp");
+        assert_eq!(kobe, ["!city.kobe.jp", "*.kobe.jp"]);
+        assert!(list.suffixes_under("example.com").is_empty());
+        assert!(list.suffixes_under("nope").is_empty());
+
+        let tlds = list.tlds().collect::<Vec<_>>();
+        assert!(tlds.contains(&"com") && tlds.contains(&"uk"));
+        assert!(!tlds.contains(&"*"));
+    }
+
+    #[test]
+    fn longest_suffix_match() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let rule = |name: &str| list.longest_suffix_match(name).map(|found| found.rule());
+
+        assert_eq!(rule("www.Example.COM."), Some("com".to_owned()));
+        assert_eq!(rule("a.b.kobe.jp"), Some("*.kobe.jp".to_owned()));
+        assert_eq!(rule("example.nope"), Some("*".to_owned()));
+        assert_eq!(rule("."), None);
+        assert_eq!(rule("a..com"), None);
+
+        let found = list.longest_suffix_match("a.b.kobe.jp").unwrap();
+        assert!(found.is_wildcard() && !found.is_exception());
+        assert_eq!((found.rule_labels(), found.suffix_labels()), (3, 3));
+
+        // the answers agree with parsing
+        for name in [
+            "www.example.co.uk",
+            "x.city.kobe.jp",
+            "com",
+            "a.b.example.nope",
+        ] {
+            let parsed = list.parse_dns_name(name).unwrap();
+            let found = list.longest_suffix_match(name).unwrap();
+            let suffix_labels = parsed
+                .suffix()
+                .map_or(0, |suffix| suffix.split('.').count());
+            assert_eq!(found.suffix_labels(), suffix_labels, "{name}");
+        }
+    }
+
+    #[test]
+    fn suffix_origin() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let origin = |name: &str| list.parse_dns_name(name).unwrap().suffix_origin();
+
+        assert_eq!(origin("com."), Some(SuffixOrigin::Exact));
+        assert_eq!(origin("a.b.kobe.jp"), Some(SuffixOrigin::Wildcard));
+        assert_eq!(origin("city.kobe.jp"), Some(SuffixOrigin::Exception));
+        assert_eq!(origin("nope"), Some(SuffixOrigin::Default));
+
+        // names derived from a parsed one keep its origin
+        let name = list.parse_dns_name("a.b.kobe.jp").unwrap();
+        assert_eq!(
+            name.prepend("www").unwrap().suffix_origin(),
+            Some(SuffixOrigin::Wildcard)
+        );
+        assert_eq!(
+            name.parent().unwrap().suffix_origin(),
+            Some(SuffixOrigin::Wildcard)
+        );
+        assert_eq!(
+            list.parse_into("a.b.kobe.jp", &crate::NameArena::new())
+                .unwrap()
+                .to_dns_name()
+                .suffix_origin(),
+            Some(SuffixOrigin::Wildcard)
+        );
+
+        let list = "*.ck,!www.ck,!nope".parse::<List>().unwrap();
+        let name = list.parse_dns_name("a.www.ck").unwrap();
+        assert_eq!(
+            (name.suffix(), name.suffix_origin()),
+            (Some("ck"), Some(SuffixOrigin::Exception))
+        );
+        // a top-level exception leaves no suffix
+        let name = list.parse_dns_name("a.nope").unwrap();
+        assert_eq!((name.suffix(), name.suffix_origin()), (None, None));
+        let empty = List::empty().parse_dns_name("example.com").unwrap();
+        assert_eq!(empty.suffix_origin(), None);
+    }
+}
@@ -0,0 +1,184 @@
+//! Several named lists held together
+
+use std::{
+    collections::{HashMap, HashSet},
+    io, mem,
+    sync::Arc,
+};
+
+use crate::{DnsName, List, ListNode, ParseOptions, SharedList};
+
+/// Named lists, such as policy variants, parsed against by name
+///
+/// Lists added to the same registry share the storage for labels they have
+/// in common, so variants of one list cost little more than the list
+/// itself.
+///
+/// ```rust
+/// # use dns_name::{List, ListRegistry};
+/// let mut registry = ListRegistry::new();
+/// registry.insert("icann", List::from_path("suffix-list.txt").unwrap());
+/// registry.insert("tenant-42", "com,corp,internal.corp".parse::<List>().unwrap());
+///
+/// let name = registry.parse("tenant-42", "app.team.internal.corp").unwrap();
+/// assert_eq!(name.root(), Some("team.internal.corp"));
+/// let name = registry.parse("icann", "app.team.internal.corp").unwrap();
+/// assert_eq!(name.root(), Some("internal.corp"));
+/// assert!(registry.parse("missing", "example.com").is_err());
+/// ```
+#[derive(Debug, Default)]
+pub struct ListRegistry {
+    lists: HashMap<String, SharedList>,
+    /// every label of every list, shared between them
+    labels: HashSet<Arc<str>>,
+}
+
+impl ListRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a list under `name`, returning the list it replaces
+    pub fn insert(&mut self, name: impl Into<String>, mut list: List) -> Option<SharedList> {
+        list.root.intern(&mut self.labels);
+        self.prune();
+        self.lists.insert(name.into(), SharedList::new(list))
+    }
+
+    /// Removes the list named `name`
+    pub fn remove(&mut self, name: &str) -> Option<SharedList> {
+        self.prune();
+        self.lists.remove(name)
+    }
+
+    /// Gets the list named `name`
+    ///
+    /// The handle stays valid if the list is later replaced or removed.
+    pub fn get(&self, name: &str) -> Option<&SharedList> {
+        self.lists.get(name)
+    }
+
+    /// Iterates over the names of the lists, in no particular order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.lists.keys().map(String::as_str)
+    }
+
+    /// Number of lists
+    pub fn len(&self) -> usize {
+        self.lists.len()
+    }
+
+    /// Returns true if there are no lists
+    pub fn is_empty(&self) -> bool {
+        self.lists.is_empty()
+    }
+
+    /// Parses a DNS name using the list named `list`
+    ///
+    /// Fails with `io::ErrorKind::NotFound` if there is no such list.
+    pub fn parse(&self, list: &str, domain: &str) -> io::Result<DnsName> {
+        self.parse_with(list, domain, &ParseOptions::default())
+    }
+
+    /// Parses a DNS name using the list named `list`, with extra checks
+    /// enabled by `options`
+    pub fn parse_with(
+        &self,
+        list: &str,
+        domain: &str,
+        options: &ParseOptions,
+    ) -> io::Result<DnsName> {
+        let Some(found) = self.lists.get(list) else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no list named {list:?}"),
+            ));
+        };
+        found.parse_dns_name_with(domain, options)
+    }
+
+    /// Forgets labels no list uses any more
+    ///
+    /// A list that was replaced or removed keeps its labels alive while
+    /// handles to it remain, so this runs on every change rather than once.
+    fn prune(&mut self) {
+        self.labels.retain(|label| Arc::strong_count(label) > 1);
+    }
+}
+
+impl ListNode {
+    /// Swaps the labels of this node and every node below it for the copies
+    /// in `labels`, adding those it lacks
+    fn intern(&mut self, labels: &mut HashSet<Arc<str>>) {
+        let children = mem::take(&mut self.children);
+        self.children = children
+            .into_iter()
+            .map(|(label, mut child)| {
+                child.intern(labels);
+                let label = match labels.get(&*label) {
+                    Some(shared) => shared.clone(),
+                    None => {
+                        labels.insert(label.clone());
+                        label
+                    }
+                };
+                (label, child)
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_labels() {
+        let mut registry = ListRegistry::new();
+        registry.insert("icann", "com,co.uk,uk".parse::<List>().unwrap());
+        registry.insert(
+            "private",
+            "com,co.uk,uk,blogspot.com".parse::<List>().unwrap(),
+        );
+
+        let label = |list: &str, label: &str| {
+            let root = &registry.get(list).unwrap().root;
+            root.children.get_key_value(label).unwrap().0.clone()
+        };
+        assert!(Arc::ptr_eq(
+            &label("icann", "com"),
+            &label("private", "com")
+        ));
+        assert!(Arc::ptr_eq(&label("icann", "uk"), &label("private", "uk")));
+        // com, uk, co, blogspot and the default *
+        assert_eq!(registry.labels.len(), 5);
+
+        let name = registry.parse("private", "a.blogspot.com").unwrap();
+        assert_eq!(name.root(), Some("a.blogspot.com"));
+        assert_eq!(
+            registry.parse("icann", "a.blogspot.com").unwrap().root(),
+            Some("blogspot.com")
+        );
+
+        let handle = registry.get("private").unwrap().clone();
+        assert!(registry.remove("private").is_some());
+        assert_eq!(registry.len(), 1);
+        assert_eq!(
+            handle.parse_dns_name("a.blogspot.com").unwrap().root(),
+            Some("a.blogspot.com")
+        );
+        let err = registry.parse("private", "example.com").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        // `blogspot` is forgotten at the next change once nothing uses it
+        drop(handle);
+        registry.insert("empty", List::empty());
+        assert_eq!(registry.labels.len(), 4);
+
+        assert!(registry.insert("icann", List::empty()).is_some());
+        assert!(registry.remove("empty").is_some());
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["icann"]);
+        assert!(registry.labels.is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+//! Reverse DNS names (`in-addr.arpa` and `ip6.arpa`)
+
+use std::{
+    fmt::Write,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use crate::{escape, DnsName, List};
+
+const IN_ADDR_ARPA: &str = "in-addr.arpa";
+const IP6_ARPA: &str = "ip6.arpa";
+
+impl DnsName {
+    /// Gets the address a reverse DNS name refers to
+    ///
+    /// Only complete names are recognized: four decimal octets under
+    /// `in-addr.arpa` or 32 hex nibbles under `ip6.arpa`, least significant
+    /// first.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// # use std::net::{IpAddr, Ipv4Addr};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("4.3.2.1.in-addr.arpa.").unwrap();
+    /// assert_eq!(name.as_reverse_ip(), Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))));
+    /// ```
+    pub fn as_reverse_ip(&self) -> Option<IpAddr> {
+        let domain = escape::trim_dot(self.name());
+
+        if let Some(octets) = strip_zone(domain, IN_ADDR_ARPA) {
+            return parse_octets(octets).map(IpAddr::V4);
+        }
+        if let Some(nibbles) = strip_zone(domain, IP6_ARPA) {
+            return parse_nibbles(nibbles).map(IpAddr::V6);
+        }
+        None
+    }
+
+    /// Generates the reverse DNS name of an address
+    ///
+    /// ```rust
+    /// # use dns_name::{DnsName, List};
+    /// # use std::net::IpAddr;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let addr = "2001:db8::1".parse::<IpAddr>().unwrap();
+    /// let name = DnsName::from_ip(addr, &list);
+    /// assert_eq!(
+    ///     name.name(),
+    ///     "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+    /// );
+    /// assert_eq!(name.as_reverse_ip(), Some(addr));
+    /// ```
+    pub fn from_ip(addr: IpAddr, list: &List) -> DnsName {
+        let mut name = String::with_capacity(72);
+        match addr {
+            IpAddr::V4(addr) => {
+                for octet in addr.octets().iter().rev() {
+                    let _ = write!(name, "{octet}.");
+                }
+                name.push_str(IN_ADDR_ARPA);
+            }
+            IpAddr::V6(addr) => {
+                for octet in addr.octets().iter().rev() {
+                    let _ = write!(name, "{:x}.{:x}.", octet & 0xf, octet >> 4);
+                }
+                name.push_str(IP6_ARPA);
+            }
+        }
+        list.parse_dns_name(&name)
+            .expect("reverse names only contain valid labels")
+    }
+}
+
+/// Strips `.zone` from the end of a name
+fn strip_zone<'a>(domain: &'a str, zone: &str) -> Option<&'a str> {
+    domain.strip_suffix(zone)?.strip_suffix('.')
+}
+
+/// Parses 4 decimal labels, least significant first, into an IPv4 address
+fn parse_octets(octets: &str) -> Option<Ipv4Addr> {
+    let mut addr = [0u8; 4];
+    let mut labels = octets.split('.');
+    for octet in addr.iter_mut().rev() {
+        let label = labels.next()?;
+        if label.is_empty() || !label.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        *octet = label.parse().ok()?;
+    }
+    match labels.next() {
+        None => Some(Ipv4Addr::from(addr)),
+        Some(_) => None,
+    }
+}
+
+/// Parses 32 nibble labels, least significant first, into an IPv6 address
+fn parse_nibbles(nibbles: &str) -> Option<Ipv6Addr> {
+    let mut addr = [0u8; 16];
+    let mut labels = nibbles.split('.');
+    for octet in addr.iter_mut().rev() {
+        for shift in [0, 4] {
+            let label = labels.next()?;
+            if label.len() != 1 {
+                return None;
+            }
+            let nibble = u8::from_str_radix(label, 16).ok()?;
+            *octet |= nibble << shift;
+        }
+    }
+    match labels.next() {
+        None => Some(Ipv6Addr::from(addr)),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverse() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let reverse = |name: &str| list.parse_dns_name(name).unwrap().as_reverse_ip();
+
+        for addr in [
+            "127.0.0.1",
+            "255.0.10.1",
+            "::",
+            "::1",
+            "fd79:cdcb:38cc:9dd:f686:e06d:32f3:c123",
+        ] {
+            let addr = addr.parse::<IpAddr>().unwrap();
+            let name = DnsName::from_ip(addr, &list);
+            assert_eq!(name.as_reverse_ip(), Some(addr));
+            assert_eq!(name.suffix(), Some("arpa"));
+        }
+
+        assert_eq!(
+            reverse("1.0.0.127.IN-ADDR.ARPA."),
+            Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+        );
+        assert_eq!(
+            reverse(&format!("{}ip6.arpa", "F.".repeat(32))),
+            Some(IpAddr::V6(Ipv6Addr::from([0xff; 16])))
+        );
+
+        assert_eq!(reverse("0.0.127.in-addr.arpa"), None);
+        assert_eq!(reverse("1.1.0.0.127.in-addr.arpa"), None);
+        assert_eq!(reverse("256.0.0.127.in-addr.arpa"), None);
+        assert_eq!(reverse("+1.0.0.127.in-addr.arpa"), None);
+        assert_eq!(reverse("in-addr.arpa"), None);
+        assert_eq!(reverse("1.0.0.127xin-addr.arpa"), None);
+        assert_eq!(reverse(&format!("{}ip6.arpa", "0.".repeat(31))), None);
+        assert_eq!(reverse(&format!("{}ip6.arpa", "00.".repeat(32))), None);
+        assert_eq!(reverse(&format!("{}ip6.arpa", "g.".repeat(32))), None);
+        assert_eq!(reverse("example.com"), None);
+    }
+}
@@ -0,0 +1,366 @@
+//! Homograph detection (UTS #39 confusable skeletons)
+//!
+//! Two names are confusable when they have the same skeleton: every character
+//! is replaced by the prototype it is easily mistaken for, so `раypal.com`
+//! (with Cyrillic `р` and `а`) and `paypal.com` compare equal.
+//!
+//! The mapping is the subset of the Unicode [confusables] data covering
+//! Cyrillic, Greek and Latin lookalikes of the letters and digits allowed in
+//! host names. Names are case-insensitive, so skeletons are lowercased.
+//!
+//! [`Watchlist`] builds on skeletons to score names for typosquatting of a
+//! set of protected domains.
+//!
+//! [confusables]: https://www.unicode.org/Public/security/latest/confusables.txt
+
+use crate::DnsName;
+
+/// Prototypes of confusable characters, sorted by character
+const CONFUSABLES: &[(char, &str)] = &[
+    ('0', "o"),
+    ('1', "l"),
+    ('I', "l"),
+    ('O', "o"),
+    ('m', "rn"),
+    ('|', "l"),
+    ('ı', "i"),
+    ('ǀ', "l"),
+    ('ɑ', "a"),
+    ('ɡ', "g"),
+    ('ɩ', "i"),
+    ('α', "a"),
+    ('ι', "i"),
+    ('ν', "v"),
+    ('ο', "o"),
+    ('ρ', "p"),
+    ('σ', "o"),
+    ('а', "a"),
+    ('е', "e"),
+    ('о', "o"),
+    ('р', "p"),
+    ('с', "c"),
+    ('у', "y"),
+    ('х', "x"),
+    ('ѕ', "s"),
+    ('і', "i"),
+    ('ј', "j"),
+    ('һ', "h"),
+    ('ӏ', "l"),
+    ('ԁ', "d"),
+    ('ԛ', "q"),
+    ('ԝ', "w"),
+    ('ℓ', "l"),
+];
+
+/// Gets the skeleton of a single character
+fn prototype(c: char) -> Option<&'static str> {
+    CONFUSABLES
+        .binary_search_by_key(&c, |&(c, _)| c)
+        .ok()
+        .map(|i| CONFUSABLES[i].1)
+}
+
+/// Computes the confusable skeleton of a string
+pub fn skeleton(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match prototype(c) {
+            Some(prototype) => out.push_str(prototype),
+            None => out.extend(c.to_lowercase()),
+        }
+    }
+    out
+}
+
+impl DnsName {
+    /// Gets the confusable skeleton of the Name
+    ///
+    /// `xn--` labels are decoded first and the trailing dot is dropped.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("xn--ypal-43d9g.com").unwrap();
+    /// assert_eq!(name.name_unicode(), "раypal.com");
+    /// assert_eq!(name.skeleton(), "paypal.corn");
+    /// ```
+    pub fn skeleton(&self) -> String {
+        let unicode = self.name_unicode();
+        skeleton(crate::escape::trim_dot(&unicode))
+    }
+
+    /// Checks whether two Names could be mistaken for each other
+    ///
+    /// Identical names are trivially confusable.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let real = list.parse_dns_name("paypal.com").unwrap();
+    /// let fake = list.parse_dns_name("раураl.com").unwrap();
+    /// let other = list.parse_dns_name("example.com").unwrap();
+    /// assert!(real.is_confusable_with(&fake));
+    /// assert!(!real.is_confusable_with(&other));
+    /// ```
+    pub fn is_confusable_with(&self, other: &DnsName) -> bool {
+        self.skeleton() == other.skeleton()
+    }
+}
+
+/// Why a name looks like a protected domain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LookalikeKind {
+    /// The same registrable label under another suffix: `paypal.net`
+    OtherSuffix,
+    /// The registrable labels have the same skeleton: `pаypal.com`
+    Homoglyph,
+    /// Hyphens or digits were added: `pay-pal.com`, `paypal2.com`
+    Decorated,
+    /// The registrable labels are a few edits apart: `paypa.com`
+    Typo { distance: usize },
+}
+
+/// A name found to resemble a protected domain
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lookalike<'a> {
+    /// The protected domain it resembles
+    pub protected: &'a DnsName,
+    pub kind: LookalikeKind,
+    /// How close the resemblance is, from 0 exclusive to 1
+    pub score: f64,
+}
+
+/// A protected domain and the skeleton of its registrable label
+#[derive(Debug, Clone)]
+struct Protected {
+    name: DnsName,
+    label: String,
+}
+
+/// Protected registrable domains to check names against for typosquatting
+///
+/// ```rust
+/// # use dns_name::List;
+/// # use dns_name::security::{LookalikeKind, Watchlist};
+/// let list = List::from_path("suffix-list.txt").unwrap();
+/// let mut watchlist = Watchlist::new();
+/// watchlist.protect(list.parse_dns_name("paypal.com").unwrap());
+///
+/// let name = list.parse_dns_name("login.pay-pal.com").unwrap();
+/// let lookalike = watchlist.check(&name).unwrap();
+/// assert_eq!(lookalike.protected.name(), "paypal.com");
+/// assert_eq!(lookalike.kind, LookalikeKind::Decorated);
+///
+/// // the protected domain itself is not a lookalike
+/// let name = list.parse_dns_name("www.paypal.com").unwrap();
+/// assert!(watchlist.check(&name).is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Watchlist {
+    protected: Vec<Protected>,
+}
+
+impl Watchlist {
+    /// Creates an empty watch list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a protected domain
+    ///
+    /// Only the root domain is used, so `www.paypal.com` protects
+    /// `paypal.com`. Names without a registrable label are ignored.
+    pub fn protect(&mut self, name: DnsName) {
+        if let Some(label) = name.registrable_unicode() {
+            let label = skeleton(&label);
+            self.protected.push(Protected { name, label });
+        }
+    }
+
+    /// Number of protected domains
+    pub fn len(&self) -> usize {
+        self.protected.len()
+    }
+
+    /// Returns true if no domains are protected
+    pub fn is_empty(&self) -> bool {
+        self.protected.is_empty()
+    }
+
+    /// Finds the protected domain a name most resembles, if any
+    ///
+    /// Names under a protected root domain are never lookalikes. Typos are
+    /// counted as single character insertions, deletions, substitutions or
+    /// transpositions, allowing one edit in labels of up to 5 characters
+    /// and two in longer ones.
+    // `Option::is_none_or` needs Rust 1.82
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn check(&self, name: &DnsName) -> Option<Lookalike<'_>> {
+        let root = name.root()?;
+        let label = skeleton(&name.registrable_unicode()?);
+
+        let mut best: Option<Lookalike> = None;
+        for protected in &self.protected {
+            let protected_root = protected.name.root().unwrap_or_default();
+            if crate::escape::trim_dot(root) == crate::escape::trim_dot(protected_root) {
+                return None;
+            }
+
+            let Some(kind) = classify(&label, name, protected) else {
+                continue;
+            };
+            let score = match kind {
+                LookalikeKind::OtherSuffix | LookalikeKind::Homoglyph => 1.0,
+                LookalikeKind::Decorated => 0.9,
+                LookalikeKind::Typo { distance } => {
+                    let len = protected.label.chars().count().max(1);
+                    0.8 * (1.0 - distance as f64 / len as f64)
+                }
+            };
+            if best.as_ref().map_or(true, |best| score > best.score) {
+                best = Some(Lookalike {
+                    protected: &protected.name,
+                    kind,
+                    score,
+                });
+            }
+        }
+        best
+    }
+}
+
+impl FromIterator<DnsName> for Watchlist {
+    fn from_iter<I: IntoIterator<Item = DnsName>>(names: I) -> Self {
+        let mut watchlist = Watchlist::new();
+        names.into_iter().for_each(|name| watchlist.protect(name));
+        watchlist
+    }
+}
+
+/// Decides how a registrable label skeleton resembles a protected one
+fn classify(label: &str, name: &DnsName, protected: &Protected) -> Option<LookalikeKind> {
+    if label == protected.label {
+        return if name.registrable() == protected.name.registrable() {
+            Some(LookalikeKind::OtherSuffix)
+        } else {
+            Some(LookalikeKind::Homoglyph)
+        };
+    }
+
+    let undecorated = label
+        .chars()
+        .filter(|c| *c != '-' && !c.is_ascii_digit())
+        .collect::<String>();
+    if undecorated == protected.label {
+        return Some(LookalikeKind::Decorated);
+    }
+
+    let max = if protected.label.chars().count() <= 5 {
+        1
+    } else {
+        2
+    };
+    match edit_distance(label, &protected.label) {
+        distance if distance <= max => Some(LookalikeKind::Typo { distance }),
+        _ => None,
+    }
+}
+
+/// Optimal string alignment distance: Levenshtein plus adjacent
+/// transpositions
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    // rows i - 2, i - 1 and i of the distance matrix
+    let mut prev2 = vec![0; b.len() + 1];
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut row = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        row[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (prev[j] + 1).min(row[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(prev2[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut row);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::List;
+
+    #[test]
+    fn sorted() {
+        assert!(CONFUSABLES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn confusable() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let parse = |name: &str| list.parse_dns_name(name).unwrap();
+
+        assert_eq!(skeleton("G00gle"), "google");
+        assert_eq!(skeleton("αρρlе"), "apple");
+
+        let real = parse("example.com.");
+        assert!(real.is_confusable_with(&parse("ехаmрlе.com")));
+        assert!(real.is_confusable_with(&parse("examp1e.com")));
+        assert!(real.is_confusable_with(&parse("EXAMPLE.COM")));
+        assert!(!real.is_confusable_with(&parse("example.net")));
+        assert!(parse("modern.com").is_confusable_with(&parse("rnodern.com")));
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("paypal", "paypal"), 0);
+        assert_eq!(edit_distance("paypal", "paypa"), 1);
+        assert_eq!(edit_distance("paypal", "papyal"), 1);
+        assert_eq!(edit_distance("paypal", "pyapla"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn watchlist() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let parse = |name: &str| list.parse_dns_name(name).unwrap();
+        let watchlist = [parse("paypal.com"), parse("www.bbc.co.uk")]
+            .into_iter()
+            .collect::<Watchlist>();
+        assert_eq!(watchlist.len(), 2);
+        let kind = |name: &str| watchlist.check(&parse(name)).map(|found| found.kind);
+
+        assert_eq!(kind("paypal.com."), None);
+        assert_eq!(kind("news.bbc.co.uk"), None);
+        assert_eq!(kind("example.com"), None);
+        assert_eq!(kind("paypal.net"), Some(LookalikeKind::OtherSuffix));
+        assert_eq!(kind("pаypal.com"), Some(LookalikeKind::Homoglyph));
+        assert_eq!(kind("paypa1.com"), Some(LookalikeKind::Homoglyph));
+        assert_eq!(kind("paypal-login.com"), None);
+        assert_eq!(kind("pay-pal24.com"), Some(LookalikeKind::Decorated));
+        assert_eq!(
+            kind("papyal.com"),
+            Some(LookalikeKind::Typo { distance: 1 })
+        );
+        assert_eq!(
+            kind("bbbc.co.uk"),
+            Some(LookalikeKind::Typo { distance: 1 })
+        );
+        assert_eq!(kind("bcb.co.uk"), Some(LookalikeKind::Typo { distance: 1 }));
+        assert_eq!(kind("abc.co.uk"), Some(LookalikeKind::Typo { distance: 1 }));
+        assert_eq!(kind("cnn.co.uk"), None);
+
+        let found = watchlist.check(&parse("paypal.net")).unwrap();
+        assert_eq!(found.protected.name(), "paypal.com");
+        assert_eq!(found.score, 1.0);
+        let found = watchlist.check(&parse("papyal.com")).unwrap();
+        assert!(found.score > 0.0 && found.score < 0.9);
+    }
+}
@@ -0,0 +1,112 @@
+//! A cheaply cloneable handle to a list
+
+use std::{ops::Deref, sync::Arc};
+
+use crate::List;
+
+// `List` is immutable once built, so it is shared between threads as is.
+// Keep it that way: a field that is not `Send + Sync` breaks the build here.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<List>();
+    assert_send_sync::<SharedList>();
+};
+
+/// A reference counted `List`
+///
+/// Cloning only bumps a reference count, so a `SharedList` can be moved
+/// into threads and async tasks freely. It dereferences to the `List`, so
+/// the whole parsing API is available on it.
+///
+/// ```rust
+/// # use dns_name::{List, SharedList};
+/// let list = SharedList::new(List::from_path("suffix-list.txt").unwrap());
+///
+/// let worker = {
+///     let list = list.clone();
+///     std::thread::spawn(move || list.parse_dns_name("www.example.co.uk").unwrap())
+/// };
+/// let name = worker.join().unwrap();
+/// assert_eq!(name.root(), Some("example.co.uk"));
+/// assert_eq!(list.parse_dns_name("www.example.co.uk").unwrap(), name);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedList(Arc<List>);
+
+impl SharedList {
+    /// Wraps a list for sharing
+    pub fn new(list: List) -> Self {
+        Self(Arc::new(list))
+    }
+
+    /// The reference counted list
+    pub fn as_arc(&self) -> &Arc<List> {
+        &self.0
+    }
+
+    /// Returns true if both handles share the same list
+    pub fn ptr_eq(&self, other: &SharedList) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for SharedList {
+    type Target = List;
+
+    fn deref(&self) -> &List {
+        &self.0
+    }
+}
+
+impl AsRef<List> for SharedList {
+    fn as_ref(&self) -> &List {
+        &self.0
+    }
+}
+
+impl From<List> for SharedList {
+    fn from(list: List) -> Self {
+        Self::new(list)
+    }
+}
+
+impl From<Arc<List>> for SharedList {
+    fn from(list: Arc<List>) -> Self {
+        Self(list)
+    }
+}
+
+impl From<SharedList> for Arc<List> {
+    fn from(list: SharedList) -> Self {
+        list.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared() {
+        let list = SharedList::from(List::from_path("suffix-list.txt").unwrap());
+        let clone = list.clone();
+        assert!(list.ptr_eq(&clone));
+        assert_eq!(Arc::strong_count(list.as_arc()), 2);
+
+        let roots = std::thread::scope(|scope| {
+            let workers = ["a.example.com", "b.example.co.uk"].map(|name| {
+                let list = list.clone();
+                scope.spawn(move || list.parse_dns_name(name).unwrap().root().map(str::to_owned))
+            });
+            workers.map(|worker| worker.join().unwrap())
+        });
+        assert_eq!(
+            roots,
+            [
+                Some("example.com".to_owned()),
+                Some("example.co.uk".to_owned())
+            ]
+        );
+        assert!(!list.ptr_eq(&SharedList::new(List::empty())));
+    }
+}
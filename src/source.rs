@@ -0,0 +1,337 @@
+//! Where a list is loaded from, and how it is verified
+
+use std::{
+    borrow::Cow,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{sha256, List};
+
+type Fetch = Arc<dyn Fn() -> io::Result<Vec<u8>> + Send + Sync>;
+type Verify = Arc<dyn Fn(&[u8], &[u8]) -> bool + Send + Sync>;
+
+/// Where the bytes of a list come from
+#[derive(Clone)]
+enum Origin {
+    Path(PathBuf),
+    Bytes(Cow<'static, [u8]>),
+    Fetch { name: String, fetch: Fetch },
+}
+
+/// A list to load, with optional integrity checks
+///
+/// The list is read in full and checked before it is parsed, so a list that
+/// fails verification is never used. Both the comma-separated and the
+/// publicsuffix.org formats are accepted.
+///
+/// ```rust
+/// # use dns_name::ListSource;
+/// let list = ListSource::bytes(b"com,co.uk".as_slice())
+///     .with_checksum("133e58c0f2041447ee13e5d174112e13abe8f9edaba8c11fb4b0e24dbb11fca6")
+///     .unwrap()
+///     .load()
+///     .unwrap();
+/// assert!(list.parse_dns_name("www.example.co.uk").is_ok());
+///
+/// let tampered = ListSource::bytes(b"com".as_slice())
+///     .with_checksum("133e58c0f2041447ee13e5d174112e13abe8f9edaba8c11fb4b0e24dbb11fca6")
+///     .unwrap();
+/// assert!(tampered.load().is_err());
+/// ```
+#[derive(Clone)]
+pub struct ListSource {
+    origin: Origin,
+    checksum: Option<[u8; 32]>,
+    signature: Option<(Vec<u8>, Verify)>,
+    fallback: Option<Box<ListSource>>,
+}
+
+impl ListSource {
+    fn new(origin: Origin) -> Self {
+        Self {
+            origin,
+            checksum: None,
+            signature: None,
+            fallback: None,
+        }
+    }
+
+    /// A list in a file
+    pub fn path<P: AsRef<Path>>(path: P) -> Self {
+        Self::new(Origin::Path(path.as_ref().to_owned()))
+    }
+
+    /// A list already in memory, such as one bundled with `include_bytes!`
+    pub fn bytes(bytes: impl Into<Cow<'static, [u8]>>) -> Self {
+        Self::new(Origin::Bytes(bytes.into()))
+    }
+
+    /// A list fetched by `fetch`, such as an HTTP download
+    ///
+    /// `name` describes the source in errors, such as the URL.
+    pub fn fetch(
+        name: impl Into<String>,
+        fetch: impl Fn() -> io::Result<Vec<u8>> + Send + Sync + 'static,
+    ) -> Self {
+        Self::new(Origin::Fetch {
+            name: name.into(),
+            fetch: Arc::new(fetch),
+        })
+    }
+
+    /// Requires the list to have this SHA-256 digest, given in hex
+    ///
+    /// Fails if `sha256` is not 64 hex digits.
+    pub fn with_checksum(mut self, sha256: &str) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidInput, "invalid sha-256 checksum");
+        // `from_str_radix` alone would take a sign, as in `+1`
+        if sha256.len() != 64 || !sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let mut checksum = [0; 32];
+        for (i, byte) in checksum.iter_mut().enumerate() {
+            // the checksum is ASCII, so any two bytes are a valid slice
+            *byte = u8::from_str_radix(&sha256[2 * i..2 * i + 2], 16).map_err(|_| invalid())?;
+        }
+        self.checksum = Some(checksum);
+        Ok(self)
+    }
+
+    /// Requires `verify(list, signature)` to accept the list's bytes
+    ///
+    /// This crate does no cryptography beyond SHA-256, so the check is
+    /// supplied by the caller, typically an Ed25519 or minisign verifier
+    /// holding the publisher's public key.
+    pub fn with_signature(
+        mut self,
+        signature: impl Into<Vec<u8>>,
+        verify: impl Fn(&[u8], &[u8]) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.signature = Some((signature.into(), Arc::new(verify)));
+        self
+    }
+
+    /// Tries `fallback` when this source fails to load
+    ///
+    /// Fallbacks are tried in the order they were added, each with its own
+    /// checks, and `List::source` records the one that was used.
+    ///
+    /// ```rust
+    /// # use dns_name::ListSource;
+    /// let list = ListSource::path("/var/cache/suffix-list.txt")
+    ///     .or(ListSource::path("suffix-list.txt"))
+    ///     .load()
+    ///     .unwrap();
+    /// assert_eq!(list.source(), Some("suffix-list.txt"));
+    /// ```
+    pub fn or(mut self, fallback: ListSource) -> Self {
+        let mut last = &mut self.fallback;
+        while let Some(source) = last {
+            last = &mut source.fallback;
+        }
+        *last = Some(Box::new(fallback));
+        self
+    }
+
+    /// Reads, verifies and parses the list, trying each fallback in turn
+    ///
+    /// A checksum or signature mismatch fails with
+    /// `io::ErrorKind::InvalidData`. If every source fails the error names
+    /// each of them and has the kind of the last one.
+    pub fn load(&self) -> io::Result<List> {
+        self.load_bytes().map(|(list, _)| list)
+    }
+
+    /// Loads the list as `load` does, along with the bytes it was parsed from
+    pub(crate) fn load_bytes(&self) -> io::Result<(List, Cow<'_, [u8]>)> {
+        let mut errors = Vec::new();
+        let mut next = Some(self);
+        while let Some(source) = next {
+            match source.load_one() {
+                Ok((mut list, bytes)) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::info!(source = %source, "loaded list");
+                    list.source = Some(source.to_string().into());
+                    return Ok((list, bytes));
+                }
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(source = %source, error = %err, "failed to load list");
+                    errors.push(io::Error::new(err.kind(), format!("{source}: {err}")));
+                }
+            }
+            next = source.fallback.as_deref();
+        }
+
+        let kind = errors
+            .last()
+            .map_or(io::ErrorKind::NotFound, io::Error::kind);
+        match errors.len() {
+            1 => Err(errors.remove(0)),
+            _ => {
+                let messages = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+                Err(io::Error::new(kind, messages.join("; ")))
+            }
+        }
+    }
+
+    fn load_one(&self) -> io::Result<(List, Cow<'_, [u8]>)> {
+        let bytes = self.read_verified()?;
+        Ok((List::from_buf_reader(&bytes[..])?, bytes))
+    }
+
+    /// Reads and verifies the bytes of this source, without its fallbacks
+    pub(crate) fn read_verified(&self) -> io::Result<Cow<'_, [u8]>> {
+        let bytes = self.read()?;
+        self.verify(&bytes)?;
+        Ok(bytes)
+    }
+
+    fn read(&self) -> io::Result<Cow<'_, [u8]>> {
+        match &self.origin {
+            Origin::Path(path) => fs::read(path).map(Cow::Owned),
+            Origin::Bytes(bytes) => Ok(Cow::Borrowed(bytes)),
+            Origin::Fetch { fetch, .. } => fetch().map(Cow::Owned),
+        }
+    }
+
+    fn verify(&self, bytes: &[u8]) -> io::Result<()> {
+        if let Some(checksum) = &self.checksum {
+            if sha256::digest(bytes) != *checksum {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "sha-256 checksum mismatch",
+                ));
+            }
+        }
+        if let Some((signature, verify)) = &self.signature {
+            if !verify(bytes, signature) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "signature verification failed",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ListSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.origin {
+            Origin::Path(path) => write!(f, "{}", path.display()),
+            Origin::Bytes(_) => f.write_str("bundled list"),
+            Origin::Fetch { name, .. } => f.write_str(name),
+        }
+    }
+}
+
+impl fmt::Debug for ListSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ListSource")
+            .field("source", &self.to_string())
+            .field("checksum", &self.checksum.is_some())
+            .field("signature", &self.signature.is_some())
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &[u8] = b"com,co.uk";
+    // sha256sum of RULES
+    const RULES_SHA256: &str = "133e58c0f2041447ee13e5d174112e13abe8f9edaba8c11fb4b0e24dbb11fca6";
+
+    #[test]
+    fn checksum() {
+        let list = ListSource::bytes(RULES)
+            .with_checksum(RULES_SHA256)
+            .unwrap()
+            .load()
+            .unwrap();
+        assert!(list.parse_dns_name("example.co.uk").is_ok());
+
+        let err = ListSource::bytes(b"com,co.uk,evil".as_slice())
+            .with_checksum(RULES_SHA256)
+            .unwrap()
+            .load()
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "bundled list: sha-256 checksum mismatch");
+
+        assert!(ListSource::bytes(RULES).with_checksum("abc").is_err());
+        assert!(ListSource::bytes(RULES)
+            .with_checksum(&"g".repeat(64))
+            .is_err());
+        assert!(ListSource::bytes(RULES)
+            .with_checksum(&format!("+1{}", "00".repeat(31)))
+            .is_err());
+        assert!(ListSource::bytes(RULES)
+            .with_checksum(&RULES_SHA256.to_uppercase())
+            .is_ok());
+    }
+
+    #[test]
+    fn signature() {
+        // stands in for a real signature scheme
+        let verify = |list: &[u8], signature: &[u8]| sha256::digest(list)[..] == *signature;
+        let signature = sha256::digest(RULES).to_vec();
+
+        let source = ListSource::fetch("https://example.com/list.dat", || Ok(RULES.to_vec()));
+        assert!(source
+            .clone()
+            .with_signature(signature, verify)
+            .load()
+            .is_ok());
+
+        let err = source
+            .with_signature(b"forged".to_vec(), verify)
+            .load()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "https://example.com/list.dat: signature verification failed"
+        );
+    }
+
+    #[test]
+    fn fallback() {
+        let source = ListSource::fetch("https://example.com/list.dat", || {
+            Err(io::Error::new(io::ErrorKind::TimedOut, "timed out"))
+        })
+        .or(ListSource::path("missing.txt"))
+        .or(ListSource::bytes(RULES));
+        let list = source.load().unwrap();
+        assert_eq!(list.source(), Some("bundled list"));
+        assert!(list.parse_dns_name("example.co.uk").is_ok());
+
+        // a fallback that fails its checks is skipped
+        let list = ListSource::bytes(b"com".as_slice())
+            .with_checksum(RULES_SHA256)
+            .unwrap()
+            .or(ListSource::path("suffix-list.txt"))
+            .load()
+            .unwrap();
+        assert_eq!(list.source(), Some("suffix-list.txt"));
+
+        let err = ListSource::path("missing.txt")
+            .or(ListSource::bytes(b"".as_slice()))
+            .load()
+            .unwrap_err();
+        assert!(err.to_string().starts_with("missing.txt: "));
+        assert!(err.to_string().ends_with("; bundled list: invalid list"));
+        assert_eq!(List::empty().source(), None);
+    }
+
+    #[test]
+    fn path() {
+        assert!(ListSource::path("suffix-list.txt").load().is_ok());
+        let err = ListSource::path("missing.txt").load().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
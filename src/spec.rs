@@ -0,0 +1,162 @@
+//! The publicsuffix.org matching algorithm, for `Algorithm::PslSpec`
+
+use crate::{List, ListLeaf, ListNode};
+
+/// The rule `*`, which applies when no rule matches
+static IMPLICIT_RULE: ListLeaf = ListLeaf {
+    is_exception_rule: false,
+    metadata: None,
+    private: false,
+};
+
+/// A matching rule: its leaf, how many labels it has, and whether a wildcard
+/// matched any of them
+pub(crate) type Rule<'a> = (&'a ListLeaf, usize, bool);
+
+impl List {
+    /// Finds the prevailing rule for a name with `len` labels, where
+    /// `label(0)` is the rightmost
+    ///
+    /// Every matching rule is considered, exact and wildcard alike. An
+    /// exception rule prevails over the others; otherwise the rule with the
+    /// most labels does. When no rule matches, the implicit `*` rule does,
+    /// whether or not the list has it.
+    pub(crate) fn prevailing_rule<'a, 'b>(
+        &'a self,
+        len: usize,
+        label: impl Fn(usize) -> &'b str,
+    ) -> Option<Rule<'a>> {
+        let mut prevailing = None;
+        visit(&self.root, 0, false, len, &label, &mut |rule| {
+            prevail(rule, &mut prevailing)
+        });
+        prevailing.or((len > 0).then_some((&IMPLICIT_RULE, 1, true)))
+    }
+
+    /// Finds the prevailing rule as `prevailing_rule` does, both among all
+    /// rules and among those outside the private domains section
+    pub(crate) fn prevailing_rules<'a, 'b>(
+        &'a self,
+        len: usize,
+        label: impl Fn(usize) -> &'b str,
+    ) -> (Option<Rule<'a>>, Option<Rule<'a>>) {
+        let (mut all, mut icann) = (None, None);
+        visit(&self.root, 0, false, len, &label, &mut |rule| {
+            prevail(rule, &mut all);
+            if !rule.0.private {
+                prevail(rule, &mut icann);
+            }
+        });
+        let implicit = (len > 0).then_some((&IMPLICIT_RULE, 1, true));
+        (all.or(implicit), icann.or(implicit))
+    }
+}
+
+/// Replaces `prevailing` with `rule` if `rule` prevails over it
+fn prevail<'a>(rule: Rule<'a>, prevailing: &mut Option<Rule<'a>>) {
+    let (leaf, depth, _) = rule;
+    let prevails = match prevailing {
+        None => true,
+        Some((other, other_len, _)) => match (leaf.is_exception_rule, other.is_exception_rule) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => depth > *other_len,
+        },
+    };
+    if prevails {
+        *prevailing = Some(rule);
+    }
+}
+
+/// Visits every node matching the next label exactly or by wildcard,
+/// passing each rule found to `found`
+fn visit<'a, 'b>(
+    node: &'a ListNode,
+    depth: usize,
+    wildcard: bool,
+    len: usize,
+    label: &impl Fn(usize) -> &'b str,
+    found: &mut impl FnMut(Rule<'a>),
+) {
+    if let Some(leaf) = &node.leaf {
+        found((leaf, depth, wildcard));
+    }
+    if depth == len {
+        return;
+    }
+
+    let next = label(depth);
+    if let Some(child) = node.children.get(next) {
+        visit(child, depth + 1, wildcard, len, label, found);
+    }
+    if next != "*" {
+        if let Some(child) = node.children.get("*") {
+            visit(child, depth + 1, true, len, label, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use crate::{conformance, Algorithm, List, ParseOptions};
+
+    fn parts(list: &List, name: &str, algorithm: Algorithm) -> (Option<String>, Option<String>) {
+        let options = ParseOptions::new().algorithm(algorithm);
+        let name = list.parse_dns_name_with(name, &options).unwrap();
+        (
+            name.suffix().map(str::to_owned),
+            name.root().map(str::to_owned),
+        )
+    }
+
+    fn some(suffix: &str, root: &str) -> (Option<String>, Option<String>) {
+        (Some(suffix.to_owned()), Some(root.to_owned()))
+    }
+
+    #[test]
+    fn spec_differences() {
+        // the exact labels `b` and `a` lead nowhere, but `*.b` matches
+        let list = "*.b,d.a.b".parse::<List>().unwrap();
+        assert_eq!(parts(&list, "c.a.b", Algorithm::Legacy), (None, None));
+        assert_eq!(
+            parts(&list, "c.a.b", Algorithm::PslSpec),
+            some("a.b", "c.a.b")
+        );
+
+        // an exception prevails over a longer rule
+        let list = "b,*.b,!a.b,x.a.b".parse::<List>().unwrap();
+        assert_eq!(
+            parts(&list, "y.x.a.b", Algorithm::Legacy),
+            some("x.a.b", "y.x.a.b")
+        );
+        assert_eq!(
+            parts(&list, "y.x.a.b", Algorithm::PslSpec),
+            some("b", "a.b")
+        );
+
+        // the implicit `*` rule applies to every list
+        let list = List::empty();
+        assert_eq!(parts(&list, "www.example", Algorithm::Legacy), (None, None));
+        assert_eq!(
+            parts(&list, "www.example", Algorithm::PslSpec),
+            some("example", "www.example")
+        );
+        let options = ParseOptions::new().algorithm(Algorithm::PslSpec);
+        assert!(list
+            .parse_dns_name_with(".", &options)
+            .unwrap()
+            .suffix()
+            .is_none());
+    }
+
+    #[test]
+    fn conformance() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let options = ParseOptions::new().algorithm(Algorithm::PslSpec);
+        let tests = BufReader::new(File::open("tests.txt").unwrap());
+        let report = conformance::run_with(&list, tests, &options).unwrap();
+        assert!(report.is_ok(), "{report}");
+    }
+}
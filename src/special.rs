@@ -0,0 +1,126 @@
+//! IANA special-use domain names (RFC 6761)
+
+use std::fmt;
+
+use crate::{escape, DnsName};
+
+/// A special-use domain name from the IANA registry
+///
+/// These are not governed by the Public Suffix List and resolvers treat them
+/// specially; see the [IANA registry].
+///
+/// [IANA registry]: https://www.iana.org/assignments/special-use-domain-names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SpecialUse {
+    /// `.local`, multicast DNS (RFC 6762)
+    Local,
+    /// `.onion`, Tor hidden services (RFC 7686)
+    Onion,
+    /// `.test`, for testing (RFC 6761)
+    Test,
+    /// `.invalid`, guaranteed not to exist (RFC 6761)
+    Invalid,
+    /// `.localhost`, the loopback interface (RFC 6761)
+    Localhost,
+    /// `.example`, `example.com`, `example.net` and `example.org`, for
+    /// documentation (RFC 6761)
+    Example,
+    /// `home.arpa`, residential home networks (RFC 8375)
+    HomeArpa,
+    /// `.alt`, non-DNS resolution contexts (RFC 9476)
+    Alt,
+}
+
+impl SpecialUse {
+    /// The special-use domain itself, without a trailing dot
+    pub fn domain(&self) -> &'static str {
+        match self {
+            SpecialUse::Local => "local",
+            SpecialUse::Onion => "onion",
+            SpecialUse::Test => "test",
+            SpecialUse::Invalid => "invalid",
+            SpecialUse::Localhost => "localhost",
+            SpecialUse::Example => "example",
+            SpecialUse::HomeArpa => "home.arpa",
+            SpecialUse::Alt => "alt",
+        }
+    }
+}
+
+impl fmt::Display for SpecialUse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.domain())
+    }
+}
+
+impl DnsName {
+    /// Gets the special-use domain the Name is in, if any
+    ///
+    /// ```rust
+    /// # use dns_name::{List, SpecialUse};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("printer.local.").unwrap();
+    /// assert_eq!(name.special_use(), Some(SpecialUse::Local));
+    /// let name = list.parse_dns_name("router.home.arpa").unwrap();
+    /// assert_eq!(name.special_use(), Some(SpecialUse::HomeArpa));
+    /// let name = list.parse_dns_name("www.example.co.uk").unwrap();
+    /// assert_eq!(name.special_use(), None);
+    /// ```
+    pub fn special_use(&self) -> Option<SpecialUse> {
+        let domain = escape::trim_dot(self.name());
+        let mut labels = escape::label_ranges(domain)
+            .into_iter()
+            .rev()
+            .map(|range| &domain[range]);
+        let tld = labels.next()?;
+        let sld = labels.next();
+
+        let special = match (tld, sld) {
+            ("local", _) => SpecialUse::Local,
+            ("onion", _) => SpecialUse::Onion,
+            ("test", _) => SpecialUse::Test,
+            ("invalid", _) => SpecialUse::Invalid,
+            ("localhost", _) => SpecialUse::Localhost,
+            ("example", _) => SpecialUse::Example,
+            ("com" | "net" | "org", Some("example")) => SpecialUse::Example,
+            ("arpa", Some("home")) => SpecialUse::HomeArpa,
+            ("alt", _) => SpecialUse::Alt,
+            _ => return None,
+        };
+        Some(special)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::List;
+
+    #[test]
+    fn special_use() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let special = |name: &str| list.parse_dns_name(name).unwrap().special_use();
+
+        assert_eq!(special("LOCALHOST"), Some(SpecialUse::Localhost));
+        assert_eq!(special("a.b.localhost."), Some(SpecialUse::Localhost));
+        assert_eq!(
+            special("duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion"),
+            Some(SpecialUse::Onion)
+        );
+        assert_eq!(special("x.test"), Some(SpecialUse::Test));
+        assert_eq!(special("nope.invalid"), Some(SpecialUse::Invalid));
+        assert_eq!(special("www.example.org"), Some(SpecialUse::Example));
+        assert_eq!(special("example"), Some(SpecialUse::Example));
+        assert_eq!(special("home.arpa"), Some(SpecialUse::HomeArpa));
+        assert_eq!(special("x.alt"), Some(SpecialUse::Alt));
+
+        assert_eq!(special("arpa"), None);
+        assert_eq!(special("1.in-addr.arpa"), None);
+        assert_eq!(special("notexample.com"), None);
+        assert_eq!(special("local.com"), None);
+        assert_eq!(special("a\\.local"), None);
+        assert_eq!(special("."), None);
+        assert_eq!(SpecialUse::HomeArpa.to_string(), "home.arpa");
+    }
+}
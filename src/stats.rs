@@ -0,0 +1,143 @@
+//! Size and memory statistics of a list
+
+use std::{collections::HashSet, mem, sync::Arc};
+
+use crate::{List, ListNode, PREVAILING_STAR_RULE};
+
+/// Control bytes hashbrown keeps past the end of a table's buckets
+const GROUP_WIDTH: usize = 16;
+
+/// Counts describing a `List`, from `List::stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ListStats {
+    /// rules, the default `*` rule included
+    pub rules: usize,
+    /// exception rules, like `!city.kobe.jp`
+    pub exception_rules: usize,
+    /// wildcard rules, like `*.kobe.jp`, the default `*` rule included
+    pub wildcard_rules: usize,
+    /// nodes of the trie, one per distinct label path, the root included
+    pub nodes: usize,
+    /// rules that repeated an earlier one
+    pub duplicates: usize,
+    /// estimate of the heap bytes the list holds; see `List::memory_usage`
+    pub memory_bytes: usize,
+}
+
+impl List {
+    /// Gets counts of the rules and nodes of the list, and its memory use
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = "com,*.kobe.jp,!city.kobe.jp".parse::<List>().unwrap();
+    /// let stats = list.stats();
+    /// assert_eq!(stats.rules, 4);
+    /// assert_eq!((stats.exception_rules, stats.wildcard_rules), (1, 2));
+    /// assert_eq!(stats.nodes, 7);
+    /// ```
+    pub fn stats(&self) -> ListStats {
+        let mut stats = ListStats {
+            duplicates: self.duplicates.len(),
+            memory_bytes: self.memory_usage(),
+            ..ListStats::default()
+        };
+        count(&self.root, false, &mut stats);
+        stats
+    }
+
+    /// Estimates the heap bytes held by the list
+    ///
+    /// This counts the trie's hash tables at their capacity, each label
+    /// once however many nodes share it, the recorded duplicate rules and
+    /// the source description. Allocator overhead is not included, so the
+    /// true figure is somewhat higher. Labels shared with other lists
+    /// through a `ListRegistry` are counted in full by each of them.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let small = "com".parse::<List>().unwrap();
+    /// let full = List::from_path("suffix-list.txt").unwrap();
+    /// assert!(full.memory_usage() > 100 * small.memory_usage());
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        let mut labels = HashSet::new();
+        let mut bytes = node_bytes(&self.root, &mut labels);
+        bytes += self.duplicates.capacity() * mem::size_of::<String>();
+        bytes += self.duplicates.iter().map(String::capacity).sum::<usize>();
+        bytes += self.source.as_ref().map_or(0, |source| source.len());
+        bytes
+    }
+}
+
+/// Adds the rules and nodes at and below `node`, reached through a `*`
+/// label if `wildcard`
+fn count(node: &ListNode, wildcard: bool, stats: &mut ListStats) {
+    stats.nodes += 1;
+    if let Some(leaf) = &node.leaf {
+        stats.rules += 1;
+        stats.exception_rules += usize::from(leaf.is_exception_rule);
+        stats.wildcard_rules += usize::from(wildcard);
+    }
+    for (label, child) in &node.children {
+        count(child, &**label == PREVAILING_STAR_RULE, stats);
+    }
+}
+
+/// Heap bytes of the tables at and below `node`, and of the labels and
+/// metadata not yet in `labels`
+fn node_bytes(node: &ListNode, labels: &mut HashSet<*const u8>) -> usize {
+    let children = &node.children;
+    let mut bytes = 0;
+    if let Some(metadata) = node.leaf.as_ref().and_then(|leaf| leaf.metadata.as_ref()) {
+        if labels.insert(metadata.as_ptr()) {
+            bytes += 2 * mem::size_of::<usize>() + metadata.len();
+        }
+    }
+    if children.capacity() > 0 {
+        // hashbrown allocates a power of two buckets, each with a control
+        // byte, for its capacity at a load factor of 7/8
+        let buckets = (children.capacity() * 8 / 7).next_power_of_two();
+        let entry = mem::size_of::<(Arc<str>, ListNode)>();
+        bytes += buckets * (entry + 1) + GROUP_WIDTH;
+    }
+    for (label, child) in children {
+        if labels.insert(label.as_ptr()) {
+            // the two reference counts and the text
+            bytes += 2 * mem::size_of::<usize>() + label.len();
+        }
+        bytes += node_bytes(child, labels);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let stats = list.stats();
+        let mut rules = Vec::new();
+        crate::query::collect_rules(&list.root, &mut Vec::new(), &mut rules);
+        assert_eq!(stats.rules, rules.len());
+        assert!(stats.exception_rules > 0 && stats.wildcard_rules > 1);
+        assert!(stats.nodes > stats.rules);
+        assert_eq!(stats.memory_bytes, list.memory_usage());
+
+        let empty = List::empty().stats();
+        assert_eq!(
+            empty,
+            ListStats {
+                nodes: 1,
+                ..ListStats::default()
+            }
+        );
+
+        let list = "com,com,a.com".parse::<List>().unwrap();
+        let stats = list.stats();
+        assert_eq!((stats.rules, stats.duplicates, stats.nodes), (3, 1, 4));
+        // `com` is stored once, but the tables and the duplicate still count
+        assert!(list.memory_usage() > 2 * "com".len());
+    }
+}
@@ -0,0 +1,254 @@
+//! Keeping fetched lists on disk between runs
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{List, ListSource};
+
+/// Versions are named `list-<sequence>.dat`, the sequence zero-padded so
+/// names sort in the order they were saved
+const PREFIX: &str = "list-";
+const EXTENSION: &str = ".dat";
+
+/// A directory holding the last lists fetched, newest first
+///
+/// Each list is written to a temporary file that is then renamed into
+/// place, and the directory is synced after, so a crash or a full disk
+/// never leaves a partial list behind for the next start. The newest list is the current one; a few earlier ones
+/// are kept so that a bad update can be rolled back.
+///
+/// ```rust
+/// # use dns_name::ListStore;
+/// # let dir = std::env::temp_dir().join(format!("dns_name-doc-{}", std::process::id()));
+/// let store = ListStore::new(&dir).unwrap().retain(1);
+/// store.save(b"com").unwrap();
+/// store.save(b"com,uk").unwrap();
+/// store.save(b"com,uk,co.uk").unwrap();
+/// assert_eq!(store.versions().unwrap().len(), 2);
+///
+/// let list = store.rollback().unwrap();
+/// assert_eq!(list.suffix_of("www.example.co.uk").unwrap().as_deref(), Some("uk"));
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ListStore {
+    dir: PathBuf,
+    retain: usize,
+}
+
+impl ListStore {
+    /// Uses `dir` for the lists, creating it if needed
+    ///
+    /// Two lists before the current one are kept by default.
+    pub fn new<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let dir = dir.as_ref().to_owned();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, retain: 2 })
+    }
+
+    /// Sets how many lists before the current one are kept
+    pub fn retain(mut self, previous: usize) -> Self {
+        self.retain = previous;
+        self
+    }
+
+    /// The directory holding the lists
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The lists kept, newest first
+    pub fn versions(&self) -> io::Result<Vec<PathBuf>> {
+        Ok(self
+            .sequences()?
+            .into_iter()
+            .rev()
+            .map(|sequence| self.path(sequence))
+            .collect())
+    }
+
+    /// Saves `bytes` as the current list, then removes lists beyond those
+    /// retained
+    ///
+    /// The bytes are stored as they are, so they can be checked against the
+    /// same checksum or signature they were fetched with. Returns the path of
+    /// the new list.
+    pub fn save(&self, bytes: &[u8]) -> io::Result<PathBuf> {
+        let sequences = self.sequences()?;
+        let sequence = sequences.last().map_or(0, |last| last + 1);
+        let path = self.path(sequence);
+
+        let temp = self.dir.join(format!(".{PREFIX}{sequence:020}.tmp"));
+        let written = fs::File::create(&temp).and_then(|mut file| {
+            file.write_all(bytes)?;
+            file.sync_all()
+        });
+        if let Err(err) = written.and_then(|()| fs::rename(&temp, &path)) {
+            let _ = fs::remove_file(&temp);
+            return Err(err);
+        }
+
+        self.sync_dir()?;
+
+        let keep = self.retain + 1;
+        for old in sequences.iter().rev().skip(keep - 1) {
+            fs::remove_file(self.path(*old))?;
+        }
+        self.sync_dir()?;
+        Ok(path)
+    }
+
+    /// Makes renames and removals in the directory durable
+    #[cfg(unix)]
+    fn sync_dir(&self) -> io::Result<()> {
+        fs::File::open(&self.dir)?.sync_all()
+    }
+
+    /// Directories cannot be opened to sync them outside Unix
+    #[cfg(not(unix))]
+    fn sync_dir(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Returns true if `bytes` are those of the current list
+    pub(crate) fn is_current(&self, bytes: &[u8]) -> io::Result<bool> {
+        match self.versions()?.first() {
+            Some(path) => Ok(fs::read(path)? == bytes),
+            None => Ok(false),
+        }
+    }
+
+    /// A source reading the current list
+    ///
+    /// Fails with `io::ErrorKind::NotFound` if no list was saved. Use it as
+    /// a fallback of the source lists are fetched from, so the last list
+    /// fetched is used when the fetch fails at startup.
+    pub fn source(&self) -> io::Result<ListSource> {
+        let path = self
+            .versions()?
+            .into_iter()
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no list saved"))?;
+        Ok(ListSource::path(path))
+    }
+
+    /// Loads the current list
+    pub fn load(&self) -> io::Result<List> {
+        self.source()?.load()
+    }
+
+    /// Removes the current list and loads the one saved before it
+    ///
+    /// Fails with `io::ErrorKind::NotFound`, removing nothing, if there is no
+    /// earlier list. If the earlier list fails to load, the current one is
+    /// kept.
+    pub fn rollback(&self) -> io::Result<List> {
+        let versions = self.versions()?;
+        let [current, previous, ..] = &versions[..] else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no earlier list to roll back to",
+            ));
+        };
+        let list = ListSource::path(previous).load()?;
+        fs::remove_file(current)?;
+        self.sync_dir()?;
+        Ok(list)
+    }
+
+    /// The sequence numbers of the lists kept, oldest first
+    fn sequences(&self) -> io::Result<Vec<u64>> {
+        let mut sequences = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let name = entry?.file_name();
+            let sequence = name
+                .to_str()
+                .and_then(|name| name.strip_prefix(PREFIX)?.strip_suffix(EXTENSION))
+                .and_then(|sequence| sequence.parse::<u64>().ok());
+            sequences.extend(sequence);
+        }
+        sequences.sort_unstable();
+        Ok(sequences)
+    }
+
+    fn path(&self, sequence: u64) -> PathBuf {
+        self.dir.join(format!("{PREFIX}{sequence:020}{EXTENSION}"))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A directory removed at the end of the test
+    pub(crate) struct TempDir(pub(crate) PathBuf);
+
+    impl TempDir {
+        pub(crate) fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("dns_name-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn suffix(list: &List) -> Option<String> {
+        list.suffix_of("www.example.co.uk").unwrap()
+    }
+
+    #[test]
+    fn retention() {
+        let dir = TempDir::new("retention");
+        let store = ListStore::new(&dir.0).unwrap();
+        assert_eq!(store.load().unwrap_err().kind(), io::ErrorKind::NotFound);
+
+        for rules in ["com", "com,uk", "com,uk,co.uk", "com,co.uk"] {
+            store.save(rules.as_bytes()).unwrap();
+        }
+        let versions = store.versions().unwrap();
+        assert_eq!(versions.len(), 3);
+        assert!(versions[0].ends_with("list-00000000000000000003.dat"));
+        assert_eq!(fs::read(&versions[2]).unwrap(), b"com,uk");
+        // nothing but the lists is left behind
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 3);
+
+        // other files in the directory are left alone
+        fs::write(dir.0.join("notes.txt"), "").unwrap();
+        let store = store.retain(0);
+        store.save(b"com,uk").unwrap();
+        assert_eq!(store.versions().unwrap().len(), 1);
+        assert!(dir.0.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn rollback() {
+        let dir = TempDir::new("rollback");
+        let store = ListStore::new(&dir.0).unwrap();
+        store.save(b"com,uk").unwrap();
+        assert_eq!(
+            store.rollback().unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+
+        store.save(b"com,uk,co.uk").unwrap();
+        assert_eq!(suffix(&store.load().unwrap()).as_deref(), Some("co.uk"));
+        let list = store.rollback().unwrap();
+        assert_eq!(suffix(&list).as_deref(), Some("uk"));
+        assert_eq!(suffix(&store.load().unwrap()).as_deref(), Some("uk"));
+        assert_eq!(store.versions().unwrap().len(), 1);
+
+        // a broken earlier list is not rolled back to
+        fs::write(store.path(0), "").unwrap();
+        store.save(b"com").unwrap();
+        assert!(store.rollback().is_err());
+        assert_eq!(store.versions().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,123 @@
+//! Building a list from an async stream of bytes
+
+use std::{future, io, pin::pin};
+
+use bytes::Bytes;
+use futures_core::Stream;
+
+use crate::{List, RuleReader};
+
+impl List {
+    /// Builds a list from a stream of bytes, such as the body of a download
+    ///
+    /// Rules are added as each line arrives rather than once the stream
+    /// ends, so most of the list is built by the time the last chunk comes
+    /// in. Lines may be split across chunks. The stream is read the same way
+    /// as `from_buf_reader` reads its input.
+    pub async fn from_stream(stream: impl Stream<Item = Bytes>) -> io::Result<List> {
+        let mut stream = pin!(stream);
+        let mut list = List::empty();
+        let mut rules = RuleReader::new(true, false);
+        let mut pending = Vec::new();
+
+        while let Some(chunk) = future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+            pending.extend_from_slice(&chunk);
+            // everything up to the last newline is whole lines
+            let Some(end) = pending.iter().rposition(|&b| b == b'\n') else {
+                continue;
+            };
+            for line in utf8(&pending[..end])?.lines() {
+                rules.read_line(&mut list, line)?;
+            }
+            pending.drain(..=end);
+        }
+        rules.read_line(&mut list, utf8(&pending)?)?;
+
+        list.finish(true)?;
+        Ok(list)
+    }
+}
+
+/// Checks that a part of the list is UTF-8, as `BufRead::read_line` does
+fn utf8(bytes: &[u8]) -> io::Result<&str> {
+    std::str::from_utf8(bytes)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "list is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use super::*;
+
+    /// Yields its chunks, pending once before each
+    struct Chunks {
+        chunks: VecDeque<Bytes>,
+        ready: bool,
+    }
+
+    impl Stream for Chunks {
+        type Item = Bytes;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Bytes>> {
+            self.ready = !self.ready;
+            if !self.ready {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(self.chunks.pop_front())
+        }
+    }
+
+    fn chunks(chunks: &[&'static [u8]]) -> Chunks {
+        Chunks {
+            chunks: chunks
+                .iter()
+                .map(|chunk| Bytes::from_static(chunk))
+                .collect(),
+            ready: false,
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn from_stream() {
+        let list = block_on(List::from_stream(chunks(&[
+            b"// comment\nco",
+            b"m\nco.u",
+            b"k\n\n// ===BEGIN PRIVATE DOMAINS===\r\n",
+            b"blogspot.com",
+        ])))
+        .unwrap();
+        let root = |name| {
+            let name = list.parse_dns_name(name).unwrap();
+            name.root().map(str::to_owned)
+        };
+        assert_eq!(root("www.example.co.uk").as_deref(), Some("example.co.uk"));
+        assert_eq!(root("a.b.blogspot.com").as_deref(), Some("b.blogspot.com"));
+        assert_eq!(root("a.example.com").as_deref(), Some("example.com"));
+        assert!(list.parse_dual("a.b.blogspot.com").unwrap().differs());
+
+        // a character split between chunks is put back together
+        let list = block_on(List::from_stream(chunks(&[b"b\xc3", b"\xbccher.de\n"]))).unwrap();
+        let name = list.parse_dns_name("www.bücher.de").unwrap();
+        assert_eq!(name.suffix(), Some("bücher.de"));
+
+        assert!(block_on(List::from_stream(chunks(&[]))).is_err());
+        assert!(block_on(List::from_stream(chunks(&[b"\xff\n"]))).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+//! Splitting the registrable label into words
+//!
+//! Brand monitoring looks for protected words inside registrable labels, like
+//! `paypal` in `paypal-login2024.com`. Splitting on hyphens and digits finds
+//! most of them; a dictionary finds the rest, as in `securepaypal`.
+
+use crate::DnsName;
+
+/// Splits a label into runs of digits and runs of everything else, dropping
+/// hyphens
+fn split_runs(label: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut digits = false;
+    for (i, c) in label.char_indices() {
+        let is_digit = c.is_ascii_digit();
+        if c == '-' || is_digit != digits {
+            if start < i {
+                tokens.push(&label[start..i]);
+            }
+            start = if c == '-' { i + 1 } else { i };
+        }
+        digits = is_digit;
+    }
+    if start < label.len() {
+        tokens.push(&label[start..]);
+    }
+    tokens
+}
+
+/// Splits a token into the fewest dictionary words covering it, or returns
+/// it whole if it cannot be covered
+fn segment<'a>(token: &'a str, is_word: &impl Fn(&str) -> bool, out: &mut Vec<&'a str>) {
+    // best[i] is the fewest words covering token[..i] and where the last starts
+    let mut best: Vec<Option<(usize, usize)>> = vec![None; token.len() + 1];
+    best[0] = Some((0, 0));
+    for end in (1..=token.len()).filter(|&end| token.is_char_boundary(end)) {
+        best[end] = (0..end)
+            .filter(|&start| token.is_char_boundary(start))
+            .filter_map(|start| {
+                let (count, _) = best[start]?;
+                is_word(&token[start..end]).then_some((count + 1, start))
+            })
+            .min();
+    }
+
+    if best[token.len()].is_none() {
+        out.push(token);
+        return;
+    }
+    let first = out.len();
+    let mut end = token.len();
+    while let Some((_, start)) = best[end].filter(|_| end > 0) {
+        out.push(&token[start..end]);
+        end = start;
+    }
+    out[first..].reverse();
+}
+
+impl DnsName {
+    /// Splits the registrable label into tokens
+    ///
+    /// Hyphens separate tokens and are dropped; runs of digits are tokens of
+    /// their own. Returns an empty list if there is no registrable label.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = list.parse_dns_name("www.paypal-login2024.co.uk").unwrap();
+    /// assert_eq!(name.registrable_tokens(), ["paypal", "login", "2024"]);
+    /// ```
+    pub fn registrable_tokens(&self) -> Vec<&str> {
+        self.registrable_label().map(split_runs).unwrap_or_default()
+    }
+
+    /// Splits the registrable label into tokens, then each token into
+    /// dictionary words
+    ///
+    /// A token is split into the fewest words for which `is_word` holds, and
+    /// kept whole if no split covers all of it.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let words = ["secure", "pay", "paypal", "pal", "login"];
+    /// let is_word = |token: &str| words.contains(&token);
+    ///
+    /// let name = list.parse_dns_name("securepaypal-login1.com").unwrap();
+    /// assert_eq!(
+    ///     name.registrable_tokens_with(is_word),
+    ///     ["secure", "paypal", "login", "1"]
+    /// );
+    /// ```
+    pub fn registrable_tokens_with(&self, is_word: impl Fn(&str) -> bool) -> Vec<&str> {
+        let mut out = Vec::new();
+        for token in self.registrable_tokens() {
+            segment(token, &is_word, &mut out);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::List;
+
+    #[test]
+    fn runs() {
+        assert_eq!(split_runs("abc"), ["abc"]);
+        assert_eq!(split_runs("a--b"), ["a", "b"]);
+        assert_eq!(split_runs("-a1b-"), ["a", "1", "b"]);
+        assert_eq!(split_runs("123"), ["123"]);
+        assert_eq!(split_runs("xn--bcher-kva"), ["xn", "bcher", "kva"]);
+        assert!(split_runs("--").is_empty());
+    }
+
+    #[test]
+    fn dictionary() {
+        let words = ["a", "ab", "abc", "cd", "bücher"];
+        let is_word = |token: &str| words.contains(&token);
+        let split = |token: &'static str| {
+            let mut out = Vec::new();
+            segment(token, &is_word, &mut out);
+            out
+        };
+
+        assert_eq!(split("abcd"), ["ab", "cd"]);
+        assert_eq!(split("abc"), ["abc"]);
+        assert_eq!(split("abx"), ["abx"]);
+        assert_eq!(split("abücher"), ["a", "bücher"]);
+
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let name = list.parse_dns_name("co.uk").unwrap();
+        assert!(name.registrable_tokens_with(is_word).is_empty());
+    }
+}
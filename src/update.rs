@@ -0,0 +1,339 @@
+//! Reloading a list from its source and reporting what changed
+
+use std::{
+    fmt, io,
+    sync::{Mutex, PoisonError, RwLock},
+};
+
+use crate::{List, ListDiff, ListSource, ListStore, SharedList};
+
+type Callback = Box<dyn Fn(&ListDiff) + Send + Sync>;
+
+/// A list kept up to date from a `ListSource`
+///
+/// `reload` fetches the source again and swaps in the new list, so it can be
+/// called on a timer from any thread. Callbacks registered with `on_change`
+/// receive the rules that changed, so caches
+/// keyed by root domain can drop only the affected entries. Parses already
+/// running keep the list they started with.
+///
+/// ```rust
+/// # use std::sync::{Arc, Mutex};
+/// # use dns_name::{ListSource, ListUpdater};
+/// let rules = Arc::new(Mutex::new("com,uk"));
+/// let source = {
+///     let rules = rules.clone();
+///     ListSource::fetch("rules", move || Ok(rules.lock().unwrap().as_bytes().to_vec()))
+/// };
+/// let updater = ListUpdater::new(source).unwrap();
+/// updater.on_change(|diff| println!("added {:?}, removed {:?}", diff.added(), diff.removed()));
+///
+/// *rules.lock().unwrap() = "com,uk,co.uk";
+/// let diff = updater.reload().unwrap();
+/// assert_eq!(diff.added(), ["co.uk"]);
+/// let name = updater.list().parse_dns_name("example.co.uk").unwrap();
+/// assert_eq!(name.suffix(), Some("co.uk"));
+/// ```
+pub struct ListUpdater {
+    source: ListSource,
+    store: Option<ListStore>,
+    current: RwLock<SharedList>,
+    callbacks: Mutex<Vec<Callback>>,
+}
+
+impl ListUpdater {
+    /// Loads the list from `source`
+    pub fn new(source: ListSource) -> io::Result<Self> {
+        let list = source.load()?;
+        Ok(Self::with_list(source, list))
+    }
+
+    /// Starts from `list` instead of loading `source` first
+    ///
+    /// Use this to start from a bundled snapshot, or from a list with a
+    /// `ParseObserver` set; the observer is carried over to each new list.
+    pub fn with_list(source: ListSource, list: List) -> Self {
+        Self {
+            source,
+            store: None,
+            current: RwLock::new(SharedList::new(list)),
+            callbacks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Loads the list from `source`, and saves it and each new list to
+    /// `store`
+    ///
+    /// The list loaded is saved unless it is already the current one in the
+    /// store, so that the first update can be rolled back. To start from
+    /// the last list saved when the source cannot be reached, add the store
+    /// as a fallback of the source.
+    ///
+    /// ```rust,no_run
+    /// # use dns_name::{ListSource, ListStore, ListUpdater};
+    /// let store = ListStore::new("/var/cache/dns_name").unwrap();
+    /// let mut source = ListSource::path("/etc/suffix-list.txt");
+    /// if let Ok(saved) = store.source() {
+    ///     source = source.or(saved);
+    /// }
+    /// let updater = ListUpdater::with_store(source, store).unwrap();
+    /// ```
+    pub fn with_store(source: ListSource, store: ListStore) -> io::Result<Self> {
+        let list = {
+            let (list, bytes) = source.load_bytes()?;
+            if !store.is_current(&bytes)? {
+                store.save(&bytes)?;
+            }
+            list
+        };
+        let mut updater = Self::with_list(source, list);
+        updater.store = Some(store);
+        Ok(updater)
+    }
+
+    /// The current list
+    pub fn list(&self) -> SharedList {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Calls `callback` with the changes each time a reload changes rules
+    ///
+    /// Callbacks run on the thread calling `reload`, after the new list is
+    /// in place.
+    pub fn on_change(&self, callback: impl Fn(&ListDiff) + Send + Sync + 'static) {
+        self.lock_callbacks().push(Box::new(callback));
+    }
+
+    /// Loads the list again and swaps it in
+    ///
+    /// Returns the rules that changed, which are empty if none did; the new
+    /// list is swapped in even then, as it may still differ in which rules
+    /// are private or where it came from. On error the current list is kept.
+    /// An observer set on the current list is carried over to the new one.
+    ///
+    /// With a store, the new list is saved first unless it is already the
+    /// current one there, and kept out if saving fails.
+    pub fn reload(&self) -> io::Result<ListDiff> {
+        let (list, bytes) = self.source.load_bytes()?;
+        self.replace(list, |_| {
+            if let Some(store) = &self.store {
+                if !store.is_current(&bytes)? {
+                    store.save(&bytes)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Swaps in the list saved before the current one in the store
+    ///
+    /// The current list is removed from the store, so a later reload
+    /// fetching the same rules saves them again. Fails with
+    /// `io::ErrorKind::NotFound` without a store or an earlier list.
+    pub fn rollback(&self) -> io::Result<ListDiff> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no list store"))?;
+        self.replace(store.rollback()?, |_| Ok(()))
+    }
+
+    /// Swaps in `list` once `commit` accepts the changes, then runs the
+    /// callbacks if any rule changed
+    fn replace(
+        &self,
+        mut list: List,
+        commit: impl FnOnce(&ListDiff) -> io::Result<()>,
+    ) -> io::Result<ListDiff> {
+        // holding the callbacks keeps other reloads from swapping lists
+        // until this one is done, so that they report in order; readers only
+        // wait for the swap itself
+        let callbacks = self.lock_callbacks();
+
+        let current = self.list();
+        let diff = current.diff(&list);
+        commit(&diff)?;
+        list.observer.clone_from(&current.observer);
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = SharedList::new(list);
+        if diff.is_empty() {
+            return Ok(diff);
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            source = %self.source,
+            added = diff.added().len(),
+            removed = diff.removed().len(),
+            "list changed"
+        );
+        for callback in callbacks.iter() {
+            callback(&diff);
+        }
+        Ok(diff)
+    }
+
+    fn lock_callbacks(&self) -> std::sync::MutexGuard<'_, Vec<Callback>> {
+        self.callbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for ListUpdater {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ListUpdater")
+            .field("source", &self.source)
+            .field("store", &self.store)
+            .field("callbacks", &self.lock_callbacks().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{store::tests::TempDir, DnsName, ParseObserver};
+
+    struct Count(Mutex<usize>);
+
+    impl ParseObserver for Count {
+        fn parsed(&self, _name: &DnsName, _listed_suffix: bool) {
+            *self.0.lock().unwrap() += 1;
+        }
+    }
+
+    #[test]
+    fn reload() {
+        let rules = Arc::new(Mutex::new(Ok("com,uk")));
+        let source = {
+            let rules = rules.clone();
+            ListSource::fetch("rules", move || match *rules.lock().unwrap() {
+                Ok(rules) => Ok(rules.as_bytes().to_vec()),
+                Err(()) => Err(io::Error::new(io::ErrorKind::TimedOut, "timed out")),
+            })
+        };
+        let updater = ListUpdater::new(source).unwrap();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        {
+            let changes = changes.clone();
+            updater.on_change(move |diff| changes.lock().unwrap().push(diff.clone()));
+        }
+
+        let before = updater.list();
+        assert!(updater.reload().unwrap().is_empty());
+        assert!(changes.lock().unwrap().is_empty());
+
+        *rules.lock().unwrap() = Ok("com,co.uk");
+        updater.reload().unwrap();
+        let expected = ListDiff::new().add_rule("co.uk").remove_rule("uk");
+        assert_eq!(*changes.lock().unwrap(), [expected]);
+        assert!(!updater.list().ptr_eq(&before));
+        assert_eq!(before.suffix_of("a.co.uk").unwrap().as_deref(), Some("uk"));
+        assert_eq!(
+            updater.list().suffix_of("a.co.uk").unwrap().as_deref(),
+            Some("co.uk")
+        );
+
+        *rules.lock().unwrap() = Err(());
+        let current = updater.list();
+        assert!(updater.reload().is_err());
+        assert!(updater.list().ptr_eq(&current));
+        assert_eq!(changes.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn store() {
+        let dir = TempDir::new("updater");
+        let store = ListStore::new(&dir.0).unwrap();
+
+        let rules = Arc::new(Mutex::new("com,uk"));
+        let source = {
+            let rules = rules.clone();
+            ListSource::fetch("rules", move || {
+                Ok(rules.lock().unwrap().as_bytes().to_vec())
+            })
+        };
+        let updater = ListUpdater::with_store(source.clone(), store.clone()).unwrap();
+        assert_eq!(store.versions().unwrap().len(), 1);
+        assert!(updater.rollback().is_err());
+        assert!(updater.reload().unwrap().is_empty());
+        assert_eq!(store.versions().unwrap().len(), 1);
+
+        // the first update can be rolled back
+        *rules.lock().unwrap() = "com,uk,co.uk";
+        updater.reload().unwrap();
+        let diff = updater.rollback().unwrap();
+        assert_eq!(diff.removed(), ["co.uk"]);
+        assert_eq!(store.versions().unwrap().len(), 1);
+
+        updater.reload().unwrap();
+        *rules.lock().unwrap() = "com,co.uk";
+        updater.reload().unwrap();
+        assert_eq!(store.versions().unwrap().len(), 3);
+        let diff = updater.rollback().unwrap();
+        assert_eq!(diff.added(), ["uk"]);
+        assert_eq!(
+            updater.list().suffix_of("a.uk").unwrap().as_deref(),
+            Some("uk")
+        );
+        updater.rollback().unwrap();
+        assert!(updater.rollback().is_err());
+
+        // starting again from the same rules saves nothing new
+        *rules.lock().unwrap() = "com,uk";
+        ListUpdater::with_store(source, store.clone()).unwrap();
+        assert_eq!(store.versions().unwrap().len(), 1);
+        assert!(
+            ListUpdater::with_list(ListSource::bytes(b"com".as_slice()), List::empty())
+                .rollback()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn reload_private() {
+        let rules = Arc::new(Mutex::new("com\nblogspot.com\n"));
+        let source = {
+            let rules = rules.clone();
+            ListSource::fetch("rules", move || {
+                Ok(rules.lock().unwrap().as_bytes().to_vec())
+            })
+        };
+        let updater = ListUpdater::new(source).unwrap();
+        let root = |updater: &ListUpdater| {
+            let dual = updater.list().parse_dual("a.b.blogspot.com").unwrap();
+            dual.icann.root().map(str::to_owned)
+        };
+        assert_eq!(root(&updater).as_deref(), Some("b.blogspot.com"));
+
+        // only the private flag changes, so no rule is reported
+        *rules.lock().unwrap() = "com\n// ===BEGIN PRIVATE DOMAINS===\nblogspot.com\n";
+        assert!(updater.reload().unwrap().is_empty());
+        assert_eq!(root(&updater).as_deref(), Some("blogspot.com"));
+    }
+
+    #[test]
+    fn keeps_observer() {
+        let rules = Arc::new(Mutex::new("com"));
+        let source = {
+            let rules = rules.clone();
+            ListSource::fetch("rules", move || {
+                Ok(rules.lock().unwrap().as_bytes().to_vec())
+            })
+        };
+        let count = Arc::new(Count(Mutex::new(0)));
+        let mut list = source.load().unwrap();
+        list.set_observer(count.clone());
+        let updater = ListUpdater::with_list(source, list);
+
+        *rules.lock().unwrap() = "com,net";
+        updater.reload().unwrap();
+        updater.list().parse_dns_name("example.net").unwrap();
+        assert_eq!(*count.0.lock().unwrap(), 1);
+    }
+}
@@ -0,0 +1,59 @@
+//! Parsing the host out of a `url::Url`
+
+use std::io;
+
+use url::{Host, Url};
+
+use crate::{DnsName, List};
+
+impl List {
+    /// Parses the host of a URL using the list
+    ///
+    /// URLs without a host (`mailto:`, `data:`) and URLs whose host is an IP
+    /// address are rejected with `io::ErrorKind::InvalidInput`. Requires the
+    /// `url` feature.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let url = url::Url::parse("https://www.Example.co.uk:8443/path").unwrap();
+    /// let domain = list.parse_url_host(&url).unwrap();
+    /// assert_eq!(domain.name(), "www.example.co.uk");
+    /// assert_eq!(domain.root(), Some("example.co.uk"));
+    /// ```
+    pub fn parse_url_host(&self, url: &Url) -> io::Result<DnsName> {
+        match url.host() {
+            Some(Host::Domain(domain)) => self.parse_dns_name(domain),
+            Some(Host::Ipv4(_)) | Some(Host::Ipv6(_)) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "url host is an ip address",
+            )),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "url has no host",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_url_host() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let parse = |url: &str| list.parse_url_host(&Url::parse(url).unwrap());
+
+        let domain = parse("http://user:pw@a.b.kobe.jp./x?y#z").unwrap();
+        assert_eq!(domain.root(), Some("a.b.kobe.jp."));
+
+        // the url crate converts IDNs to punycode
+        let domain = parse("https://bücher.de").unwrap();
+        assert_eq!(domain.name(), "xn--bcher-kva.de");
+
+        assert!(parse("http://127.0.0.1/").is_err());
+        assert!(parse("http://[::1]:80/").is_err());
+        assert!(parse("mailto:someone@example.com").is_err());
+    }
+}
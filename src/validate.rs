@@ -0,0 +1,531 @@
+//! Label validation driven by `ParseOptions`
+
+use std::ops::Range;
+
+use crate::{
+    escape, LabelError, ParseError, ParseOptions, ValidationProfile, MAX_LABEL_LEN, MAX_WIRE_LEN,
+};
+
+/// Runs every check enabled by `options` over the labels of a name
+///
+/// With `wildcard`, the first label is a `*` allowed by
+/// `ParseOptions::wildcard_names`: it counts as a label, but its content is
+/// not checked against the profile.
+pub(crate) fn check(
+    domain: &str,
+    labels: &[Range<usize>],
+    wildcard: bool,
+    options: &ParseOptions,
+) -> Result<(), ParseError> {
+    if options.reject_dotless && labels.len() == 1 {
+        return Err(ParseError::DotlessDomain);
+    }
+    if options.reject_numeric_tld
+        && labels
+            .last()
+            .is_some_and(|tld| is_numeric(&domain[tld.clone()]))
+    {
+        return Err(ParseError::NumericTld);
+    }
+
+    let check_content =
+        options.profile != ValidationProfile::Dns || options.underscore_labels.is_some();
+    #[cfg(feature = "idna")]
+    let check_content = check_content || options.validate_punycode;
+    if !options.strict_lengths && !check_content {
+        return Ok(());
+    }
+
+    // the root label's length octet
+    let mut wire_len = 1;
+    for (index, range) in labels.iter().enumerate() {
+        // names are canonicalized before this, so escapes are well formed
+        let label = escape::unescape(&domain[range.clone()]).unwrap_or_default();
+
+        if options.strict_lengths && label.len() > MAX_LABEL_LEN {
+            return Err(ParseError::LabelTooLong {
+                index,
+                len: label.len(),
+            });
+        }
+        wire_len += label.len() + 1;
+
+        if check_content && !(wildcard && index == 0) {
+            check_label(&label, options).map_err(|reason| ParseError::InvalidLabel {
+                index,
+                offset: range.start + offset_in(&domain[range.clone()], reason, options),
+                reason,
+            })?;
+        }
+    }
+
+    if options.strict_lengths && wire_len > MAX_WIRE_LEN {
+        return Err(ParseError::NameTooLong { len: wire_len });
+    }
+    Ok(())
+}
+
+/// Returns true if a label is all ASCII digits
+pub(crate) fn is_numeric(label: &str) -> bool {
+    !label.is_empty() && label.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Finds where in a label `check_label` found `reason`
+///
+/// Escapes make octets and characters differ, so labels with escapes are
+/// blamed as a whole, at their start.
+fn offset_in(label: &str, reason: LabelError, options: &ParseOptions) -> usize {
+    if label.contains('\\') {
+        return 0;
+    }
+    // the part of a service label `check_label` looked at
+    let skip = usize::from(options.underscore_labels == Some(true) && label.starts_with('_'));
+    match reason {
+        LabelError::InvalidCharacter(c) => label[skip..].find(c).map_or(0, |i| skip + i),
+        LabelError::LeadingHyphen => skip,
+        LabelError::TrailingHyphen => label.len() - 1,
+        LabelError::ReservedHyphens => skip + 2,
+        _ => 0,
+    }
+}
+
+/// Checks a label's octets against the validation profile and underscore
+/// policy
+pub(crate) fn check_label(label: &[u8], options: &ParseOptions) -> Result<(), LabelError> {
+    #[cfg(feature = "idna")]
+    if options.validate_punycode && !is_valid_punycode(&String::from_utf8_lossy(label)) {
+        return Err(LabelError::InvalidPunycode);
+    }
+
+    let mut label = label;
+    if let Some(service) = label.strip_prefix(b"_") {
+        match options.underscore_labels {
+            Some(false) => return Err(LabelError::UnderscoreLabel),
+            // the rest of a service label follows the profile
+            Some(true) => label = service,
+            None => {}
+        }
+    }
+
+    let profile = options.profile;
+    if profile == ValidationProfile::Dns || label.is_empty() {
+        return Ok(());
+    }
+
+    let text = String::from_utf8_lossy(label);
+    if let Some(c) = text
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && *c != '-')
+    {
+        return Err(LabelError::InvalidCharacter(c));
+    }
+    if label.starts_with(b"-") {
+        return Err(LabelError::LeadingHyphen);
+    }
+    if label.ends_with(b"-") {
+        return Err(LabelError::TrailingHyphen);
+    }
+
+    #[cfg(feature = "idna")]
+    if profile == ValidationProfile::StrictIdna {
+        check_idna(&text)?;
+    }
+
+    Ok(())
+}
+
+/// Checks the IDNA2008 rules for an LDH label: hyphens in the third and fourth
+/// positions are reserved for `xn--` A-labels, which must be valid punycode
+#[cfg(feature = "idna")]
+fn check_idna(label: &str) -> Result<(), LabelError> {
+    let label = label.to_ascii_lowercase();
+    if label.get(2..4) != Some("--") {
+        return Ok(());
+    }
+    if !label.starts_with("xn--") {
+        return Err(LabelError::ReservedHyphens);
+    }
+    if !is_valid_punycode(&label) {
+        return Err(LabelError::InvalidPunycode);
+    }
+    Ok(())
+}
+
+/// Returns false if a label starts with `xn--` but is not a valid IDNA
+/// A-label: the punycode does not decode, or does not encode back to the
+/// same label
+#[cfg(feature = "idna")]
+pub(crate) fn is_valid_punycode(label: &str) -> bool {
+    let label = label.to_ascii_lowercase();
+    if !label.starts_with("xn--") {
+        return true;
+    }
+    let (unicode, result) = idna::domain_to_unicode(&label);
+    let round_trip = idna::domain_to_ascii_strict(&unicode).ok();
+    result.is_ok() && round_trip.as_deref() == Some(label.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InvalidUtf8, List};
+
+    fn profile(profile: ValidationProfile) -> ParseOptions {
+        ParseOptions::new().profile(profile)
+    }
+
+    #[test]
+    fn hostname() {
+        let check =
+            |label: &str| check_label(label.as_bytes(), &profile(ValidationProfile::Hostname));
+
+        assert_eq!(check("www"), Ok(()));
+        assert_eq!(check("a-1"), Ok(()));
+        assert_eq!(check("1a"), Ok(()));
+        assert_eq!(check("a--b"), Ok(()));
+        assert_eq!(check("exa/mple"), Err(LabelError::InvalidCharacter('/')));
+        assert_eq!(check("_dmarc"), Err(LabelError::InvalidCharacter('_')));
+        assert_eq!(check("bücher"), Err(LabelError::InvalidCharacter('ü')));
+        assert_eq!(check("-a"), Err(LabelError::LeadingHyphen));
+        assert_eq!(check("a-"), Err(LabelError::TrailingHyphen));
+        assert_eq!(check_label(b"exa/mple", &ParseOptions::new()), Ok(()));
+    }
+
+    #[test]
+    fn parse_with_profile() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let options = ParseOptions::new().profile(ValidationProfile::Hostname);
+
+        assert!(list
+            .parse_dns_name_with("www.example.com.", &options)
+            .is_ok());
+        let err = list
+            .parse_dns_name_with("www.exa\\/mple.com", &options)
+            .unwrap_err();
+        assert_eq!(
+            ParseError::from_io(&err),
+            Some(&ParseError::InvalidLabel {
+                index: 1,
+                offset: 7,
+                reason: LabelError::InvalidCharacter('/')
+            })
+        );
+    }
+
+    #[test]
+    fn error_positions() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let hostname = profile(ValidationProfile::Hostname);
+        let service = hostname.clone().underscore_labels(true);
+        let error = |name: &str, options: &ParseOptions| match ParseError::from_io(
+            &list.parse_dns_name_with(name, options).unwrap_err(),
+        ) {
+            Some(ParseError::InvalidLabel {
+                index,
+                offset,
+                reason,
+            }) => (*index, *offset, *reason),
+            other => panic!("{name}: {other:?}"),
+        };
+
+        let empty = LabelError::Empty;
+        assert_eq!(error("a..b", &ParseOptions::new()), (1, 2, empty));
+        assert_eq!(error(".a", &ParseOptions::new()), (0, 0, empty));
+        assert_eq!(error("a.b c.d", &ParseOptions::new()).1, 3);
+        assert_eq!(error("www.exa!mple.com", &hostname).1, 7);
+        assert_eq!(error("www.EXA!mple.com", &hostname).1, 7);
+        assert_eq!(
+            error("a.-b.com", &hostname),
+            (1, 2, LabelError::LeadingHyphen)
+        );
+        assert_eq!(
+            error("a.b-.com", &hostname),
+            (1, 3, LabelError::TrailingHyphen)
+        );
+        assert_eq!(error("x._a_b.com", &service).1, 4);
+        assert_eq!(error("x._-b.com", &service).1, 3);
+        assert_eq!(error("x._-b.com", &hostname).1, 2);
+        // a label with escapes is blamed at its start
+        assert_eq!(error("www.a\\000b.com", &hostname).1, 4);
+        // positions are in the name as rewritten, not in the input
+        let leading_dot = ParseOptions::new().leading_dot(true);
+        assert_eq!(error(".a..b", &leading_dot), (1, 2, empty));
+        let percent = ParseOptions::new().percent_decoding(true);
+        assert_eq!(error("%61%61..b", &percent), (1, 3, empty));
+
+        let err = list.parse_dns_name("a..b").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "label 1 is invalid at byte 2: empty");
+    }
+
+    #[test]
+    fn underscores() {
+        let check = |label: &str, options: &ParseOptions| check_label(label.as_bytes(), options);
+        let dns = ParseOptions::new();
+        let hostname = profile(ValidationProfile::Hostname);
+
+        assert_eq!(check("_dmarc", &dns), Ok(()));
+        assert_eq!(
+            check("_dmarc", &hostname),
+            Err(LabelError::InvalidCharacter('_'))
+        );
+
+        let allow = hostname.clone().underscore_labels(true);
+        assert_eq!(check("_443", &allow), Ok(()));
+        assert_eq!(check("_", &allow), Ok(()));
+        assert_eq!(
+            check("_a_b", &allow),
+            Err(LabelError::InvalidCharacter('_'))
+        );
+        assert_eq!(check("_-a", &allow), Err(LabelError::LeadingHyphen));
+
+        let reject = dns.underscore_labels(false);
+        assert_eq!(check("_tcp", &reject), Err(LabelError::UnderscoreLabel));
+        assert_eq!(check("a_b", &reject), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "idna")]
+    fn strict_idna() {
+        let check =
+            |label: &str| check_label(label.as_bytes(), &profile(ValidationProfile::StrictIdna));
+
+        assert_eq!(check("xn--bcher-kva"), Ok(()));
+        assert_eq!(check("xn--85x722f"), Ok(()));
+        assert_eq!(check("a--b"), Ok(()));
+        assert_eq!(check("ab--c"), Err(LabelError::ReservedHyphens));
+        assert_eq!(check("xn--"), Err(LabelError::TrailingHyphen));
+        assert_eq!(check("xn--a"), Err(LabelError::InvalidPunycode));
+        assert_eq!(check("xn--abc-"), Err(LabelError::TrailingHyphen));
+        // decodes to plain ASCII, which is not a valid A-label
+        assert_eq!(check("xn--www-8aa"), Err(LabelError::InvalidPunycode));
+        assert_eq!(check("xn--zzzzzzzzz"), Err(LabelError::InvalidPunycode));
+    }
+
+    #[test]
+    fn dotless() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let reject = ParseOptions::new().dotless_names(false);
+
+        for name in ["com", "localhost", "localhost.", "a\\.b"] {
+            let err = list.parse_dns_name_with(name, &reject).unwrap_err();
+            assert_eq!(ParseError::from_io(&err), Some(&ParseError::DotlessDomain));
+            assert!(list.parse_dns_name(name).unwrap().is_dotless());
+        }
+        assert!(list.parse_dns_name_with(".", &reject).is_ok());
+        assert!(list.parse_dns_name_with("co.uk", &reject).is_ok());
+        assert!(!list.parse_dns_name(".").unwrap().is_dotless());
+        assert!(!list.parse_dns_name("example.com").unwrap().is_dotless());
+    }
+
+    #[test]
+    fn numeric_tld() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let reject = ParseOptions::new().numeric_tlds(false);
+
+        for name in ["127.38.53.247", "a.b.1.", "0", "a.\\049"] {
+            let err = list.parse_dns_name_with(name, &reject).unwrap_err();
+            assert_eq!(ParseError::from_io(&err), Some(&ParseError::NumericTld));
+            assert!(list.parse_dns_name(name).unwrap().has_numeric_tld());
+        }
+        for name in ["127.com", "a.b1", "1.a"] {
+            assert!(list.parse_dns_name_with(name, &reject).is_ok());
+        }
+        assert!(!list.parse_dns_name(".").unwrap().has_numeric_tld());
+    }
+
+    #[test]
+    fn wildcard_names() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let options = ParseOptions::new()
+            .profile(ValidationProfile::Hostname)
+            .wildcard_names(true);
+
+        let name = list
+            .parse_dns_name_with("*.Example.com.", &options)
+            .unwrap();
+        assert_eq!(name.name(), "*.example.com.");
+        assert_eq!(name.root(), Some("example.com."));
+        assert!(name.is_wildcard());
+
+        let name = list.parse_dns_name_with("*.com", &options).unwrap();
+        assert_eq!((name.suffix(), name.root()), (Some("com"), None));
+
+        // the wildcard counts as a label for every check but the profile's
+        let error = |name: &str, options: &ParseOptions| {
+            ParseError::from_io(&list.parse_dns_name_with(name, options).unwrap_err()).cloned()
+        };
+        let dotless = options.clone().dotless_names(false);
+        assert!(list.parse_dns_name_with("*.com", &dotless).is_ok());
+        assert_eq!(error("com", &dotless), Some(ParseError::DotlessDomain));
+        assert_eq!(
+            error("*.exa!mple.com", &options),
+            Some(ParseError::InvalidLabel {
+                index: 1,
+                offset: 5,
+                reason: LabelError::InvalidCharacter('!'),
+            })
+        );
+        let strict = options.clone().strict_lengths(true);
+        assert_eq!(
+            error(&format!("*.{}.com", "a".repeat(64)), &strict),
+            Some(ParseError::LabelTooLong { index: 1, len: 64 })
+        );
+
+        // the wildcard no longer matches `*.kobe.jp` literally
+        let name = list.parse_dns_name_with("*.kobe.jp", &options).unwrap();
+        assert_eq!(name.root(), Some("kobe.jp"));
+        let name = list.parse_dns_name("*.kobe.jp").unwrap();
+        assert_eq!(name.suffix(), Some("*.kobe.jp"));
+
+        for name in [
+            "www.*.example.com",
+            "*.*.example.com",
+            "*",
+            "a*.example.com",
+        ] {
+            assert!(list.parse_dns_name_with(name, &options).is_err());
+        }
+        assert!(!list.parse_dns_name("a*.example.com").unwrap().is_wildcard());
+    }
+
+    #[test]
+    fn empty() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let root = ParseOptions::new().empty_as_root(true);
+
+        let err = list.parse_dns_name("").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(ParseError::from_io(&err), Some(&ParseError::EmptyName));
+
+        let name = list.parse_dns_name_with("", &root).unwrap();
+        assert!(name.is_root());
+        assert_eq!(name.name(), ".");
+        assert!(name.is_fqdn());
+        assert!(list.parse_dns_name_with(" ", &root).is_err());
+        assert!(!list.parse_dns_name("a.").unwrap().is_root());
+    }
+
+    #[test]
+    fn leading_dot() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let options = ParseOptions::new().leading_dot(true);
+
+        let name = list
+            .parse_dns_name_with(".WWW.example.co.uk.", &options)
+            .unwrap();
+        assert_eq!(name.name(), "www.example.co.uk.");
+        assert_eq!(name.root(), Some("example.co.uk."));
+        assert_eq!(name.original(), ".WWW.example.co.uk.");
+        assert!(name.had_leading_dot());
+        assert!(!list
+            .parse_dns_name_with("example.com", &options)
+            .unwrap()
+            .had_leading_dot());
+
+        let root = list.parse_dns_name_with(".", &options).unwrap();
+        assert!(root.is_root() && !root.had_leading_dot());
+        for name in ["..", "..example.com", ". example.com"] {
+            assert!(list.parse_dns_name_with(name, &options).is_err());
+        }
+    }
+
+    #[test]
+    fn limits() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let error = |name: &str, options: &ParseOptions| {
+            ParseError::from_io(&list.parse_dns_name_with(name, options).unwrap_err()).cloned()
+        };
+
+        let options = ParseOptions::new().max_labels(3);
+        assert!(list.parse_dns_name_with("a.b.c.", &options).is_ok());
+        assert_eq!(
+            error("a.b.c.d", &options),
+            Some(ParseError::TooManyLabels { count: 4, max: 3 })
+        );
+        let wildcard = options.clone().wildcard_names(true);
+        assert!(list.parse_dns_name_with("*.b.c.d", &wildcard).is_err());
+
+        let options = ParseOptions::new().max_depth(1).wildcard_names(true);
+        assert!(list
+            .parse_dns_name_with("www.example.com", &options)
+            .is_ok());
+        assert!(list.parse_dns_name_with("*.example.com", &options).is_ok());
+        assert_eq!(
+            error("*.www.example.com", &options),
+            Some(ParseError::TooDeep { depth: 2, max: 1 })
+        );
+        // the depth is counted from the root domain the list finds
+        assert!(list.parse_dns_name_with("a.b.c.kobe.jp", &options).is_ok());
+        assert!(list.parse_dns_name_with("co.uk", &options).is_ok());
+        let none = ParseOptions::new().max_depth(0);
+        assert!(list.parse_dns_name_with("example.co.uk", &none).is_ok());
+        assert!(list
+            .parse_dns_name_with("www.example.co.uk", &none)
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "idna")]
+    fn punycode() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let options = ParseOptions::new().validate_punycode(true);
+
+        for name in [
+            "www.xn--bcher-kva.de",
+            "_dmarc.xn--e1afmkfd.xn--p1ai",
+            "a_b.com",
+        ] {
+            assert!(list.parse_dns_name_with(name, &options).is_ok(), "{name}");
+        }
+        let err = list
+            .parse_dns_name_with("www.XN--a.com", &options)
+            .unwrap_err();
+        assert_eq!(
+            ParseError::from_io(&err),
+            Some(&ParseError::InvalidLabel {
+                index: 1,
+                offset: 4,
+                reason: LabelError::InvalidPunycode
+            })
+        );
+        // not in the form the encoder produces
+        assert!(list
+            .parse_dns_name_with("xn--bcher-kva-.de", &options)
+            .is_err());
+        assert!(list.parse_dns_name("www.xn--a.com").is_ok());
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+        let parse = |bytes: &[u8], handling| {
+            let options = ParseOptions::new().invalid_utf8(handling);
+            list.parse_dns_name_bytes_with(bytes, &options)
+        };
+
+        let bytes = b"a\xc3(.\xffB.example.com";
+        let err = parse(bytes, InvalidUtf8::Reject).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        let name = parse(bytes, InvalidUtf8::Escape).unwrap();
+        assert_eq!(name.name(), "a\\195(.\\255b.example.com");
+        assert_eq!(name.root(), Some("example.com"));
+        assert_eq!(name.to_wire().unwrap()[..4], *b"\x03a\xc3(");
+
+        let name = parse(bytes, InvalidUtf8::Lossy).unwrap();
+        assert_eq!(name.name(), "a\u{fffd}(.\u{fffd}b.example.com");
+
+        // valid input is the same whatever the handling
+        for handling in [InvalidUtf8::Reject, InvalidUtf8::Escape, InvalidUtf8::Lossy] {
+            let name = parse("Bücher.de".as_bytes(), handling).unwrap();
+            assert_eq!(name.name(), "bücher.de");
+        }
+        let hostname = ParseOptions::new()
+            .invalid_utf8(InvalidUtf8::Escape)
+            .profile(ValidationProfile::Hostname);
+        assert!(list
+            .parse_dns_name_bytes_with(b"\xff.com", &hostname)
+            .is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! DNS wire format (RFC 1035 section 3.1)
+
+use std::io;
+
+use crate::{escape, DnsName, List, MAX_LABEL_LEN, MAX_WIRE_LEN};
+
+/// The top two bits of a length octet mark a compression pointer
+const POINTER_MASK: u8 = 0xc0;
+
+fn invalid(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl DnsName {
+    /// Encodes the Name as length-prefixed labels, without compression
+    ///
+    /// Wire format names are always absolute, so a name without a trailing
+    /// dot is encoded as if it had one. Escapes are decoded into the octets
+    /// they stand for. Names with a label over 63 bytes or longer than 255
+    /// bytes encoded are rejected.
+    ///
+    /// ```rust
+    /// # use dns_name::List;
+    /// let list = List::empty();
+    /// let name = list.parse_dns_name("www.example.com").unwrap();
+    /// assert_eq!(name.to_wire().unwrap(), b"\x03www\x07example\x03com\x00");
+    /// ```
+    pub fn to_wire(&self) -> io::Result<Vec<u8>> {
+        let name = escape::trim_dot(self.name());
+        let mut wire = Vec::with_capacity(name.len() + 2);
+        if !name.is_empty() {
+            for range in escape::label_ranges(name) {
+                let label = escape::unescape(&name[range])?;
+                if label.len() > MAX_LABEL_LEN {
+                    return Err(invalid("label too long"));
+                }
+                wire.push(label.len() as u8);
+                wire.extend_from_slice(&label);
+            }
+        }
+        wire.push(0);
+
+        if wire.len() > MAX_WIRE_LEN {
+            return Err(invalid("name too long"));
+        }
+        Ok(wire)
+    }
+
+    /// Decodes a wire format name that uses no compression pointers
+    ///
+    /// `bytes` must hold exactly one name. The result is fully qualified, with
+    /// dots, non-printable octets and invalid UTF-8 inside labels escaped.
+    ///
+    /// ```rust
+    /// # use dns_name::{DnsName, List};
+    /// let list = List::from_path("suffix-list.txt").unwrap();
+    /// let name = DnsName::from_wire(b"\x03www\x07example\x02co\x02uk\x00", &list).unwrap();
+    /// assert_eq!(name.name(), "www.example.co.uk.");
+    /// assert_eq!(name.root(), Some("example.co.uk."));
+    /// ```
+    pub fn from_wire(bytes: &[u8], list: &List) -> io::Result<DnsName> {
+        let (name, end) = decode(bytes, 0, false)?;
+        if end != bytes.len() {
+            return Err(invalid("trailing data after name"));
+        }
+        list.parse_dns_name(&name)
+    }
+
+    /// Decodes a wire format name starting at `offset` in a whole DNS message,
+    /// following compression pointers
+    ///
+    /// Returns the name and the offset just past it in the message, where the
+    /// next field begins.
+    ///
+    /// ```rust
+    /// # use dns_name::{DnsName, List};
+    /// let list = List::empty();
+    /// // "example.com" at 0, then "www" + pointer to 0
+    /// let message = b"\x07example\x03com\x00\x03www\xc0\x00";
+    /// let (name, end) = DnsName::from_wire_message(message, 13, &list).unwrap();
+    /// assert_eq!(name.name(), "www.example.com.");
+    /// assert_eq!(end, message.len());
+    /// ```
+    pub fn from_wire_message(
+        message: &[u8],
+        offset: usize,
+        list: &List,
+    ) -> io::Result<(DnsName, usize)> {
+        let (name, end) = decode(message, offset, true)?;
+        Ok((list.parse_dns_name(&name)?, end))
+    }
+}
+
+/// Decodes the name at `offset` into presentation format, returning it along
+/// with the offset after the name's last octet at its original position
+fn decode(bytes: &[u8], offset: usize, follow_pointers: bool) -> io::Result<(String, usize)> {
+    let mut name = String::new();
+    let mut pos = offset;
+    let mut end = None;
+    let mut wire_len = 0;
+    // pointers must go strictly backwards, which rules out loops
+    let mut limit = offset;
+
+    loop {
+        let len = *bytes.get(pos).ok_or_else(|| invalid("truncated name"))?;
+
+        if len & POINTER_MASK == POINTER_MASK {
+            if !follow_pointers {
+                return Err(invalid("unexpected compression pointer"));
+            }
+            let low = *bytes
+                .get(pos + 1)
+                .ok_or_else(|| invalid("truncated name"))?;
+            let target = usize::from(len & !POINTER_MASK) << 8 | usize::from(low);
+            if target >= limit {
+                return Err(invalid("compression pointer does not point backwards"));
+            }
+            end.get_or_insert(pos + 2);
+            limit = target;
+            pos = target;
+            continue;
+        }
+        if len & POINTER_MASK != 0 {
+            return Err(invalid("unsupported label type"));
+        }
+
+        let len = usize::from(len);
+        wire_len += len + 1;
+        if wire_len > MAX_WIRE_LEN {
+            return Err(invalid("name too long"));
+        }
+        if len == 0 {
+            end.get_or_insert(pos + 1);
+            break;
+        }
+
+        let label = bytes
+            .get(pos + 1..pos + 1 + len)
+            .ok_or_else(|| invalid("truncated name"))?;
+        escape::escape(label, false, &mut name);
+        name.push('.');
+        pos += 1 + len;
+    }
+
+    if name.is_empty() {
+        name.push('.');
+    }
+    Ok((name, end.unwrap_or(pos + 1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseOptions;
+
+    #[test]
+    fn round_trip() {
+        let list = List::from_path("suffix-list.txt").unwrap();
+
+        for input in [
+            ".",
+            "com.",
+            "a.b.c.kobe.jp.",
+            "xn--85x722f.xn--fiqs8s.",
+            "a\\.b\\000\\255.example.",
+        ] {
+            let name = list.parse_dns_name(input).unwrap();
+            let wire = name.to_wire().unwrap();
+            assert_eq!(DnsName::from_wire(&wire, &list).unwrap(), name);
+        }
+
+        assert_eq!(list.parse_dns_name(".").unwrap().to_wire().unwrap(), [0]);
+        let name = DnsName::from_wire(b"\x03c.m\x02\xff \x00", &list).unwrap();
+        assert_eq!(name.name(), "c\\.m.\\255\\032.");
+        assert_eq!(name.to_wire().unwrap(), b"\x03c.m\x02\xff \x00");
+        let long = list.parse_dns_name(&"a".repeat(64)).unwrap();
+        assert!(long.to_wire().is_err());
+        let options = ParseOptions::new().max_labels(128);
+        let long = list
+            .parse_dns_name_with(&["a"; 128].join("."), &options)
+            .unwrap();
+        assert!(long.to_wire().is_err());
+    }
+
+    #[test]
+    fn from_wire_errors() {
+        let list = List::empty();
+        let from_wire = |bytes: &[u8]| DnsName::from_wire(bytes, &list);
+
+        assert!(from_wire(b"").is_err());
+        assert!(from_wire(b"\x03com").is_err());
+        assert!(from_wire(b"\x03co").is_err());
+        assert!(from_wire(b"\x03com\x00\x00").is_err());
+        assert!(from_wire(b"\x03com\xc0\x00").is_err());
+        assert!(from_wire(b"\x40com\x00").is_err());
+    }
+
+    #[test]
+    fn compression() {
+        let list = List::empty();
+        let message = b"\x07example\x03com\x00\x03www\xc0\x00\x04mail\xc0\x0d";
+
+        let (name, end) = DnsName::from_wire_message(message, 0, &list).unwrap();
+        assert_eq!((name.name(), end), ("example.com.", 13));
+        let (name, end) = DnsName::from_wire_message(message, 19, &list).unwrap();
+        assert_eq!((name.name(), end), ("mail.www.example.com.", 26));
+
+        // pointer loops and forward pointers
+        assert!(DnsName::from_wire_message(b"\xc0\x00", 0, &list).is_err());
+        assert!(DnsName::from_wire_message(b"\x01a\xc0\x00", 0, &list).is_err());
+        assert!(DnsName::from_wire_message(b"\xc0\x02\x00", 0, &list).is_err());
+        assert!(DnsName::from_wire_message(b"\x00", 1, &list).is_err());
+    }
+}